@@ -1,15 +1,18 @@
 use genetic_algorithm::crossover::neural_crossover::node_swap::HiddenLayerSwap;
+use genetic_algorithm::dataset::idx::read_idx_dataset;
+use genetic_algorithm::dataset::scaling::{FeatureScaler, Scaling};
+use genetic_algorithm::genome::early_stopping::EarlyStopping;
 use genetic_algorithm::genome::fitness_function::FitnessFunction;
 use genetic_algorithm::genome::population::{Individual, Population, ProblemType};
 use genetic_algorithm::mutation::neural_mutation::add_node::AddNode;
 use genetic_algorithm::mutation::neural_mutation::delete_node::DeleteNode;
+use genetic_algorithm::neural_network::classification_report::ClassificationReport;
+use genetic_algorithm::neural_network::dataset_fitness::{DatasetFitness, Decoding, Metric};
 use genetic_algorithm::neural_network::neural_network::NeuralNetwork;
 use genetic_algorithm::selection::genome_selection::TournamentSelection;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use std::borrow::Borrow;
-use std::fs::File;
-use std::io::Read;
 
 const DEFAULT_SEED: &[u8; 32] = &[
     1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4,
@@ -19,6 +22,14 @@ const DEFAULT_SEED: &[u8; 32] = &[
 //     4,
 // ];
 
+const IMAGES_FILE_PATH: &str =
+    "examples/genetic_algorithm_gui/neural_network/images_net/train-images.idx3-ubyte";
+const LABELS_FILE_PATH: &str =
+    "examples/genetic_algorithm_gui/neural_network/images_net/train-labels.idx1-ubyte";
+const NUMBER_OF_TRAINING_IMAGES: usize = 60;
+const NUMBER_OF_VALIDATION_IMAGES: usize = 20;
+const VALIDATION_INTERVAL: u64 = 5;
+const VALIDATION_PATIENCE: u64 = 50;
 const MAX_NUM: u32 = 9;
 const NUMBER_OF_NETWORKS_TO_MAKE: u32 = 100;
 const K_VALUE: u32 = 100;
@@ -41,28 +52,6 @@ impl NetFitness {
             actual_list,
         }
     }
-    fn get_accuracy(&mut self, net: &NeuralNetwork) -> f64 {
-        let mut correct = 0.0;
-        let mut output = vec![];
-        for (index, image_list) in self.image_list.iter().enumerate() {
-            output = net.feedforward(image_list.as_slice());
-            let actual_val = self.actual_list[index];
-
-            for number in 1..MAX_NUM + 1 {
-                if actual_val == number as u8 {
-                    if output[number as usize - 1] > 0.5 {
-                        correct += 1.0
-                    }
-                }
-            }
-        }
-        println!(
-            "Correct: {:?}, Image_List: {:?}",
-            correct,
-            self.image_list.len()
-        );
-        correct / (self.image_list.len() as f64)
-    }
 }
 
 impl FitnessFunction for NetFitness {
@@ -76,15 +65,15 @@ impl FitnessFunction for NetFitness {
             output = net.feedforward(image_list.as_slice());
             let actual_val = self.actual_list[index];
 
-            for number in 1..MAX_NUM + 1 {
+            for number in 0..MAX_NUM + 1 {
                 if actual_val == number as u8 {
-                    if output[number as usize - 1] > 0.5 {
+                    if output[number as usize] > 0.5 {
                         fitness += 100000.0;
                     } else {
                         fitness -= 10000.0;
                     }
                 } else {
-                    if output[number as usize - 1] > 0.5 {
+                    if output[number as usize] > 0.5 {
                         fitness -= 10000.0;
                     }
                 }
@@ -95,9 +84,35 @@ impl FitnessFunction for NetFitness {
 }
 
 fn main() {
-    let input_label_list = read_input_labels_from_file();
-    let input_images_list = read_input_images_from_file();
     let mut rng: StdRng = SeedableRng::from_seed(*DEFAULT_SEED);
+    let mut dataset = read_idx_dataset(IMAGES_FILE_PATH, LABELS_FILE_PATH)
+        .expect("Failed to read the MNIST training files");
+    dataset.shuffle(&mut rng);
+    let mut training_set = dataset.subset(&(0..NUMBER_OF_TRAINING_IMAGES).collect::<Vec<usize>>());
+    let mut validation_set = dataset.subset(
+        &(NUMBER_OF_TRAINING_IMAGES..NUMBER_OF_TRAINING_IMAGES + NUMBER_OF_VALIDATION_IMAGES)
+            .collect::<Vec<usize>>(),
+    );
+    let scaler = FeatureScaler::fit(Scaling::MinMax, &training_set);
+    scaler.transform(&mut training_set);
+    scaler.transform(&mut validation_set);
+    let mut early_stopping = EarlyStopping::new(
+        Box::new(DatasetFitness::new(
+            validation_set.clone(),
+            Metric::Accuracy,
+            *DEFAULT_SEED,
+        )),
+        Metric::Accuracy.problem_type(),
+        VALIDATION_INTERVAL,
+        VALIDATION_PATIENCE,
+    );
+
+    let input_images_list = training_set.inputs().clone();
+    let input_label_list = training_set
+        .labels()
+        .iter()
+        .map(|&label| label as u8)
+        .collect();
 
     let mut network_list = Vec::new();
     let mut net_fitness_function = NetFitness::new(input_images_list, input_label_list);
@@ -110,7 +125,7 @@ fn main() {
             new_seed[i] = rng.gen::<u8>();
         }
         // new_seed
-        let net = NeuralNetwork::new(28 * 28, &[], 9, new_seed);
+        let net = NeuralNetwork::new(28 * 28, &[], MAX_NUM + 1, new_seed);
         let fitness = net_fitness_function.calculate_fitness(&net);
         network_list.push(Individual::new(net, fitness));
     }
@@ -149,9 +164,43 @@ fn main() {
             "Generation: {:?}, Best fitness: {:?} Accuracy: {:?}",
             gen,
             indv,
-            net_fitness_function.get_accuracy(indv.retrieve_individual())
+            ClassificationReport::new(
+                indv.retrieve_individual(),
+                &training_set,
+                Decoding::Argmax,
+                &[],
+                1
+            )
+            .accuracy
         );
+        let stop = early_stopping.update(&mut net_pop);
+        if gen % VALIDATION_INTERVAL == 0 {
+            println!(
+                "Validation accuracy: {:?}",
+                early_stopping.last_validation_fitness()
+            );
+        }
+        if stop {
+            break;
+        }
     }
+    let best = early_stopping.best().unwrap();
+    println!(
+        "Stopped after {} generations, best validation accuracy {} in generation {}",
+        gen,
+        best.fitness(),
+        early_stopping.best_generation()
+    );
+    println!(
+        "{}",
+        ClassificationReport::new(
+            best.retrieve_individual(),
+            &validation_set,
+            Decoding::Argmax,
+            &[1, 3],
+            10,
+        )
+    );
 }
 
 // pub fn generate_seed(mut rng: StdRng) -> [u8; 32] {
@@ -163,52 +212,3 @@ fn main() {
 //     }
 //     new_seed
 // }
-
-fn read_input_labels_from_file() -> Vec<u8> {
-    let mut input_label_list = Vec::new();
-    let mut labels = File::open(
-        "examples/genetic_algorithm_gui/neural_network/images_net/train-labels.idx1-ubyte",
-    )
-    .unwrap();
-    let mut buf = [0u8; 4];
-    labels.read(&mut buf).unwrap();
-    let magic_number = u32::from_be_bytes(buf);
-    labels.read(&mut buf).unwrap();
-    let number_of_items = u32::from_be_bytes(buf);
-    let mut buf = [0u8; 1];
-    for _ in 0..number_of_items {
-        labels.read(&mut buf).unwrap();
-        input_label_list.push(buf[0]);
-    }
-    input_label_list
-}
-
-fn read_input_images_from_file() -> Vec<Vec<f64>> {
-    let mut input_images_list: Vec<Vec<f64>> = Vec::new();
-    let mut images = File::open(
-        "examples/genetic_algorithm_gui/neural_network/images_net/train-images.idx3-ubyte",
-    )
-    .unwrap();
-    let mut buf = [0u8; 4];
-    images.read(&mut buf).unwrap();
-    let magic_number = u32::from_be_bytes(buf);
-    images.read(&mut buf).unwrap();
-    let number_of_images = u32::from_be_bytes(buf);
-    images.read(&mut buf).unwrap();
-    let number_of_rows = u32::from_be_bytes(buf);
-    images.read(&mut buf).unwrap();
-    let number_of_columns = u32::from_be_bytes(buf);
-
-    let mut pixel_buf = [0u8; 1];
-    for i in 0..number_of_images / 1000 {
-        let mut input_images_array: Vec<f64> = Vec::new();
-        for j in 0..number_of_columns {
-            for k in 0..number_of_rows {
-                images.read(&mut pixel_buf).unwrap();
-                input_images_array.push(f64::from(pixel_buf[0]));
-            }
-        }
-        input_images_list.push(input_images_array);
-    }
-    input_images_list
-}
@@ -0,0 +1,52 @@
+#![feature(test)]
+extern crate test;
+
+use genetic_algorithm::neural_network::neural_network::NeuralNetwork;
+use test::Bencher;
+
+const DEFAULT_SEED: [u8; 32] = [
+    1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4,
+];
+
+// Roughly the size of the images example: 28x28 inputs, two hidden layers and 10 outputs.
+fn create_network() -> NeuralNetwork {
+    NeuralNetwork::new(784, &[64, 32], 10, DEFAULT_SEED)
+}
+
+fn create_batch() -> Vec<Vec<f64>> {
+    (0..64)
+        .map(|sample| {
+            (0..784)
+                .map(|input| ((sample + input) % 255) as f64 / 255.0)
+                .collect()
+        })
+        .collect()
+}
+
+#[bench]
+fn feedforward(b: &mut Bencher) {
+    let net = create_network();
+    let batch = create_batch();
+    b.iter(|| {
+        batch
+            .iter()
+            .map(|inputs| net.feedforward(inputs))
+            .collect::<Vec<_>>()
+    });
+}
+
+#[bench]
+fn compiled_feedforward_batch(b: &mut Bencher) {
+    let compiled = create_network().compile::<f64>();
+    let batch = create_batch();
+    let batch: Vec<&[f64]> = batch.iter().map(|inputs| inputs.as_slice()).collect();
+    b.iter(|| compiled.feedforward_batch(&batch));
+}
+
+#[bench]
+fn compiled_f32_feedforward_batch(b: &mut Bencher) {
+    let compiled = create_network().compile::<f32>();
+    let batch = create_batch();
+    let batch: Vec<&[f64]> = batch.iter().map(|inputs| inputs.as_slice()).collect();
+    b.iter(|| compiled.feedforward_batch(&batch));
+}
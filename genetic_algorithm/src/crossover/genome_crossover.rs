@@ -217,6 +217,19 @@ pub fn get_default_better_individual<'a, T>(
     }
 }
 
+// The parent that get_default_better_individual did not pick.
+pub fn other_parent<'a, T>(
+    better_individual: &Individual<T>,
+    indv_one: &'a Individual<T>,
+    indv_two: &'a Individual<T>,
+) -> &'a Individual<T> {
+    if std::ptr::eq(better_individual, indv_one) {
+        indv_two
+    } else {
+        indv_one
+    }
+}
+
 #[cfg(test)]
 mod crossover_test {
     use crate::crossover::genome_crossover::StringCrossover;
@@ -1,2 +1,3 @@
 pub mod genome_crossover;
+pub mod neat_crossover;
 pub mod neural_crossover;
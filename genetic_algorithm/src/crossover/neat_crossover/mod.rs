@@ -0,0 +1 @@
+pub mod neat_crossover;
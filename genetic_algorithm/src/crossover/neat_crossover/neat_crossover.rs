@@ -0,0 +1,112 @@
+use crate::crossover::genome_crossover::{get_default_better_individual, other_parent, Crossover};
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, ProblemType};
+use crate::neat::neat_genome::NeatGenome;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Historically aligned crossover, see NeatGenome::crossover. The original NEAT paper uses 0.75 as disable rate.
+pub struct NeatCrossover {
+    crossover_rate: f64,
+    disable_rate: f64,
+    seed: StdRng,
+}
+
+impl NeatCrossover {
+    pub fn new(crossover_rate: f64, disable_rate: f64, seed: [u8; 32]) -> NeatCrossover {
+        NeatCrossover {
+            crossover_rate,
+            disable_rate,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Crossover for NeatCrossover {
+    type T = NeatGenome;
+
+    fn crossover(
+        &mut self,
+        _first_individual: &Individual<NeatGenome>,
+        _second_individual: &Individual<NeatGenome>,
+        fitness_function: &mut Box<dyn FitnessFunction<T = NeatGenome>>,
+        problem_type: &ProblemType,
+    ) -> Individual<NeatGenome> {
+        let better_individual =
+            get_default_better_individual(_first_individual, _second_individual, problem_type);
+        if self.seed.gen::<f64>() >= self.crossover_rate {
+            return better_individual.clone();
+        }
+        let other_individual =
+            other_parent(better_individual, _first_individual, _second_individual);
+        let equal_fitness = _first_individual.fitness() == _second_individual.fitness();
+
+        let new_genome = better_individual.retrieve_individual().crossover(
+            other_individual.retrieve_individual(),
+            equal_fitness,
+            self.disable_rate,
+            &mut self.seed,
+        );
+
+        let new_fitness = fitness_function.calculate_fitness(&new_genome);
+        Individual::new(new_genome, new_fitness)
+    }
+}
+
+#[cfg(test)]
+mod neat_crossover_test {
+    use crate::crossover::genome_crossover::Crossover;
+    use crate::crossover::neat_crossover::neat_crossover::NeatCrossover;
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, ProblemType};
+    use crate::neat::innovation::InnovationTracker;
+    use crate::neat::neat_genome::NeatGenome;
+    use std::borrow::Borrow;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeatFitnessFunction;
+    impl FitnessFunction for TestNeatFitnessFunction {
+        type T = NeatGenome;
+
+        fn calculate_fitness(&mut self, _: &NeatGenome) -> f64 {
+            3.0
+        }
+    }
+
+    #[test]
+    fn neat_crossover_test() {
+        let tracker = InnovationTracker::new();
+        let genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+        let mut grown_genome = genome.clone();
+        grown_genome.split_connection(0, &tracker);
+        let indv_one = Individual::new(genome, 1.0);
+        let indv_two = Individual::new(grown_genome, 2.0);
+        let mut fitness_function: Box<dyn FitnessFunction<T = NeatGenome>> =
+            Box::new(TestNeatFitnessFunction::default());
+
+        let mut neat_crossover = NeatCrossover::new(1.0, 0.75, *DEFAULT_SEED);
+        let new_indv = neat_crossover.crossover(
+            indv_one.borrow(),
+            indv_two.borrow(),
+            &mut fitness_function,
+            &ProblemType::Max,
+        );
+        // The child has the structure of the fitter parent.
+        assert_eq!(*new_indv.fitness(), 3.0);
+        assert_eq!(new_indv.retrieve_individual().number_of_hidden_nodes(), 1);
+        assert_eq!(new_indv.retrieve_individual().connections().len(), 5);
+
+        let new_indv = neat_crossover.crossover(
+            indv_one.borrow(),
+            indv_two.borrow(),
+            &mut fitness_function,
+            &ProblemType::Min,
+        );
+        assert_eq!(new_indv.retrieve_individual().number_of_hidden_nodes(), 0);
+        assert_eq!(new_indv.retrieve_individual().connections().len(), 3);
+    }
+}
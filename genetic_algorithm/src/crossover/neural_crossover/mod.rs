@@ -1 +1,2 @@
+pub mod node_crossover;
 pub mod node_swap;
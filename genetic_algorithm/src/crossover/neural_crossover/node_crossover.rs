@@ -0,0 +1,247 @@
+use crate::crossover::genome_crossover::{get_default_better_individual, other_parent, Crossover};
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, ProblemType};
+use crate::neural_network::neural_network::NeuralNetwork;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+// For both crossovers the child takes the topology of the better parent. Nodes are matched to the other parent by layer
+// and node position, so parents with different layer widths only exchange the nodes and weights they have in common.
+
+// Every matched node is taken as a whole from one of the two parents.
+pub struct NodeCrossover {
+    crossover_rate: f64,
+    seed: StdRng,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum WeightBlend {
+    // Every matched weight is taken from one of the two parents.
+    Uniform,
+    // Every matched weight becomes alpha * better parent weight + (1 - alpha) * other parent weight.
+    Arithmetic { alpha: f64 },
+}
+
+pub struct WeightCrossover {
+    crossover_rate: f64,
+    blend: WeightBlend,
+    seed: StdRng,
+}
+
+impl NodeCrossover {
+    pub fn new(crossover_rate: f64, seed: [u8; 32]) -> NodeCrossover {
+        NodeCrossover {
+            crossover_rate,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl WeightCrossover {
+    pub fn new(crossover_rate: f64, blend: WeightBlend, seed: [u8; 32]) -> WeightCrossover {
+        WeightCrossover {
+            crossover_rate,
+            blend,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Crossover for NodeCrossover {
+    type T = NeuralNetwork;
+
+    fn crossover(
+        &mut self,
+        _first_individual: &Individual<NeuralNetwork>,
+        _second_individual: &Individual<NeuralNetwork>,
+        fitness_function: &mut Box<dyn FitnessFunction<T = NeuralNetwork>>,
+        problem_type: &ProblemType,
+    ) -> Individual<NeuralNetwork> {
+        let better_individual =
+            get_default_better_individual(_first_individual, _second_individual, problem_type);
+        if self.seed.gen::<f64>() >= self.crossover_rate {
+            return better_individual.clone();
+        }
+        let other_individual =
+            other_parent(better_individual, _first_individual, _second_individual);
+
+        let seed = &mut self.seed;
+        let new_net = better_individual
+            .retrieve_individual()
+            .recombine_nodes_and_create_new_from(
+                other_individual.retrieve_individual(),
+                |own, other| {
+                    if seed.gen_bool(0.5) {
+                        own.to_vec()
+                    } else {
+                        other.to_vec()
+                    }
+                },
+            );
+
+        let new_fitness = fitness_function.calculate_fitness(&new_net);
+        Individual::new(new_net, new_fitness)
+    }
+}
+
+impl Crossover for WeightCrossover {
+    type T = NeuralNetwork;
+
+    fn crossover(
+        &mut self,
+        _first_individual: &Individual<NeuralNetwork>,
+        _second_individual: &Individual<NeuralNetwork>,
+        fitness_function: &mut Box<dyn FitnessFunction<T = NeuralNetwork>>,
+        problem_type: &ProblemType,
+    ) -> Individual<NeuralNetwork> {
+        let better_individual =
+            get_default_better_individual(_first_individual, _second_individual, problem_type);
+        if self.seed.gen::<f64>() >= self.crossover_rate {
+            return better_individual.clone();
+        }
+        let other_individual =
+            other_parent(better_individual, _first_individual, _second_individual);
+
+        let blend = self.blend;
+        let seed = &mut self.seed;
+        let new_net = better_individual
+            .retrieve_individual()
+            .recombine_nodes_and_create_new_from(
+                other_individual.retrieve_individual(),
+                |own, other| {
+                    own.iter()
+                        .zip(other.iter())
+                        .map(|(&own_weight, &other_weight)| match blend {
+                            WeightBlend::Uniform => {
+                                if seed.gen_bool(0.5) {
+                                    own_weight
+                                } else {
+                                    other_weight
+                                }
+                            }
+                            WeightBlend::Arithmetic { alpha } => {
+                                alpha * own_weight + (1.0 - alpha) * other_weight
+                            }
+                        })
+                        .collect()
+                },
+            );
+
+        let new_fitness = fitness_function.calculate_fitness(&new_net);
+        Individual::new(new_net, new_fitness)
+    }
+}
+
+#[cfg(test)]
+mod node_crossover_test {
+    use crate::crossover::genome_crossover::Crossover;
+    use crate::crossover::neural_crossover::node_crossover::{
+        NodeCrossover, WeightBlend, WeightCrossover,
+    };
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, ProblemType};
+    use crate::neural_network::neural_network::NeuralNetwork;
+    use std::borrow::Borrow;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeuralNetworkFitnessFunction;
+    impl FitnessFunction for TestNeuralNetworkFitnessFunction {
+        type T = NeuralNetwork;
+
+        fn calculate_fitness(&mut self, _: &NeuralNetwork) -> f64 {
+            3.0
+        }
+    }
+
+    fn create_parents() -> (Individual<NeuralNetwork>, Individual<NeuralNetwork>) {
+        let xs: [u32; 2] = [4, 3];
+        let mut net_one = NeuralNetwork::new(3, xs.as_ref(), 2, *DEFAULT_SEED);
+        let weights = vec![1.0; net_one.weight_count()];
+        net_one.set_weights(&weights);
+
+        let xs: [u32; 1] = [2];
+        let mut net_two = NeuralNetwork::new(3, xs.as_ref(), 2, *DEFAULT_SEED);
+        let weights = vec![-1.0; net_two.weight_count()];
+        net_two.set_weights(&weights);
+
+        (Individual::new(net_one, 2.0), Individual::new(net_two, 1.0))
+    }
+
+    #[test]
+    fn node_crossover_test() {
+        let (indv_one, indv_two) = create_parents();
+        let mut fitness_function: Box<dyn FitnessFunction<T = NeuralNetwork>> =
+            Box::new(TestNeuralNetworkFitnessFunction::default());
+
+        let mut node_crossover = NodeCrossover::new(1.0, *DEFAULT_SEED);
+        let new_indv = node_crossover.crossover(
+            indv_two.borrow(),
+            indv_one.borrow(),
+            &mut fitness_function,
+            &ProblemType::Max,
+        );
+        let new_net = new_indv.retrieve_individual();
+
+        assert_eq!(*new_indv.fitness(), 3.0);
+        assert_eq!(new_net.hidden_length(), 2);
+        assert_eq!(new_net.hidden_layer_length(0), 4);
+        assert_eq!(
+            new_net.weight_count(),
+            indv_one.retrieve_individual().weight_count()
+        );
+        // Nodes are swapped whole, so every weight of a node comes from the same parent (or from the better parent
+        // where the other one has no matching weight).
+        let weights = new_net.weights();
+        assert!(weights.iter().any(|&weight| weight == -1.0));
+        assert!(weights.iter().any(|&weight| weight == 1.0));
+
+        let mut node_crossover = NodeCrossover::new(0.0, *DEFAULT_SEED);
+        let new_indv = node_crossover.crossover(
+            indv_one.borrow(),
+            indv_two.borrow(),
+            &mut fitness_function,
+            &ProblemType::Min,
+        );
+        assert_eq!(*new_indv.fitness(), 1.0);
+        assert_eq!(new_indv.retrieve_individual().hidden_length(), 1);
+    }
+
+    #[test]
+    fn weight_crossover_test() {
+        let (indv_one, indv_two) = create_parents();
+        let mut fitness_function: Box<dyn FitnessFunction<T = NeuralNetwork>> =
+            Box::new(TestNeuralNetworkFitnessFunction::default());
+
+        let mut weight_crossover =
+            WeightCrossover::new(1.0, WeightBlend::Arithmetic { alpha: 0.75 }, *DEFAULT_SEED);
+        let new_indv = weight_crossover.crossover(
+            indv_one.borrow(),
+            indv_two.borrow(),
+            &mut fitness_function,
+            &ProblemType::Max,
+        );
+        let weights = new_indv.retrieve_individual().weights();
+        assert_eq!(weights.len(), indv_one.retrieve_individual().weight_count());
+        for weight in weights {
+            assert!(weight == 0.5 || weight == 1.0);
+        }
+
+        let mut weight_crossover = WeightCrossover::new(1.0, WeightBlend::Uniform, *DEFAULT_SEED);
+        let new_indv = weight_crossover.crossover(
+            indv_one.borrow(),
+            indv_two.borrow(),
+            &mut fitness_function,
+            &ProblemType::Max,
+        );
+        let weights = new_indv.retrieve_individual().weights();
+        assert!(weights.iter().any(|&weight| weight == -1.0));
+        assert!(weights
+            .iter()
+            .all(|&weight| weight == -1.0 || weight == 1.0));
+    }
+}
@@ -34,10 +34,11 @@ impl Crossover for HiddenLayerSwap {
         let gen_number = self.seed.gen::<f64>();
 
         if gen_number < self.swap_chance {
-            let mut indv_one_net = _first_individual.retrieve_individual().clone();
-            let indv_two_net = _second_individual.retrieve_individual().clone();
+            let indv_one_net = _first_individual.retrieve_individual();
+            let indv_two_net = _second_individual.retrieve_individual();
 
-            let new_net = indv_one_net.hidden_layer_swap_and_create_new_from(indv_two_net.borrow());
+            let new_net =
+                indv_one_net.hidden_layer_swap_and_create_new_from(indv_two_net, &mut self.seed);
 
             return match new_net {
                 None => get_default_better_individual(
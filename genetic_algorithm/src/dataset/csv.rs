@@ -0,0 +1,249 @@
+use crate::dataset::dataset::{Dataset, DatasetError};
+use std::fs;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Column {
+    Index(usize),
+    // Needs a header.
+    Name(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LabelKind {
+    // The label column holds class names, which become one hot targets. Classes are sorted by name.
+    Class,
+    // The label column holds a number, which becomes a single target.
+    Value,
+}
+
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    // b',' for CSV and b'\t' for TSV.
+    pub delimiter: u8,
+    pub has_header: bool,
+    // Without a label column every column is an input and the targets are empty.
+    pub label_column: Option<Column>,
+    pub label_kind: LabelKind,
+}
+
+impl Default for CsvOptions {
+    fn default() -> CsvOptions {
+        CsvOptions {
+            delimiter: b',',
+            has_header: true,
+            label_column: None,
+            label_kind: LabelKind::Class,
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn tsv() -> CsvOptions {
+        CsvOptions {
+            delimiter: b'\t',
+            ..CsvOptions::default()
+        }
+    }
+}
+
+pub fn read_csv(file_path: &str, options: &CsvOptions) -> Result<Dataset, DatasetError> {
+    parse_csv(&fs::read_to_string(file_path)?, options)
+}
+
+// Every column apart from the label column has to be numeric. Empty lines are skipped and fields can be quoted with ".
+pub fn parse_csv(text: &str, options: &CsvOptions) -> Result<Dataset, DatasetError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header = if options.has_header {
+        lines
+            .next()
+            .map(|(_, line)| split_line(line, options.delimiter))
+    } else {
+        None
+    };
+
+    let label_index = match &options.label_column {
+        None => None,
+        Some(Column::Index(index)) => Some(*index),
+        Some(Column::Name(name)) => {
+            let header = header.as_ref().ok_or_else(|| {
+                DatasetError::InvalidFormat(format!(
+                    "column {} can only be found in a header",
+                    name
+                ))
+            })?;
+            Some(
+                header
+                    .iter()
+                    .position(|column| column == name)
+                    .ok_or_else(|| {
+                        DatasetError::InvalidFormat(format!("there is no column named {}", name))
+                    })?,
+            )
+        }
+    };
+
+    let mut inputs = Vec::new();
+    let mut labels = Vec::new();
+    let mut number_of_columns = header.as_ref().map(Vec::len);
+    for (line_index, line) in lines {
+        let line_number = line_index + 1;
+        let fields = split_line(line, options.delimiter);
+        match number_of_columns {
+            Some(expected) if expected != fields.len() => {
+                return Err(DatasetError::InvalidFormat(format!(
+                    "line {} has {} columns instead of {}",
+                    line_number,
+                    fields.len(),
+                    expected
+                )))
+            }
+            _ => number_of_columns = Some(fields.len()),
+        }
+        if let Some(label_index) = label_index {
+            if label_index >= fields.len() {
+                return Err(DatasetError::InvalidFormat(format!(
+                    "line {} has no column {}",
+                    line_number, label_index
+                )));
+            }
+        }
+
+        let mut row = Vec::with_capacity(fields.len());
+        for (column, field) in fields.iter().enumerate() {
+            if Some(column) == label_index {
+                labels.push((line_number, field.clone()));
+                continue;
+            }
+            row.push(parse_number(field, line_number, column)?);
+        }
+        inputs.push(row);
+    }
+
+    if label_index.is_none() {
+        let targets = vec![Vec::new(); inputs.len()];
+        return Ok(Dataset::new(inputs, targets));
+    }
+    match options.label_kind {
+        LabelKind::Class => {
+            let mut class_names: Vec<String> =
+                labels.iter().map(|(_, label)| label.clone()).collect();
+            class_names.sort();
+            class_names.dedup();
+            let labels: Vec<usize> = labels
+                .iter()
+                .map(|(_, label)| class_names.binary_search(label).unwrap())
+                .collect();
+            Ok(Dataset::from_labels(inputs, &labels, class_names))
+        }
+        LabelKind::Value => {
+            let mut targets = Vec::with_capacity(labels.len());
+            for (line_number, label) in labels.iter() {
+                targets.push(vec![parse_number(
+                    label,
+                    *line_number,
+                    label_index.unwrap(),
+                )?]);
+            }
+            Ok(Dataset::new(inputs, targets))
+        }
+    }
+}
+
+fn parse_number(field: &str, line_number: usize, column: usize) -> Result<f64, DatasetError> {
+    field.trim().parse::<f64>().map_err(|_| {
+        DatasetError::InvalidFormat(format!(
+            "{:?} in line {} column {} is not a number",
+            field, line_number, column
+        ))
+    })
+}
+
+// Splits a line on the delimiter, except inside double quotes. Two double quotes inside quotes are a literal quote.
+fn split_line(line: &str, delimiter: u8) -> Vec<String> {
+    let delimiter = delimiter as char;
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut characters = line.chars().peekable();
+    while let Some(character) = characters.next() {
+        match character {
+            '"' if in_quotes && characters.peek() == Some(&'"') => {
+                field.push('"');
+                characters.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            character if character == delimiter && !in_quotes => {
+                fields.push(field.trim().to_string());
+                field.clear();
+            }
+            character => field.push(character),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+#[cfg(test)]
+mod csv_test {
+    use crate::dataset::csv::{parse_csv, Column, CsvOptions, LabelKind};
+
+    const IRIS: &str = "sepal_length,sepal_width,species\n\
+                        5.1,3.5,setosa\n\
+                        7.0,3.2,versicolor\n\
+                        \n\
+                        4.9,3.0,\"setosa\"\n";
+
+    #[test]
+    fn csv_class_label_test() {
+        let options = CsvOptions {
+            label_column: Some(Column::Name(String::from("species"))),
+            ..CsvOptions::default()
+        };
+        let dataset = parse_csv(IRIS, &options).unwrap();
+        assert_eq!(dataset.len(), 3);
+        assert_eq!(dataset.inputs()[1], vec![7.0, 3.2]);
+        assert_eq!(
+            *dataset.class_names(),
+            vec![String::from("setosa"), String::from("versicolor")]
+        );
+        assert_eq!(dataset.labels(), vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn csv_value_label_test() {
+        let options = CsvOptions {
+            has_header: false,
+            label_column: Some(Column::Index(0)),
+            label_kind: LabelKind::Value,
+            ..CsvOptions::tsv()
+        };
+        let dataset = parse_csv("1.5\t2\t3\n-2\t4\t5\n", &options).unwrap();
+        assert_eq!(*dataset.targets(), vec![vec![1.5], vec![-2.0]]);
+        assert_eq!(*dataset.inputs(), vec![vec![2.0, 3.0], vec![4.0, 5.0]]);
+
+        let options = CsvOptions {
+            has_header: false,
+            ..CsvOptions::default()
+        };
+        let dataset = parse_csv("1,\"2\"\n3,4\n", &options).unwrap();
+        assert_eq!(*dataset.inputs(), vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+        assert!(dataset.targets()[0].is_empty());
+    }
+
+    #[test]
+    fn csv_validation_test() {
+        let options = CsvOptions {
+            label_column: Some(Column::Name(String::from("color"))),
+            ..CsvOptions::default()
+        };
+        assert!(parse_csv(IRIS, &options).is_err());
+
+        let options = CsvOptions::default();
+        // The species column is not numeric without being the label column.
+        assert!(parse_csv(IRIS, &options).is_err());
+        assert!(parse_csv("a,b\n1,2\n3\n", &options).is_err());
+    }
+}
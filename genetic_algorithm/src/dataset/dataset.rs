@@ -0,0 +1,314 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum DatasetError {
+    Io(std::io::Error),
+    InvalidFormat(String),
+}
+
+impl fmt::Display for DatasetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DatasetError::Io(error) => write!(f, "Could not read the dataset: {}", error),
+            DatasetError::InvalidFormat(reason) => write!(f, "Invalid dataset: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DatasetError {}
+
+impl From<std::io::Error> for DatasetError {
+    fn from(error: std::io::Error) -> DatasetError {
+        DatasetError::Io(error)
+    }
+}
+
+// Samples for supervised learning: every input row has a target row. Classification datasets have one hot targets and
+// the name of every class, in the order of the target columns.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Dataset {
+    inputs: Vec<Vec<f64>>,
+    targets: Vec<Vec<f64>>,
+    class_names: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DatasetSplit {
+    pub train: Dataset,
+    pub validation: Dataset,
+    pub test: Dataset,
+}
+
+#[derive(Clone, Debug)]
+pub struct Fold {
+    pub train: Dataset,
+    pub validation: Dataset,
+}
+
+impl Dataset {
+    pub fn new(inputs: Vec<Vec<f64>>, targets: Vec<Vec<f64>>) -> Dataset {
+        Dataset::with_class_names(inputs, targets, Vec::new())
+    }
+
+    pub fn with_class_names(
+        inputs: Vec<Vec<f64>>,
+        targets: Vec<Vec<f64>>,
+        class_names: Vec<String>,
+    ) -> Dataset {
+        if inputs.len() != targets.len() {
+            panic!(
+                "Got {} inputs but {} targets, every input needs a target.",
+                inputs.len(),
+                targets.len()
+            );
+        }
+        for (rows, name) in [(&inputs, "input"), (&targets, "target")].iter() {
+            if let Some(row) = rows.iter().find(|row| row.len() != rows[0].len()) {
+                panic!(
+                    "Every {} row needs the same length. The first row has {} values but another has {}.",
+                    name,
+                    rows[0].len(),
+                    row.len()
+                );
+            }
+        }
+        Dataset {
+            inputs,
+            targets,
+            class_names,
+        }
+    }
+
+    // Classification dataset with a one hot target for every label. Labels have to be below the number of classes.
+    pub fn from_labels(
+        inputs: Vec<Vec<f64>>,
+        labels: &[usize],
+        class_names: Vec<String>,
+    ) -> Dataset {
+        let targets = labels
+            .iter()
+            .map(|&label| {
+                if label >= class_names.len() {
+                    panic!(
+                        "Label {} is out of range, there are only {} classes.",
+                        label,
+                        class_names.len()
+                    );
+                }
+                let mut target = vec![0.0; class_names.len()];
+                target[label] = 1.0;
+                target
+            })
+            .collect();
+        Dataset::with_class_names(inputs, targets, class_names)
+    }
+
+    pub fn inputs(&self) -> &Vec<Vec<f64>> {
+        &self.inputs
+    }
+
+    pub fn inputs_mut(&mut self) -> &mut Vec<Vec<f64>> {
+        &mut self.inputs
+    }
+
+    pub fn targets(&self) -> &Vec<Vec<f64>> {
+        &self.targets
+    }
+
+    pub fn class_names(&self) -> &Vec<String> {
+        &self.class_names
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    pub fn number_of_features(&self) -> usize {
+        self.inputs.first().map_or(0, Vec::len)
+    }
+
+    // Index of the largest target of every sample, which is the class for one hot targets.
+    pub fn labels(&self) -> Vec<usize> {
+        self.targets.iter().map(|target| argmax(target)).collect()
+    }
+
+    pub fn subset(&self, indices: &[usize]) -> Dataset {
+        Dataset {
+            inputs: indices
+                .iter()
+                .map(|&index| self.inputs[index].clone())
+                .collect(),
+            targets: indices
+                .iter()
+                .map(|&index| self.targets[index].clone())
+                .collect(),
+            class_names: self.class_names.clone(),
+        }
+    }
+
+    pub fn shuffle(&mut self, rng: &mut impl Rng) {
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.shuffle(rng);
+        *self = self.subset(&order);
+    }
+
+    // Shuffles the samples and splits them into a train, validation and test set. The fractions are rounded down to
+    // whole samples and the train set gets the rest.
+    pub fn split(
+        &self,
+        validation_fraction: f64,
+        test_fraction: f64,
+        rng: &mut impl Rng,
+    ) -> DatasetSplit {
+        if validation_fraction < 0.0
+            || test_fraction < 0.0
+            || validation_fraction + test_fraction > 1.0
+        {
+            panic!(
+                "Can not split off {} for validation and {} for testing.",
+                validation_fraction, test_fraction
+            );
+        }
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.shuffle(rng);
+        let number_of_validation = (self.len() as f64 * validation_fraction) as usize;
+        let number_of_test = (self.len() as f64 * test_fraction) as usize;
+        let (validation, rest) = order.split_at(number_of_validation);
+        let (test, train) = rest.split_at(number_of_test);
+
+        DatasetSplit {
+            train: self.subset(train),
+            validation: self.subset(validation),
+            test: self.subset(test),
+        }
+    }
+
+    // Shuffles the samples into k folds whose sizes differ by at most one. Every fold is the validation set once, with
+    // the other folds as train set.
+    pub fn k_folds(&self, k: usize, rng: &mut impl Rng) -> Vec<Fold> {
+        if k < 2 || k > self.len() {
+            panic!("Can not make {} folds out of {} samples.", k, self.len());
+        }
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.shuffle(rng);
+
+        let mut fold_starts = Vec::with_capacity(k + 1);
+        for fold in 0..=k {
+            fold_starts.push(fold * self.len() / k);
+        }
+        (0..k)
+            .map(|fold| {
+                let validation = &order[fold_starts[fold]..fold_starts[fold + 1]];
+                let train: Vec<usize> = order[..fold_starts[fold]]
+                    .iter()
+                    .chain(order[fold_starts[fold + 1]..].iter())
+                    .cloned()
+                    .collect();
+                Fold {
+                    train: self.subset(&train),
+                    validation: self.subset(validation),
+                }
+            })
+            .collect()
+    }
+}
+
+// Index of the largest value, the first one on ties.
+pub fn argmax(values: &[f64]) -> usize {
+    (0..values.len()).fold(0, |best, index| {
+        if values[index] > values[best] {
+            index
+        } else {
+            best
+        }
+    })
+}
+
+#[cfg(test)]
+mod dataset_test {
+    use crate::dataset::dataset::Dataset;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    fn create_dataset() -> Dataset {
+        let inputs = (0..10).map(|x| vec![x as f64]).collect();
+        let labels: Vec<usize> = (0..10).map(|x| x % 3).collect();
+        let class_names = vec![String::from("a"), String::from("b"), String::from("c")];
+        Dataset::from_labels(inputs, &labels, class_names)
+    }
+
+    #[test]
+    fn dataset_labels_test() {
+        let dataset = create_dataset();
+        assert_eq!(dataset.len(), 10);
+        assert_eq!(dataset.number_of_features(), 1);
+        assert_eq!(dataset.targets()[4], vec![0.0, 1.0, 0.0]);
+        assert_eq!(dataset.labels()[..4], [0, 1, 2, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ragged_dataset_test() {
+        Dataset::new(vec![vec![1.0, 2.0], vec![3.0]], vec![vec![1.0], vec![0.0]]);
+    }
+
+    #[test]
+    fn dataset_split_test() {
+        let dataset = create_dataset();
+        let mut rng: StdRng = SeedableRng::from_seed(*DEFAULT_SEED);
+        let split = dataset.split(0.2, 0.3, &mut rng);
+        assert_eq!(split.validation.len(), 2);
+        assert_eq!(split.test.len(), 3);
+        assert_eq!(split.train.len(), 5);
+
+        // Every sample ends up in exactly one set, with its own target.
+        let mut values: Vec<f64> = Vec::new();
+        for set in [&split.train, &split.validation, &split.test].iter() {
+            for (inputs, label) in set.inputs().iter().zip(set.labels()) {
+                assert_eq!(inputs[0] as usize % 3, label);
+                values.push(inputs[0]);
+            }
+        }
+        values.sort_by(|first, second| first.partial_cmp(second).unwrap());
+        assert_eq!(values, (0..10).map(|x| x as f64).collect::<Vec<f64>>());
+
+        // The same seed gives the same split.
+        let mut rng: StdRng = SeedableRng::from_seed(*DEFAULT_SEED);
+        assert_eq!(dataset.split(0.2, 0.3, &mut rng).test, split.test);
+    }
+
+    #[test]
+    fn dataset_k_folds_test() {
+        let dataset = create_dataset();
+        let mut rng: StdRng = SeedableRng::from_seed(*DEFAULT_SEED);
+        let folds = dataset.k_folds(3, &mut rng);
+        assert_eq!(folds.len(), 3);
+
+        let mut validation_values: Vec<f64> = Vec::new();
+        for fold in folds.iter() {
+            assert_eq!(fold.train.len() + fold.validation.len(), 10);
+            assert!(fold.validation.len() == 3 || fold.validation.len() == 4);
+            for inputs in fold.validation.inputs().iter() {
+                assert!(!fold.train.inputs().contains(inputs));
+                validation_values.push(inputs[0]);
+            }
+        }
+        validation_values.sort_by(|first, second| first.partial_cmp(second).unwrap());
+        assert_eq!(
+            validation_values,
+            (0..10).map(|x| x as f64).collect::<Vec<f64>>()
+        );
+    }
+}
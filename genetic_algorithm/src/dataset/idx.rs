@@ -0,0 +1,205 @@
+use crate::dataset::dataset::{Dataset, DatasetError};
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+// Multi dimensional array in the IDX format used by MNIST. The magic number is two zero bytes, a type code and the number
+// of dimensions, followed by the size of every dimension as big endian u32 and then the values in row-major order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdxArray {
+    dimensions: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl IdxArray {
+    pub fn read_from_file(file_path: &str) -> Result<IdxArray, DatasetError> {
+        let mut bytes = Vec::new();
+        BufReader::new(File::open(file_path)?).read_to_end(&mut bytes)?;
+        IdxArray::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<IdxArray, DatasetError> {
+        let invalid_format = |reason: String| Err(DatasetError::InvalidFormat(reason));
+        if bytes.len() < 4 || bytes[0] != 0 || bytes[1] != 0 {
+            return invalid_format(String::from(
+                "the idx magic number has to start with two zero bytes",
+            ));
+        }
+        let type_code = bytes[2];
+        let value_size: usize = match type_code {
+            0x08 | 0x09 => 1,
+            0x0B => 2,
+            0x0C | 0x0D => 4,
+            0x0E => 8,
+            _ => return invalid_format(format!("unknown idx type code {:#04x}", type_code)),
+        };
+        let number_of_dimensions = bytes[3] as usize;
+        let header_size = 4 + 4 * number_of_dimensions;
+        if bytes.len() < header_size {
+            return invalid_format(format!(
+                "the header of {} dimensions is cut off",
+                number_of_dimensions
+            ));
+        }
+
+        let dimensions: Vec<usize> = bytes[4..header_size]
+            .chunks(4)
+            .map(|size| u32::from_be_bytes(size.try_into().unwrap()) as usize)
+            .collect();
+        let data_size = match dimensions
+            .iter()
+            .try_fold(value_size, |size, &dimension| size.checked_mul(dimension))
+        {
+            Some(data_size) => data_size,
+            None => return invalid_format(format!("dimensions {:?} are too large", dimensions)),
+        };
+        let data = &bytes[header_size..];
+        if data.len() != data_size {
+            return invalid_format(format!(
+                "dimensions {:?} need {} bytes of data but the file has {}",
+                dimensions,
+                data_size,
+                data.len()
+            ));
+        }
+
+        let values = data
+            .chunks(value_size)
+            .map(|value| match type_code {
+                0x08 => f64::from(value[0]),
+                0x09 => f64::from(value[0] as i8),
+                0x0B => f64::from(i16::from_be_bytes(value.try_into().unwrap())),
+                0x0C => f64::from(i32::from_be_bytes(value.try_into().unwrap())),
+                0x0D => f64::from(f32::from_be_bytes(value.try_into().unwrap())),
+                _ => f64::from_be_bytes(value.try_into().unwrap()),
+            })
+            .collect();
+        Ok(IdxArray { dimensions, values })
+    }
+
+    pub fn dimensions(&self) -> &Vec<usize> {
+        &self.dimensions
+    }
+
+    pub fn values(&self) -> &Vec<f64> {
+        &self.values
+    }
+
+    // Number of entries along the first dimension, e.g. the number of images.
+    pub fn number_of_items(&self) -> usize {
+        self.dimensions.first().cloned().unwrap_or(0)
+    }
+
+    // Every entry along the first dimension flattened into a row.
+    pub fn rows(&self) -> Vec<Vec<f64>> {
+        let row_length = self.dimensions.iter().skip(1).product::<usize>();
+        if row_length == 0 {
+            return vec![Vec::new(); self.number_of_items()];
+        }
+        self.values
+            .chunks(row_length)
+            .map(|row| row.to_vec())
+            .collect()
+    }
+}
+
+// Classification dataset from an IDX file of samples and an IDX file of labels, like the MNIST training files. The
+// classes are named after the labels 0 up to the largest label.
+pub fn read_idx_dataset(
+    inputs_file_path: &str,
+    labels_file_path: &str,
+) -> Result<Dataset, DatasetError> {
+    idx_dataset(
+        &IdxArray::read_from_file(inputs_file_path)?,
+        &IdxArray::read_from_file(labels_file_path)?,
+    )
+}
+
+pub fn idx_dataset(inputs: &IdxArray, labels: &IdxArray) -> Result<Dataset, DatasetError> {
+    if labels.dimensions().len() != 1 {
+        return Err(DatasetError::InvalidFormat(format!(
+            "labels need a single dimension, got {:?}",
+            labels.dimensions()
+        )));
+    }
+    if inputs.number_of_items() != labels.number_of_items() {
+        return Err(DatasetError::InvalidFormat(format!(
+            "got {} inputs but {} labels",
+            inputs.number_of_items(),
+            labels.number_of_items()
+        )));
+    }
+    if labels
+        .values()
+        .iter()
+        .any(|&label| label < 0.0 || label.fract() != 0.0)
+    {
+        return Err(DatasetError::InvalidFormat(String::from(
+            "labels have to be whole numbers of at least 0",
+        )));
+    }
+
+    let labels: Vec<usize> = labels
+        .values()
+        .iter()
+        .map(|&label| label as usize)
+        .collect();
+    let number_of_classes = labels.iter().max().map_or(0, |&label| label + 1);
+    let class_names = (0..number_of_classes)
+        .map(|class| class.to_string())
+        .collect();
+    Ok(Dataset::from_labels(inputs.rows(), &labels, class_names))
+}
+
+#[cfg(test)]
+mod idx_test {
+    use crate::dataset::dataset::DatasetError;
+    use crate::dataset::idx::{idx_dataset, IdxArray};
+
+    // Two 2 by 2 images of unsigned bytes.
+    fn create_images() -> Vec<u8> {
+        vec![
+            0, 0, 0x08, 3, 0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 2, 0, 255, 10, 20, 1, 2, 3, 4,
+        ]
+    }
+
+    #[test]
+    fn idx_read_test() {
+        let images = IdxArray::from_bytes(&create_images()).unwrap();
+        assert_eq!(*images.dimensions(), vec![2, 2, 2]);
+        assert_eq!(images.rows()[0], vec![0.0, 255.0, 10.0, 20.0]);
+
+        let labels = IdxArray::from_bytes(&[0, 0, 0x08, 1, 0, 0, 0, 2, 3, 1]).unwrap();
+        let dataset = idx_dataset(&images, &labels).unwrap();
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.labels(), vec![3, 1]);
+        assert_eq!(dataset.class_names().len(), 4);
+
+        let shorts = IdxArray::from_bytes(&[0, 0, 0x0B, 1, 0, 0, 0, 2, 0xFF, 0xFE, 1, 0]).unwrap();
+        assert_eq!(*shorts.values(), vec![-2.0, 256.0]);
+    }
+
+    #[test]
+    fn idx_validation_test() {
+        let mut wrong_magic_number = create_images();
+        wrong_magic_number[0] = 8;
+        let mut unknown_type = create_images();
+        unknown_type[2] = 0x0A;
+        let mut truncated = create_images();
+        truncated.pop();
+        let overflowing = vec![
+            0, 0, 0x0E, 3, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
+        ];
+
+        for bytes in [wrong_magic_number, unknown_type, truncated, overflowing].iter() {
+            match IdxArray::from_bytes(bytes) {
+                Err(DatasetError::InvalidFormat(_)) => {}
+                _ => panic!("{:?} is not a valid idx array", bytes),
+            }
+        }
+
+        let images = IdxArray::from_bytes(&create_images()).unwrap();
+        let labels = IdxArray::from_bytes(&[0, 0, 0x08, 1, 0, 0, 0, 1, 3]).unwrap();
+        assert!(idx_dataset(&images, &labels).is_err());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod csv;
+pub mod dataset;
+pub mod idx;
+pub mod scaling;
@@ -0,0 +1,130 @@
+use crate::dataset::dataset::Dataset;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Scaling {
+    // Maps every feature onto [0, 1].
+    MinMax,
+    // Shifts every feature to a mean of 0 and a standard deviation of 1.
+    Standard,
+}
+
+// Per feature scaling learned from one dataset, usually the train set, and then applied unchanged to the validation and
+// test sets so no information leaks from them. Features that never change are only shifted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeatureScaler {
+    scaling: Scaling,
+    offsets: Vec<f64>,
+    scales: Vec<f64>,
+}
+
+impl FeatureScaler {
+    pub fn fit(scaling: Scaling, dataset: &Dataset) -> FeatureScaler {
+        if dataset.is_empty() {
+            panic!("Can not fit a feature scaler on an empty dataset.");
+        }
+        let number_of_samples = dataset.len() as f64;
+        let mut offsets = Vec::with_capacity(dataset.number_of_features());
+        let mut scales = Vec::with_capacity(dataset.number_of_features());
+        for feature in 0..dataset.number_of_features() {
+            let values = dataset.inputs().iter().map(|inputs| inputs[feature]);
+            let (offset, scale) = match scaling {
+                Scaling::MinMax => {
+                    let min = values.clone().fold(std::f64::INFINITY, f64::min);
+                    let max = values.fold(std::f64::NEG_INFINITY, f64::max);
+                    (min, max - min)
+                }
+                Scaling::Standard => {
+                    let mean = values.clone().sum::<f64>() / number_of_samples;
+                    let variance =
+                        values.map(|value| (value - mean).powi(2)).sum::<f64>() / number_of_samples;
+                    (mean, variance.sqrt())
+                }
+            };
+            offsets.push(offset);
+            scales.push(if scale > 0.0 { scale } else { 1.0 });
+        }
+        FeatureScaler {
+            scaling,
+            offsets,
+            scales,
+        }
+    }
+
+    pub fn scaling(&self) -> Scaling {
+        self.scaling
+    }
+
+    pub fn transform_inputs(&self, inputs: &[f64]) -> Vec<f64> {
+        if inputs.len() != self.offsets.len() {
+            panic!(
+                "The scaler was fitted on {} features but got {}.",
+                self.offsets.len(),
+                inputs.len()
+            );
+        }
+        inputs
+            .iter()
+            .zip(self.offsets.iter().zip(self.scales.iter()))
+            .map(|(value, (offset, scale))| (value - offset) / scale)
+            .collect()
+    }
+
+    pub fn transform(&self, dataset: &mut Dataset) {
+        for inputs in dataset.inputs_mut().iter_mut() {
+            *inputs = self.transform_inputs(inputs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod scaling_test {
+    use crate::dataset::dataset::Dataset;
+    use crate::dataset::scaling::{FeatureScaler, Scaling};
+
+    fn create_dataset() -> Dataset {
+        let inputs = vec![
+            vec![1.0, 5.0, 2.0],
+            vec![3.0, 5.0, 4.0],
+            vec![5.0, 5.0, 6.0],
+        ];
+        Dataset::new(inputs, vec![Vec::new(); 3])
+    }
+
+    #[test]
+    fn min_max_scaling_test() {
+        let mut dataset = create_dataset();
+        let scaler = FeatureScaler::fit(Scaling::MinMax, &dataset);
+        scaler.transform(&mut dataset);
+        assert_eq!(dataset.inputs()[1], vec![0.5, 0.0, 0.5]);
+        assert_eq!(dataset.inputs()[2], vec![1.0, 0.0, 1.0]);
+        // Values outside of the fitted range are not clipped.
+        assert_eq!(
+            scaler.transform_inputs(&[7.0, 6.0, 0.0]),
+            vec![1.5, 1.0, -0.5]
+        );
+    }
+
+    #[test]
+    fn standard_scaling_test() {
+        let mut dataset = create_dataset();
+        let scaler = FeatureScaler::fit(Scaling::Standard, &dataset);
+        scaler.transform(&mut dataset);
+        for feature in 0..3 {
+            let values: Vec<f64> = dataset
+                .inputs()
+                .iter()
+                .map(|inputs| inputs[feature])
+                .collect();
+            let mean = values.iter().sum::<f64>() / 3.0;
+            assert!(mean.abs() < 1e-12);
+        }
+        let variance = dataset
+            .inputs()
+            .iter()
+            .map(|inputs| inputs[0] * inputs[0])
+            .sum::<f64>()
+            / 3.0;
+        assert!((variance - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,549 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, ProblemType};
+use crate::genome::statistics::GenerationStatistics;
+use rand::distributions::StandardNormal;
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+const MIN_STEP_SIZE: f64 = 1e-10;
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Recombination {
+    // Every gene and step size is the average of the rho parents.
+    Intermediate,
+    // Every gene and step size is copied from one of the rho parents chosen at random.
+    Discrete,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum StepSizeAdaptation {
+    // One sigma shared by every gene of the individual.
+    Single,
+    // One sigma per gene.
+    PerGene,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum SurvivorSelection {
+    // (mu + lambda): the next parents are the best of the parents and the offspring.
+    Plus,
+    // (mu, lambda): the next parents are the best of the offspring only.
+    Comma,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct StrategyConfig {
+    pub mu: usize,
+    pub rho: usize,
+    pub lambda: usize,
+    pub recombination: Recombination,
+    pub step_size_adaptation: StepSizeAdaptation,
+    pub selection: SurvivorSelection,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StrategyGenome {
+    genes: Vec<f64>,
+    step_sizes: Vec<f64>,
+}
+
+pub struct EvolutionStrategy {
+    config: StrategyConfig,
+    parents: Vec<Individual<StrategyGenome>>,
+    problem_type: ProblemType,
+    generation: u64,
+    seed: StdRng,
+}
+
+impl StrategyGenome {
+    pub fn genes(&self) -> &Vec<f64> {
+        &self.genes
+    }
+    pub fn step_sizes(&self) -> &Vec<f64> {
+        &self.step_sizes
+    }
+}
+
+impl EvolutionStrategy {
+    // Starts from the given parents. If fewer than mu parents are given the remaining ones are mutated copies of them,
+    // which is how a single template (e.g. `NeuralNetwork::weights`) is turned into a starting population.
+    pub fn new(
+        config: StrategyConfig,
+        initial_parents: Vec<Vec<f64>>,
+        initial_step_size: f64,
+        problem_type: ProblemType,
+        fitness_function: &mut Box<dyn FitnessFunction<T = Vec<f64>>>,
+        seed: [u8; 32],
+    ) -> EvolutionStrategy {
+        if initial_parents.is_empty() {
+            panic!("Please give at least one initial parent to the evolution strategy.");
+        }
+        if let Some(parent) = initial_parents
+            .iter()
+            .find(|parent| parent.len() != initial_parents[0].len())
+        {
+            panic!(
+                "Every initial parent needs the same number of genes. The first parent has {} genes but another has {}",
+                initial_parents[0].len(),
+                parent.len()
+            );
+        }
+        if config.mu == 0 || config.lambda == 0 {
+            panic!("Please make mu and lambda greater than 0.");
+        }
+        if config.rho == 0 || config.rho > config.mu {
+            panic!(
+                "Please make rho between 1 and mu. Current rho is: {} and current mu is {}",
+                config.rho, config.mu
+            );
+        }
+        if let SurvivorSelection::Comma = config.selection {
+            if config.lambda < config.mu {
+                panic!(
+                    "Comma selection needs lambda to be at least mu. Current lambda is: {} and current mu is {}",
+                    config.lambda, config.mu
+                );
+            }
+        }
+
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let number_of_genes = initial_parents[0].len();
+        let number_of_step_sizes = match config.step_size_adaptation {
+            StepSizeAdaptation::Single => 1,
+            StepSizeAdaptation::PerGene => number_of_genes,
+        };
+
+        let mut parents = Vec::new();
+        for index in 0..config.mu {
+            let mut genes = initial_parents[index % initial_parents.len()].clone();
+            if index >= initial_parents.len() {
+                for gene in genes.iter_mut() {
+                    *gene += initial_step_size * rng.sample::<f64, _>(StandardNormal);
+                }
+            }
+            let genome = StrategyGenome {
+                genes,
+                step_sizes: vec![initial_step_size; number_of_step_sizes],
+            };
+            let fitness = fitness_function.calculate_fitness(&genome.genes);
+            parents.push(Individual::new(genome, fitness));
+        }
+
+        EvolutionStrategy {
+            config,
+            parents,
+            problem_type,
+            generation: 0,
+            seed: rng,
+        }
+    }
+
+    pub fn new_within_bounds(
+        config: StrategyConfig,
+        bounds: &[(f64, f64)],
+        initial_step_size: f64,
+        problem_type: ProblemType,
+        fitness_function: &mut Box<dyn FitnessFunction<T = Vec<f64>>>,
+        seed: [u8; 32],
+    ) -> EvolutionStrategy {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let initial_parents = (0..config.mu)
+            .map(|_| {
+                bounds
+                    .iter()
+                    .map(|&(lower, upper)| rng.gen_range(lower, upper))
+                    .collect()
+            })
+            .collect();
+        EvolutionStrategy::new(
+            config,
+            initial_parents,
+            initial_step_size,
+            problem_type,
+            fitness_function,
+            rng.gen(),
+        )
+    }
+
+    // Creates lambda offspring, selects the next mu parents and returns the statistics of the new parents.
+    pub fn step(
+        &mut self,
+        fitness_function: &mut Box<dyn FitnessFunction<T = Vec<f64>>>,
+    ) -> GenerationStatistics {
+        let mut offspring = Vec::new();
+        for _ in 0..self.config.lambda {
+            let mut genome = self.recombine();
+            self.mutate(&mut genome);
+            let fitness = fitness_function.calculate_fitness(&genome.genes);
+            offspring.push(Individual::new(genome, fitness));
+        }
+
+        let mut candidates = match self.config.selection {
+            SurvivorSelection::Plus => {
+                let mut candidates = self.parents.clone();
+                candidates.extend(offspring);
+                candidates
+            }
+            SurvivorSelection::Comma => offspring,
+        };
+
+        // Best first. Individuals with a NaN fitness go last, so they are the first to be dropped.
+        let problem_type = self.problem_type;
+        candidates.sort_by(|first, second| {
+            match (first.fitness.is_nan(), second.fitness.is_nan()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    let ordering = first.fitness.partial_cmp(&second.fitness).unwrap();
+                    match problem_type {
+                        ProblemType::Max => ordering.reverse(),
+                        ProblemType::Min => ordering,
+                    }
+                }
+            }
+        });
+        candidates.truncate(self.config.mu);
+        self.parents = candidates;
+
+        self.generation += 1;
+        self.statistics()
+    }
+
+    pub fn run(
+        &mut self,
+        generations: u64,
+        fitness_function: &mut Box<dyn FitnessFunction<T = Vec<f64>>>,
+    ) -> Vec<GenerationStatistics> {
+        (0..generations)
+            .map(|_| self.step(fitness_function))
+            .collect()
+    }
+
+    pub fn statistics(&self) -> GenerationStatistics {
+        let fitnesses: Vec<f64> = self.parents.iter().map(|parent| parent.fitness).collect();
+        GenerationStatistics::from_fitnesses(self.generation, &fitnesses, self.problem_type)
+    }
+
+    pub fn best(&self) -> &Individual<StrategyGenome> {
+        let mut best = &self.parents[0];
+        for parent in self.parents.iter().skip(1) {
+            if self.problem_type.is_better(parent.fitness, best.fitness) {
+                best = parent;
+            }
+        }
+        best
+    }
+
+    pub fn parents(&self) -> &Vec<Individual<StrategyGenome>> {
+        &self.parents
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn recombine(&mut self) -> StrategyGenome {
+        let chosen = sample(&mut self.seed, self.parents.len(), self.config.rho).into_vec();
+        let parents = &self.parents;
+        let chosen: Vec<&StrategyGenome> = chosen
+            .iter()
+            .map(|&index| parents[index].retrieve_individual())
+            .collect();
+
+        let recombination = self.config.recombination;
+        let genes = recombine_values(
+            recombination,
+            &chosen,
+            |genome| &genome.genes,
+            &mut self.seed,
+        );
+        let step_sizes = recombine_values(
+            recombination,
+            &chosen,
+            |genome| &genome.step_sizes,
+            &mut self.seed,
+        );
+        StrategyGenome { genes, step_sizes }
+    }
+
+    // Log-normal self-adaptation: the step sizes are mutated first and then used to mutate the genes.
+    fn mutate(&mut self, genome: &mut StrategyGenome) {
+        let number_of_genes = genome.genes.len() as f64;
+        match self.config.step_size_adaptation {
+            StepSizeAdaptation::Single => {
+                let learning_rate = 1.0 / number_of_genes.sqrt();
+                let noise: f64 = self.seed.sample(StandardNormal);
+                genome.step_sizes[0] =
+                    (genome.step_sizes[0] * (learning_rate * noise).exp()).max(MIN_STEP_SIZE);
+            }
+            StepSizeAdaptation::PerGene => {
+                let global_learning_rate = 1.0 / (2.0 * number_of_genes).sqrt();
+                let local_learning_rate = 1.0 / (2.0 * number_of_genes.sqrt()).sqrt();
+                let global_noise: f64 = self.seed.sample(StandardNormal);
+                for step_size in genome.step_sizes.iter_mut() {
+                    let local_noise: f64 = self.seed.sample(StandardNormal);
+                    *step_size = (*step_size
+                        * (global_learning_rate * global_noise
+                            + local_learning_rate * local_noise)
+                            .exp())
+                    .max(MIN_STEP_SIZE);
+                }
+            }
+        }
+
+        let single_step_size = genome.step_sizes.len() == 1;
+        for (index, gene) in genome.genes.iter_mut().enumerate() {
+            let step_size = if single_step_size {
+                genome.step_sizes[0]
+            } else {
+                genome.step_sizes[index]
+            };
+            *gene += step_size * self.seed.sample::<f64, _>(StandardNormal);
+        }
+    }
+}
+
+fn recombine_values(
+    recombination: Recombination,
+    chosen: &[&StrategyGenome],
+    values: impl Fn(&StrategyGenome) -> &Vec<f64>,
+    seed: &mut StdRng,
+) -> Vec<f64> {
+    let length = values(chosen[0]).len();
+    match recombination {
+        Recombination::Intermediate => (0..length)
+            .map(|index| {
+                chosen
+                    .iter()
+                    .map(|genome| values(genome)[index])
+                    .sum::<f64>()
+                    / chosen.len() as f64
+            })
+            .collect(),
+        Recombination::Discrete => (0..length)
+            .map(|index| values(chosen[seed.gen_range(0, chosen.len())])[index])
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod evolution_strategy_test {
+    use crate::evolution_strategy::evolution_strategy::{
+        EvolutionStrategy, Recombination, StepSizeAdaptation, StrategyConfig, SurvivorSelection,
+    };
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::ProblemType;
+    use crate::neural_network::neural_network::NeuralNetwork;
+    use crate::neural_network::weights_fitness::NetworkWeightsFitness;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestSphereFitnessFunction;
+    impl FitnessFunction for TestSphereFitnessFunction {
+        type T = Vec<f64>;
+
+        fn calculate_fitness(&mut self, individual: &Vec<f64>) -> f64 {
+            individual.iter().map(|x| x * x).sum()
+        }
+    }
+
+    // Undefined wherever the first gene is positive.
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestPartialSphereFitnessFunction;
+    impl FitnessFunction for TestPartialSphereFitnessFunction {
+        type T = Vec<f64>;
+
+        fn calculate_fitness(&mut self, individual: &Vec<f64>) -> f64 {
+            if individual[0] > 0.0 {
+                std::f64::NAN
+            } else {
+                individual.iter().map(|x| x * x).sum()
+            }
+        }
+    }
+
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeuralNetworkFitnessFunction;
+    impl FitnessFunction for TestNeuralNetworkFitnessFunction {
+        type T = NeuralNetwork;
+
+        fn calculate_fitness(&mut self, net: &NeuralNetwork) -> f64 {
+            net.feedforward(&[1.0, 0.0])[0]
+        }
+    }
+
+    #[test]
+    fn plus_selection_per_gene_converges() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestSphereFitnessFunction::default());
+        let config = StrategyConfig {
+            mu: 5,
+            rho: 2,
+            lambda: 30,
+            recombination: Recombination::Intermediate,
+            step_size_adaptation: StepSizeAdaptation::PerGene,
+            selection: SurvivorSelection::Plus,
+        };
+        let mut strategy = EvolutionStrategy::new_within_bounds(
+            config,
+            &[(-5.0, 5.0); 4],
+            1.0,
+            ProblemType::Min,
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+        let start_fitness = strategy.best().fitness;
+        let stats = strategy.run(150, &mut fitness_function);
+
+        assert_eq!(strategy.parents().len(), 5);
+        assert_eq!(stats[149].generation, 150);
+        assert_eq!(strategy.best().retrieve_individual().step_sizes().len(), 4);
+        // Plus selection never loses the best individual.
+        for window in stats.windows(2) {
+            assert!(window[1].best_fitness <= window[0].best_fitness);
+        }
+        assert!(strategy.best().fitness < start_fitness);
+        assert!(strategy.best().fitness < 0.001);
+    }
+
+    #[test]
+    fn comma_selection_single_step_size_converges() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestSphereFitnessFunction::default());
+        let config = StrategyConfig {
+            mu: 5,
+            rho: 5,
+            lambda: 35,
+            recombination: Recombination::Discrete,
+            step_size_adaptation: StepSizeAdaptation::Single,
+            selection: SurvivorSelection::Comma,
+        };
+        let mut strategy = EvolutionStrategy::new_within_bounds(
+            config,
+            &[(-5.0, 5.0); 4],
+            1.0,
+            ProblemType::Min,
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+        strategy.run(150, &mut fitness_function);
+
+        assert_eq!(strategy.best().retrieve_individual().step_sizes().len(), 1);
+        assert!(strategy.best().fitness < 0.01);
+    }
+
+    #[test]
+    fn nan_fitness_is_selected_last() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestPartialSphereFitnessFunction::default());
+        let config = StrategyConfig {
+            mu: 5,
+            rho: 2,
+            lambda: 30,
+            recombination: Recombination::Intermediate,
+            step_size_adaptation: StepSizeAdaptation::Single,
+            selection: SurvivorSelection::Plus,
+        };
+        let mut strategy = EvolutionStrategy::new_within_bounds(
+            config,
+            &[(-5.0, 5.0); 2],
+            1.0,
+            ProblemType::Min,
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+        strategy.run(20, &mut fitness_function);
+
+        for parent in strategy.parents().iter() {
+            assert!(!parent.fitness.is_nan());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn parents_of_different_lengths_are_rejected() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestSphereFitnessFunction::default());
+        let config = StrategyConfig {
+            mu: 2,
+            rho: 2,
+            lambda: 4,
+            recombination: Recombination::Intermediate,
+            step_size_adaptation: StepSizeAdaptation::PerGene,
+            selection: SurvivorSelection::Plus,
+        };
+        EvolutionStrategy::new(
+            config,
+            vec![vec![0.0, 1.0], vec![0.0, 1.0, 2.0]],
+            1.0,
+            ProblemType::Min,
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn comma_selection_needs_enough_offspring() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestSphereFitnessFunction::default());
+        let config = StrategyConfig {
+            mu: 5,
+            rho: 2,
+            lambda: 4,
+            recombination: Recombination::Discrete,
+            step_size_adaptation: StepSizeAdaptation::Single,
+            selection: SurvivorSelection::Comma,
+        };
+        EvolutionStrategy::new(
+            config,
+            vec![vec![0.0]],
+            1.0,
+            ProblemType::Min,
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+    }
+
+    #[test]
+    fn neural_network_weights_evolve() {
+        let net = NeuralNetwork::new(2, &[2], 1, *DEFAULT_SEED);
+        let weights = net.weights();
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> = Box::new(
+            NetworkWeightsFitness::new(net, Box::new(TestNeuralNetworkFitnessFunction::default())),
+        );
+        let config = StrategyConfig {
+            mu: 3,
+            rho: 2,
+            lambda: 12,
+            recombination: Recombination::Intermediate,
+            step_size_adaptation: StepSizeAdaptation::PerGene,
+            selection: SurvivorSelection::Plus,
+        };
+        let mut strategy = EvolutionStrategy::new(
+            config,
+            vec![weights.clone()],
+            0.5,
+            ProblemType::Max,
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+        let start_fitness = strategy.best().fitness;
+        strategy.run(50, &mut fitness_function);
+
+        assert_eq!(
+            strategy.best().retrieve_individual().genes().len(),
+            weights.len()
+        );
+        assert!(strategy.best().fitness > start_fitness);
+        assert!(strategy.best().fitness > 0.9);
+    }
+}
@@ -0,0 +1 @@
+pub mod evolution_strategy;
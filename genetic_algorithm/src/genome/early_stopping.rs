@@ -0,0 +1,163 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population, ProblemType};
+use serde::{Deserialize, Serialize};
+
+// Guards against overfitting: every evaluation_interval generations the current best individual of the population is
+// evaluated on a held-out validation set. The individual with the best validation fitness so far is kept as hall of fame,
+// with its validation fitness as fitness, and the evolution should stop once the validation fitness did not improve for
+// patience generations. Call update once per generation, after the population changed.
+pub struct EarlyStopping<T> {
+    validation_fitness_function: Box<dyn FitnessFunction<T = T>>,
+    problem_type: ProblemType,
+    evaluation_interval: u64,
+    patience: u64,
+    min_improvement: f64,
+    generation: u64,
+    best: Option<Individual<T>>,
+    best_generation: u64,
+    last_validation_fitness: Option<f64>,
+}
+
+impl<T: Clone + Serialize + Deserialize> EarlyStopping<T> {
+    // problem_type is the direction of the validation fitness, which can differ from the one of the population, e.g. a
+    // loss for training and the accuracy for validation.
+    pub fn new(
+        validation_fitness_function: Box<dyn FitnessFunction<T = T>>,
+        problem_type: ProblemType,
+        evaluation_interval: u64,
+        patience: u64,
+    ) -> EarlyStopping<T> {
+        if evaluation_interval == 0 {
+            panic!("The evaluation interval has to be at least 1 generation.");
+        }
+        EarlyStopping {
+            validation_fitness_function,
+            problem_type,
+            evaluation_interval,
+            patience,
+            min_improvement: 0.0,
+            generation: 0,
+            best: None,
+            best_generation: 0,
+            last_validation_fitness: None,
+        }
+    }
+
+    // A validation fitness only counts as better when it beats the best one by more than min_improvement.
+    pub fn set_min_improvement(&mut self, min_improvement: f64) {
+        self.min_improvement = min_improvement;
+    }
+
+    // Returns whether the evolution should stop.
+    pub fn update(&mut self, population: &mut Population<T>) -> bool {
+        self.generation += 1;
+        if self.generation % self.evaluation_interval == 0 {
+            let top_individual = population.find_top_individual().retrieve_individual();
+            let validation_fitness = self
+                .validation_fitness_function
+                .calculate_fitness(top_individual);
+            self.last_validation_fitness = Some(validation_fitness);
+            if self.is_improvement(validation_fitness) {
+                self.best = Some(Individual::new(top_individual.clone(), validation_fitness));
+                self.best_generation = self.generation;
+            }
+        }
+        self.should_stop()
+    }
+
+    fn is_improvement(&self, validation_fitness: f64) -> bool {
+        match &self.best {
+            None => true,
+            Some(best) => match self.problem_type {
+                ProblemType::Max => validation_fitness > best.fitness() + self.min_improvement,
+                ProblemType::Min => validation_fitness < best.fitness() - self.min_improvement,
+            },
+        }
+    }
+
+    pub fn should_stop(&self) -> bool {
+        self.best.is_some() && self.generation - self.best_generation >= self.patience
+    }
+
+    // Best individual on the validation set so far, with its validation fitness.
+    pub fn best(&self) -> Option<&Individual<T>> {
+        self.best.as_ref()
+    }
+
+    // Generation of the best individual on the validation set, counted in calls to update.
+    pub fn best_generation(&self) -> u64 {
+        self.best_generation
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn last_validation_fitness(&self) -> Option<f64> {
+        self.last_validation_fitness
+    }
+}
+
+#[cfg(test)]
+mod early_stopping_test {
+    use crate::genome::early_stopping::EarlyStopping;
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+
+    // Rewards strings of three ones, so longer strings overfit.
+    #[derive(Default, Copy, Clone, Debug)]
+    struct ValidationFitnessFunction;
+    impl FitnessFunction for ValidationFitnessFunction {
+        type T = String;
+
+        fn calculate_fitness(&mut self, individual: &String) -> f64 {
+            -(individual.len() as f64 - 3.0).abs()
+        }
+    }
+
+    fn create_population(length: usize) -> Population<String> {
+        let individuals = (1..=length)
+            .map(|length| Individual::new("1".repeat(length), length as f64))
+            .collect();
+        Population::new(individuals, ProblemType::Max)
+    }
+
+    #[test]
+    fn early_stopping_test() {
+        let mut early_stopping =
+            EarlyStopping::new(Box::new(ValidationFitnessFunction), ProblemType::Max, 2, 4);
+        assert!(!early_stopping.should_stop());
+
+        // The top individual grows by one character every generation.
+        let mut stopped_at = None;
+        for generation in 1..=20 {
+            let mut population = create_population(generation);
+            if early_stopping.update(&mut population) {
+                stopped_at = Some(generation);
+                break;
+            }
+        }
+
+        // Evaluated every second generation: "11" scores -1 in generation 2, "1111" is not better in generation 4 and
+        // "111111" is worse in generation 6, which runs out of patience.
+        assert_eq!(stopped_at, Some(6));
+        let best = early_stopping.best().unwrap();
+        assert_eq!(best.retrieve_individual(), "11");
+        assert_eq!(*best.fitness(), -1.0);
+        assert_eq!(early_stopping.best_generation(), 2);
+        assert_eq!(early_stopping.last_validation_fitness(), Some(-3.0));
+    }
+
+    #[test]
+    fn early_stopping_min_improvement_test() {
+        let mut early_stopping =
+            EarlyStopping::new(Box::new(ValidationFitnessFunction), ProblemType::Max, 1, 2);
+        early_stopping.set_min_improvement(1.5);
+        assert!(!early_stopping.update(&mut create_population(1)));
+        // An improvement of 1 is not enough.
+        assert!(!early_stopping.update(&mut create_population(2)));
+        assert!(early_stopping.update(&mut create_population(2)));
+        assert_eq!(early_stopping.best_generation(), 1);
+        assert_eq!(early_stopping.best().unwrap().retrieve_individual(), "1");
+    }
+}
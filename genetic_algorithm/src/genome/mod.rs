@@ -1,2 +1,5 @@
+pub mod early_stopping;
 pub mod fitness_function;
+pub mod parsimony;
 pub mod population;
+pub mod statistics;
@@ -0,0 +1,85 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::ProblemType;
+
+// Linear parsimony pressure: the fitness gets worse by coefficient for every unit of size, e.g.
+// `|net: &NeuralNetwork| net.parameter_count()`. For lexicographic or double tournament parsimony, which do not change
+// the fitness, see the selections in parsimony_selection.
+pub struct ParsimonyFitness<T> {
+    fitness_function: Box<dyn FitnessFunction<T = T>>,
+    size: fn(&T) -> usize,
+    coefficient: f64,
+    problem_type: ProblemType,
+}
+
+impl<T> ParsimonyFitness<T> {
+    pub fn new(
+        fitness_function: Box<dyn FitnessFunction<T = T>>,
+        size: fn(&T) -> usize,
+        coefficient: f64,
+        problem_type: ProblemType,
+    ) -> ParsimonyFitness<T> {
+        ParsimonyFitness {
+            fitness_function,
+            size,
+            coefficient,
+            problem_type,
+        }
+    }
+
+    // Fitness of the wrapped fitness function, without the penalty.
+    pub fn raw_fitness(&mut self, individual: &T) -> f64 {
+        self.fitness_function.calculate_fitness(individual)
+    }
+}
+
+impl<T> FitnessFunction for ParsimonyFitness<T> {
+    type T = T;
+
+    fn calculate_fitness(&mut self, individual: &T) -> f64 {
+        let penalty = self.coefficient * (self.size)(individual) as f64;
+        let fitness = self.fitness_function.calculate_fitness(individual);
+        match self.problem_type {
+            ProblemType::Max => fitness - penalty,
+            ProblemType::Min => fitness + penalty,
+        }
+    }
+}
+
+#[cfg(test)]
+mod parsimony_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::parsimony::ParsimonyFitness;
+    use crate::genome::population::ProblemType;
+
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestFitnessFunction;
+    impl FitnessFunction for TestFitnessFunction {
+        type T = String;
+
+        fn calculate_fitness(&mut self, individual: &String) -> f64 {
+            individual.matches('1').count() as f64
+        }
+    }
+
+    #[test]
+    fn parsimony_fitness_test() {
+        let individual = String::from("11011");
+
+        let mut fitness_function = ParsimonyFitness::new(
+            Box::new(TestFitnessFunction),
+            |individual: &String| individual.len(),
+            0.5,
+            ProblemType::Max,
+        );
+        assert_eq!(fitness_function.calculate_fitness(&individual), 1.5);
+        assert_eq!(fitness_function.raw_fitness(&individual), 4.0);
+
+        let mut fitness_function = ParsimonyFitness::new(
+            Box::new(TestFitnessFunction),
+            |individual: &String| individual.len(),
+            0.5,
+            ProblemType::Min,
+        );
+        assert_eq!(fitness_function.calculate_fitness(&individual), 6.5);
+    }
+}
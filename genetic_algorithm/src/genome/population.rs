@@ -1,5 +1,6 @@
 use crate::crossover::genome_crossover::Crossover;
 use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::statistics::GenerationStatistics;
 use crate::mutation::genome_mutation::Mutate;
 use crate::selection::genome_selection::SelectIndividual;
 use serde::{Deserialize, Serialize};
@@ -60,6 +61,16 @@ pub enum ProblemType {
     Min,
 }
 
+impl ProblemType {
+    // Whether the fitness is strictly better than the other fitness for this problem type.
+    pub fn is_better(&self, fitness: f64, other_fitness: f64) -> bool {
+        match self {
+            ProblemType::Max => fitness > other_fitness,
+            ProblemType::Min => fitness < other_fitness,
+        }
+    }
+}
+
 impl<T: Clone + Serialize + Deserialize> Population<T> {
     pub fn new(
         list_of_individuals: Vec<Individual<T>>,
@@ -163,6 +174,15 @@ impl<T: Clone + Serialize + Deserialize> Population<T> {
         self.problem_type
     }
 
+    pub fn statistics(&self, generation: u64) -> GenerationStatistics {
+        let fitnesses: Vec<f64> = self
+            .list_of_individuals
+            .iter()
+            .map(|individual| individual.fitness)
+            .collect();
+        GenerationStatistics::from_fitnesses(generation, &fitnesses, self.problem_type)
+    }
+
     pub fn print_pop(&mut self)
     where
         T: std::fmt::Debug,
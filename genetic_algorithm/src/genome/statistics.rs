@@ -0,0 +1,93 @@
+use crate::genome::population::ProblemType;
+use serde::{Deserialize, Serialize};
+
+// Summary of the fitness values of one generation (or one iteration for swarm based algorithms).
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GenerationStatistics {
+    pub generation: u64,
+    pub best_fitness: f64,
+    pub worst_fitness: f64,
+    pub mean_fitness: f64,
+    pub standard_deviation: f64,
+}
+
+impl GenerationStatistics {
+    pub fn from_fitnesses(
+        generation: u64,
+        fitnesses: &[f64],
+        problem_type: ProblemType,
+    ) -> GenerationStatistics {
+        if fitnesses.is_empty() {
+            return GenerationStatistics {
+                generation,
+                ..Default::default()
+            };
+        }
+
+        let mut min_fitness = fitnesses[0];
+        let mut max_fitness = fitnesses[0];
+        for &fitness in fitnesses.iter().skip(1) {
+            min_fitness = min_fitness.min(fitness);
+            max_fitness = max_fitness.max(fitness);
+        }
+
+        let (best_fitness, worst_fitness) = match problem_type {
+            ProblemType::Max => (max_fitness, min_fitness),
+            ProblemType::Min => (min_fitness, max_fitness),
+        };
+
+        let mean_fitness = fitnesses.iter().sum::<f64>() / fitnesses.len() as f64;
+        let variance = fitnesses
+            .iter()
+            .map(|fitness| (fitness - mean_fitness).powi(2))
+            .sum::<f64>()
+            / fitnesses.len() as f64;
+
+        GenerationStatistics {
+            generation,
+            best_fitness,
+            worst_fitness,
+            mean_fitness,
+            standard_deviation: variance.sqrt(),
+        }
+    }
+}
+
+impl std::fmt::Display for GenerationStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Generation: {}, Best fitness: {}, Worst fitness: {}, Mean fitness: {}, Std dev: {}",
+            self.generation,
+            self.best_fitness,
+            self.worst_fitness,
+            self.mean_fitness,
+            self.standard_deviation
+        )
+    }
+}
+
+#[cfg(test)]
+mod statistics_test {
+    use crate::genome::population::ProblemType;
+    use crate::genome::statistics::GenerationStatistics;
+
+    #[test]
+    fn statistics_from_fitnesses() {
+        let fitnesses = [1.0, 2.0, 3.0, 4.0];
+        let stats = GenerationStatistics::from_fitnesses(3, &fitnesses, ProblemType::Max);
+
+        assert_eq!(stats.generation, 3);
+        assert_eq!(stats.best_fitness, 4.0);
+        assert_eq!(stats.worst_fitness, 1.0);
+        assert_eq!(stats.mean_fitness, 2.5);
+        assert_eq!(stats.standard_deviation, 1.25_f64.sqrt());
+
+        let stats = GenerationStatistics::from_fitnesses(3, &fitnesses, ProblemType::Min);
+        assert_eq!(stats.best_fitness, 1.0);
+        assert_eq!(stats.worst_fitness, 4.0);
+
+        let stats = GenerationStatistics::from_fitnesses(0, &[], ProblemType::Min);
+        assert_eq!(stats.mean_fitness, 0.0);
+    }
+}
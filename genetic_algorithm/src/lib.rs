@@ -3,7 +3,12 @@
 extern crate rand;
 
 pub mod crossover;
+pub mod dataset;
+pub mod evolution_strategy;
 pub mod genome;
 pub mod mutation;
+pub mod neat;
 pub mod neural_network;
+pub mod particle_swarm;
+pub mod problems;
 pub mod selection;
@@ -1,2 +1,3 @@
 pub mod genome_mutation;
+pub mod neat_mutation;
 pub mod neural_mutation;
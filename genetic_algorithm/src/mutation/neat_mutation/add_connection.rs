@@ -0,0 +1,108 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use crate::neat::innovation::InnovationTracker;
+use crate::neat::neat_genome::{NeatGenome, MAX_WEIGHT, MIN_WEIGHT};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Connects two random unconnected nodes with a random weight. Genomes that are already fully connected are left as is.
+pub struct AddConnection {
+    add_connection_mutation_rate: f64,
+    tracker: InnovationTracker,
+    seed: StdRng,
+}
+
+impl AddConnection {
+    pub fn new(
+        add_connection_mutation_rate: f64,
+        tracker: InnovationTracker,
+        seed: [u8; 32],
+    ) -> AddConnection {
+        AddConnection {
+            add_connection_mutation_rate,
+            tracker,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Mutate for AddConnection {
+    type T = NeatGenome;
+
+    fn mutate(
+        &mut self,
+        population: &Population<NeatGenome>,
+        mut fitness_function: Box<dyn FitnessFunction<T = NeatGenome>>,
+    ) -> Vec<Individual<NeatGenome>> {
+        let mut new_population = Vec::new();
+
+        for individual in population.list_of_individuals().iter() {
+            if self.seed.gen::<f64>() > self.add_connection_mutation_rate {
+                new_population.push(individual.clone());
+                continue;
+            }
+            let mut mutated_genome = individual.retrieve_individual().clone();
+            let possible_connections = mutated_genome.possible_new_connections();
+            if possible_connections.is_empty() {
+                new_population.push(individual.clone());
+                continue;
+            }
+
+            let (in_node, out_node) =
+                possible_connections[self.seed.gen_range(0, possible_connections.len())];
+            let weight = self.seed.gen_range(MIN_WEIGHT, MAX_WEIGHT);
+            mutated_genome.add_connection(in_node, out_node, weight, &self.tracker);
+
+            let new_fitness = fitness_function.calculate_fitness(&mutated_genome);
+            new_population.push(Individual::new(mutated_genome, new_fitness));
+        }
+
+        new_population
+    }
+}
+
+#[cfg(test)]
+mod add_connection_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::neat_mutation::add_connection::AddConnection;
+    use crate::neat::innovation::InnovationTracker;
+    use crate::neat::neat_genome::NeatGenome;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeatFitnessFunction;
+    impl FitnessFunction for TestNeatFitnessFunction {
+        type T = NeatGenome;
+
+        fn calculate_fitness(&mut self, genome: &NeatGenome) -> f64 {
+            genome.connections().len() as f64
+        }
+    }
+
+    #[test]
+    fn mutation_add_connection_test() {
+        let tracker = InnovationTracker::new();
+        let mut genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+        genome.split_connection(0, &tracker);
+        let fitness_function = Box::new(TestNeatFitnessFunction::default());
+        let mut new_pop = Population::new(vec![Individual::new(genome, 5.0)], ProblemType::Max);
+
+        let mut add_connection = AddConnection::new(1.0, tracker.clone(), *DEFAULT_SEED);
+        new_pop.mutate(&mut add_connection, fitness_function.clone());
+        assert_eq!(*new_pop.list_of_individuals()[0].fitness(), 6.0);
+
+        // Only two connections were possible, after that the genome stays the same.
+        new_pop.mutate(&mut add_connection, fitness_function.clone());
+        new_pop.mutate(&mut add_connection, fitness_function.clone());
+        assert_eq!(*new_pop.list_of_individuals()[0].fitness(), 7.0);
+        assert!(new_pop.list_of_individuals()[0]
+            .retrieve_individual()
+            .possible_new_connections()
+            .is_empty());
+    }
+}
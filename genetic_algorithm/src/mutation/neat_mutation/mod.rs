@@ -0,0 +1,4 @@
+pub mod add_connection;
+pub mod perturb_connection_weights;
+pub mod split_connection;
+pub mod toggle_connection;
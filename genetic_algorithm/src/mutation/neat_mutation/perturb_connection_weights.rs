@@ -0,0 +1,118 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use crate::neat::neat_genome::{NeatGenome, MAX_WEIGHT, MIN_WEIGHT};
+use rand::distributions::StandardNormal;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Same as PerturbWeights for the NeuralNetwork: every connection weight is first checked against the reset rate and
+// replaced by a new random weight, otherwise it is checked against the perturb rate and gets gaussian noise added.
+pub struct PerturbConnectionWeights {
+    perturb_rate: f64,
+    sigma: f64,
+    reset_rate: f64,
+    seed: StdRng,
+}
+
+impl PerturbConnectionWeights {
+    pub fn new(
+        perturb_rate: f64,
+        sigma: f64,
+        reset_rate: f64,
+        seed: [u8; 32],
+    ) -> PerturbConnectionWeights {
+        PerturbConnectionWeights {
+            perturb_rate,
+            sigma,
+            reset_rate,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Mutate for PerturbConnectionWeights {
+    type T = NeatGenome;
+
+    fn mutate(
+        &mut self,
+        population: &Population<NeatGenome>,
+        mut fitness_function: Box<dyn FitnessFunction<T = NeatGenome>>,
+    ) -> Vec<Individual<NeatGenome>> {
+        let mut new_population = Vec::new();
+
+        for individual in population.list_of_individuals().iter() {
+            let mut mutated_genome = individual.retrieve_individual().clone();
+            let mut is_mutated = false;
+
+            for weight in mutated_genome.weights_mut() {
+                if self.seed.gen::<f64>() < self.reset_rate {
+                    *weight = self.seed.gen_range(MIN_WEIGHT, MAX_WEIGHT);
+                    is_mutated = true;
+                } else if self.seed.gen::<f64>() < self.perturb_rate {
+                    *weight += self.sigma * self.seed.sample::<f64, _>(StandardNormal);
+                    is_mutated = true;
+                }
+            }
+
+            if !is_mutated {
+                new_population.push(individual.clone());
+                continue;
+            }
+
+            let new_fitness = fitness_function.calculate_fitness(&mutated_genome);
+            new_population.push(Individual::new(mutated_genome, new_fitness));
+        }
+
+        new_population
+    }
+}
+
+#[cfg(test)]
+mod perturb_connection_weights_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::neat_mutation::perturb_connection_weights::PerturbConnectionWeights;
+    use crate::neat::innovation::InnovationTracker;
+    use crate::neat::neat_genome::NeatGenome;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeatFitnessFunction;
+    impl FitnessFunction for TestNeatFitnessFunction {
+        type T = NeatGenome;
+
+        fn calculate_fitness(&mut self, _: &NeatGenome) -> f64 {
+            2.0
+        }
+    }
+
+    #[test]
+    fn mutation_perturb_connection_weights_test() {
+        let tracker = InnovationTracker::new();
+        let genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+        let fitness_function = Box::new(TestNeatFitnessFunction::default());
+        let mut new_pop =
+            Population::new(vec![Individual::new(genome.clone(), 1.0)], ProblemType::Max);
+
+        let mut perturb = PerturbConnectionWeights::new(0.0, 0.5, 0.0, *DEFAULT_SEED);
+        new_pop.mutate(&mut perturb, fitness_function.clone());
+        assert_eq!(*new_pop.list_of_individuals()[0].fitness(), 1.0);
+
+        let mut perturb = PerturbConnectionWeights::new(1.0, 0.5, 0.0, *DEFAULT_SEED);
+        new_pop.mutate(&mut perturb, fitness_function.clone());
+        let mutated_genome = new_pop.list_of_individuals()[0].retrieve_individual();
+        assert_eq!(*new_pop.list_of_individuals()[0].fitness(), 2.0);
+        for (connection, original) in mutated_genome
+            .connections()
+            .iter()
+            .zip(genome.connections().iter())
+        {
+            assert_eq!(connection.innovation, original.innovation);
+            assert_ne!(connection.weight, original.weight);
+        }
+    }
+}
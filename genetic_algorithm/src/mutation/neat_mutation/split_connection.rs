@@ -0,0 +1,116 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use crate::neat::innovation::InnovationTracker;
+use crate::neat::neat_genome::NeatGenome;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// NEAT add node mutation: a random enabled connection is split in two by a new hidden node.
+pub struct SplitConnection {
+    split_connection_mutation_rate: f64,
+    tracker: InnovationTracker,
+    seed: StdRng,
+}
+
+impl SplitConnection {
+    pub fn new(
+        split_connection_mutation_rate: f64,
+        tracker: InnovationTracker,
+        seed: [u8; 32],
+    ) -> SplitConnection {
+        SplitConnection {
+            split_connection_mutation_rate,
+            tracker,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Mutate for SplitConnection {
+    type T = NeatGenome;
+
+    fn mutate(
+        &mut self,
+        population: &Population<NeatGenome>,
+        mut fitness_function: Box<dyn FitnessFunction<T = NeatGenome>>,
+    ) -> Vec<Individual<NeatGenome>> {
+        let mut new_population = Vec::new();
+
+        for individual in population.list_of_individuals().iter() {
+            if self.seed.gen::<f64>() > self.split_connection_mutation_rate {
+                new_population.push(individual.clone());
+                continue;
+            }
+            let mut mutated_genome = individual.retrieve_individual().clone();
+            let enabled_connections: Vec<usize> = (0..mutated_genome.connections().len())
+                .filter(|&index| mutated_genome.connections()[index].enabled)
+                .collect();
+            if enabled_connections.is_empty() {
+                new_population.push(individual.clone());
+                continue;
+            }
+
+            let connection_index =
+                enabled_connections[self.seed.gen_range(0, enabled_connections.len())];
+            mutated_genome.split_connection(connection_index, &self.tracker);
+
+            let new_fitness = fitness_function.calculate_fitness(&mutated_genome);
+            new_population.push(Individual::new(mutated_genome, new_fitness));
+        }
+
+        new_population
+    }
+}
+
+#[cfg(test)]
+mod split_connection_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::neat_mutation::split_connection::SplitConnection;
+    use crate::neat::innovation::InnovationTracker;
+    use crate::neat::neat_genome::NeatGenome;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeatFitnessFunction;
+    impl FitnessFunction for TestNeatFitnessFunction {
+        type T = NeatGenome;
+
+        fn calculate_fitness(&mut self, genome: &NeatGenome) -> f64 {
+            genome.number_of_hidden_nodes() as f64
+        }
+    }
+
+    #[test]
+    fn mutation_split_connection_test() {
+        let tracker = InnovationTracker::new();
+        let genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+        let fitness_function = Box::new(TestNeatFitnessFunction::default());
+        let mut new_pop = Population::new(
+            vec![
+                Individual::new(genome.clone(), 0.0),
+                Individual::new(genome, 0.0),
+            ],
+            ProblemType::Max,
+        );
+
+        let mut split_connection = SplitConnection::new(0.0, tracker.clone(), *DEFAULT_SEED);
+        new_pop.mutate(&mut split_connection, fitness_function.clone());
+        assert_eq!(*new_pop.list_of_individuals()[0].fitness(), 0.0);
+
+        let mut split_connection = SplitConnection::new(1.0, tracker.clone(), *DEFAULT_SEED);
+        for _ in 0..3 {
+            new_pop.mutate(&mut split_connection, fitness_function.clone());
+        }
+        for individual in new_pop.list_of_individuals().iter() {
+            let genome = individual.retrieve_individual();
+            assert_eq!(*individual.fitness(), 3.0);
+            assert_eq!(genome.connections().len(), 9);
+            assert_eq!(genome.feedforward(&[0.5, 0.5]).len(), 1);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use crate::neat::neat_genome::NeatGenome;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Flips the enabled flag of a random connection, so disabled genes can come back to life.
+pub struct ToggleConnection {
+    toggle_connection_mutation_rate: f64,
+    seed: StdRng,
+}
+
+impl ToggleConnection {
+    pub fn new(toggle_connection_mutation_rate: f64, seed: [u8; 32]) -> ToggleConnection {
+        ToggleConnection {
+            toggle_connection_mutation_rate,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Mutate for ToggleConnection {
+    type T = NeatGenome;
+
+    fn mutate(
+        &mut self,
+        population: &Population<NeatGenome>,
+        mut fitness_function: Box<dyn FitnessFunction<T = NeatGenome>>,
+    ) -> Vec<Individual<NeatGenome>> {
+        let mut new_population = Vec::new();
+
+        for individual in population.list_of_individuals().iter() {
+            if self.seed.gen::<f64>() > self.toggle_connection_mutation_rate {
+                new_population.push(individual.clone());
+                continue;
+            }
+            let mut mutated_genome = individual.retrieve_individual().clone();
+            let connection_index = self.seed.gen_range(0, mutated_genome.connections().len());
+            let enabled = mutated_genome.connections()[connection_index].enabled;
+            mutated_genome.set_connection_enabled(connection_index, !enabled);
+
+            let new_fitness = fitness_function.calculate_fitness(&mutated_genome);
+            new_population.push(Individual::new(mutated_genome, new_fitness));
+        }
+
+        new_population
+    }
+}
+
+#[cfg(test)]
+mod toggle_connection_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::neat_mutation::toggle_connection::ToggleConnection;
+    use crate::neat::innovation::InnovationTracker;
+    use crate::neat::neat_genome::NeatGenome;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeatFitnessFunction;
+    impl FitnessFunction for TestNeatFitnessFunction {
+        type T = NeatGenome;
+
+        fn calculate_fitness(&mut self, genome: &NeatGenome) -> f64 {
+            genome
+                .connections()
+                .iter()
+                .filter(|connection| connection.enabled)
+                .count() as f64
+        }
+    }
+
+    #[test]
+    fn mutation_toggle_connection_test() {
+        let tracker = InnovationTracker::new();
+        let genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+        let fitness_function = Box::new(TestNeatFitnessFunction::default());
+        let mut new_pop = Population::new(vec![Individual::new(genome, 3.0)], ProblemType::Max);
+
+        let mut toggle_connection = ToggleConnection::new(1.0, *DEFAULT_SEED);
+        new_pop.mutate(&mut toggle_connection, fitness_function.clone());
+        assert_eq!(*new_pop.list_of_individuals()[0].fitness(), 2.0);
+    }
+}
@@ -4,25 +4,50 @@ use crate::mutation::genome_mutation::Mutate;
 use crate::neural_network::neural_network::NeuralNetwork;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 // A node with a new hidden layer is guaranteed to be added (as long as the mutation rate just for add node is met) if the neural network currently has 0 hidden nodes.
 // If the neural network already has a hidden node/layer, then both add a new node and add a new layer have to met to create a new hidden layer with a lone node.
 pub struct AddNode {
     add_node_mutation_rate: f64,
     add_layer_mutation_rate: f64,
+    layer_insertion: LayerInsertion,
     seed: StdRng,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum LayerInsertion {
+    // A new layer with a single node is pushed right before the outputs.
+    AtEnd,
+    // An identity layer is inserted at a random depth, see NeuralNetwork::insert_hidden_layer. The outputs of the network
+    // stay the same, so adding a layer this way is never destructive.
+    IdentityAtRandomDepth,
+}
+
 impl AddNode {
     pub fn new(
         add_node_mutation_rate: f64,
         add_layer_mutation_rate: f64,
         seed: [u8; 32],
+    ) -> AddNode {
+        AddNode::with_layer_insertion(
+            add_node_mutation_rate,
+            add_layer_mutation_rate,
+            LayerInsertion::AtEnd,
+            seed,
+        )
+    }
+
+    pub fn with_layer_insertion(
+        add_node_mutation_rate: f64,
+        add_layer_mutation_rate: f64,
+        layer_insertion: LayerInsertion,
+        seed: [u8; 32],
     ) -> AddNode {
         AddNode {
             add_node_mutation_rate,
             add_layer_mutation_rate,
+            layer_insertion,
             seed: SeedableRng::from_seed(seed),
         }
     }
@@ -51,10 +76,20 @@ impl Mutate for AddNode {
             }
 
             if add_layer {
-                mutated_neural_net.push_hidden_node_with_new_layer_at_end();
+                match self.layer_insertion {
+                    LayerInsertion::AtEnd => {
+                        mutated_neural_net.push_hidden_node_with_new_layer_at_end(&mut self.seed)
+                    }
+                    LayerInsertion::IdentityAtRandomDepth => {
+                        let layer_index = self
+                            .seed
+                            .gen_range(0, mutated_neural_net.hidden_length() + 1);
+                        mutated_neural_net.insert_hidden_layer(layer_index);
+                    }
+                }
             } else {
-                mutated_neural_net
-                    .push_hidden_node(self.seed.gen_range(0, mutated_neural_net.hidden_length()));
+                let layer_index = self.seed.gen_range(0, mutated_neural_net.hidden_length());
+                mutated_neural_net.push_hidden_node(layer_index, &mut self.seed);
             }
 
             let new_fitness = fitness_function.calculate_fitness(&mutated_neural_net);
@@ -70,7 +105,7 @@ impl Mutate for AddNode {
 mod add_node_test {
     use crate::genome::fitness_function::FitnessFunction;
     use crate::genome::population::{Individual, Population, ProblemType};
-    use crate::mutation::neural_mutation::add_node::AddNode;
+    use crate::mutation::neural_mutation::add_node::{AddNode, LayerInsertion};
     use crate::neural_network::neural_network::NeuralNetwork;
 
     const DEFAULT_SEED: &[u8; 32] = &[
@@ -146,4 +181,26 @@ mod add_node_test {
         let net = new_pop.list_of_individuals()[0].retrieve_individual();
         assert_eq!(net.hidden_layer_length(0), 2);
     }
+
+    #[test]
+    fn mutation_add_identity_layer_test() {
+        let xs: [u32; 2] = [3, 2];
+        let net = NeuralNetwork::new(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        let outputs = net.feedforward(&[0.3, -0.7]);
+        let fitness_function = Box::new(TestNeuralNetworkFitnessFunction::default());
+        let mut new_pop = Population::new(vec![Individual::new(net, 1.0)], ProblemType::Max);
+
+        let mut add_node = AddNode::with_layer_insertion(
+            1.0,
+            1.0,
+            LayerInsertion::IdentityAtRandomDepth,
+            *DEFAULT_SEED,
+        );
+        for _ in 0..3 {
+            new_pop.mutate(&mut add_node, fitness_function.clone());
+        }
+        let net = new_pop.list_of_individuals()[0].retrieve_individual();
+        assert_eq!(net.hidden_length(), 5);
+        assert_eq!(net.feedforward(&[0.3, -0.7]), outputs);
+    }
 }
@@ -0,0 +1,176 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use crate::neural_network::neural_network::NeuralNetwork;
+use crate::neural_network::trainer::Trainer;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Inheritance {
+    // The trained weights replace the weights of the individual.
+    Lamarckian,
+    // The individual keeps its weights and only gets the fitness it reaches after training.
+    Baldwinian,
+}
+
+// Memetic step: every individual is, with the training rate, fine tuned with backpropagation on the given samples
+// before its fitness is calculated.
+pub struct BackpropTraining {
+    training_rate: f64,
+    trainer: Trainer,
+    inputs: Vec<Vec<f64>>,
+    targets: Vec<Vec<f64>>,
+    inheritance: Inheritance,
+    seed: StdRng,
+}
+
+impl BackpropTraining {
+    pub fn new(
+        training_rate: f64,
+        trainer: Trainer,
+        inputs: Vec<Vec<f64>>,
+        targets: Vec<Vec<f64>>,
+        inheritance: Inheritance,
+        seed: [u8; 32],
+    ) -> BackpropTraining {
+        if inputs.len() != targets.len() {
+            panic!(
+                "Got {} inputs but {} targets, every input needs a target.",
+                inputs.len(),
+                targets.len()
+            );
+        }
+        BackpropTraining {
+            training_rate,
+            trainer,
+            inputs,
+            targets,
+            inheritance,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Mutate for BackpropTraining {
+    type T = NeuralNetwork;
+
+    fn mutate(
+        &mut self,
+        population: &Population<NeuralNetwork>,
+        mut fitness_function: Box<dyn FitnessFunction<T = NeuralNetwork>>,
+    ) -> Vec<Individual<NeuralNetwork>> {
+        let mut new_population = Vec::new();
+
+        for individual in population.list_of_individuals().iter() {
+            if self.seed.gen::<f64>() > self.training_rate {
+                new_population.push(individual.clone());
+                continue;
+            }
+            let mut trained_neural_net = individual.retrieve_individual().clone();
+            self.trainer
+                .train(&mut trained_neural_net, &self.inputs, &self.targets);
+
+            let new_fitness = fitness_function.calculate_fitness(&trained_neural_net);
+            let new_individual = match self.inheritance {
+                Inheritance::Lamarckian => Individual::new(trained_neural_net, new_fitness),
+                Inheritance::Baldwinian => {
+                    Individual::new(individual.retrieve_individual().clone(), new_fitness)
+                }
+            };
+            new_population.push(new_individual);
+        }
+
+        new_population
+    }
+}
+
+#[cfg(test)]
+mod backprop_training_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::neural_mutation::backprop_training::{BackpropTraining, Inheritance};
+    use crate::neural_network::activation::Activation;
+    use crate::neural_network::neural_network::NeuralNetwork;
+    use crate::neural_network::trainer::{Loss, Optimizer, Trainer};
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    // Mean squared error on y = x / 2.
+    #[derive(Clone, Debug)]
+    struct TestNeuralNetworkFitnessFunction;
+    impl FitnessFunction for TestNeuralNetworkFitnessFunction {
+        type T = NeuralNetwork;
+
+        fn calculate_fitness(&mut self, net: &NeuralNetwork) -> f64 {
+            create_samples()
+                .0
+                .iter()
+                .map(|inputs| (net.feedforward(inputs)[0] - inputs[0] / 2.0).powi(2))
+                .sum::<f64>()
+        }
+    }
+
+    fn create_samples() -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        let inputs: Vec<Vec<f64>> = (0..8).map(|x| vec![x as f64 / 8.0]).collect();
+        let targets = inputs.iter().map(|inputs| vec![inputs[0] / 2.0]).collect();
+        (inputs, targets)
+    }
+
+    fn create_population() -> Population<NeuralNetwork> {
+        let mut net = NeuralNetwork::new(1, &[2], 1, *DEFAULT_SEED);
+        net.set_output_activation(Activation::Linear);
+        let fitness = TestNeuralNetworkFitnessFunction.calculate_fitness(&net);
+        Population::new(vec![Individual::new(net, fitness)], ProblemType::Min)
+    }
+
+    fn create_training(inheritance: Inheritance) -> BackpropTraining {
+        let (inputs, targets) = create_samples();
+        let trainer = Trainer::new(
+            Optimizer::Sgd {
+                learning_rate: 0.1,
+                momentum: 0.5,
+            },
+            Loss::MeanSquaredError,
+            50,
+            1,
+            *DEFAULT_SEED,
+        );
+        BackpropTraining::new(1.0, trainer, inputs, targets, inheritance, *DEFAULT_SEED)
+    }
+
+    #[test]
+    fn mutation_lamarckian_training_test() {
+        let mut new_pop = create_population();
+        let original_fitness = *new_pop.list_of_individuals()[0].fitness();
+
+        let mut training = create_training(Inheritance::Lamarckian);
+        new_pop.mutate(&mut training, Box::new(TestNeuralNetworkFitnessFunction));
+        let individual = &new_pop.list_of_individuals()[0];
+
+        assert!(*individual.fitness() < original_fitness);
+        assert_eq!(
+            *individual.fitness(),
+            TestNeuralNetworkFitnessFunction.calculate_fitness(individual.retrieve_individual())
+        );
+    }
+
+    #[test]
+    fn mutation_baldwinian_training_test() {
+        let mut new_pop = create_population();
+        let original_fitness = *new_pop.list_of_individuals()[0].fitness();
+        let original_weights = new_pop.list_of_individuals()[0]
+            .retrieve_individual()
+            .weights();
+
+        let mut training = create_training(Inheritance::Baldwinian);
+        new_pop.mutate(&mut training, Box::new(TestNeuralNetworkFitnessFunction));
+        let individual = &new_pop.list_of_individuals()[0];
+
+        assert!(*individual.fitness() < original_fitness);
+        assert_eq!(individual.retrieve_individual().weights(), original_weights);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use crate::neural_network::activation::Activation;
+use crate::neural_network::neural_network::NeuralNetwork;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Picks a random layer and gives it a random activation from the candidates.
+// The output layer is only a candidate layer when include_output_layer is set, since fitness functions usually depend on
+// the range of the outputs.
+pub struct ChangeActivation {
+    change_activation_mutation_rate: f64,
+    candidates: Vec<Activation>,
+    include_output_layer: bool,
+    seed: StdRng,
+}
+
+impl ChangeActivation {
+    pub fn new(
+        change_activation_mutation_rate: f64,
+        candidates: Vec<Activation>,
+        include_output_layer: bool,
+        seed: [u8; 32],
+    ) -> ChangeActivation {
+        if candidates.is_empty() {
+            panic!("Please give at least one candidate activation.");
+        }
+        ChangeActivation {
+            change_activation_mutation_rate,
+            candidates,
+            include_output_layer,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Mutate for ChangeActivation {
+    type T = NeuralNetwork;
+
+    fn mutate(
+        &mut self,
+        population: &Population<NeuralNetwork>,
+        mut fitness_function: Box<dyn FitnessFunction<T = NeuralNetwork>>,
+    ) -> Vec<Individual<NeuralNetwork>> {
+        let mut new_population = Vec::new();
+
+        for individual in population.list_of_individuals().iter() {
+            let gen_number = self.seed.gen::<f64>();
+            if gen_number > self.change_activation_mutation_rate {
+                new_population.push(individual.clone());
+                continue;
+            }
+            let mut mutated_neural_net = individual.retrieve_individual().clone();
+
+            let number_of_layers = if self.include_output_layer {
+                mutated_neural_net.hidden_length() + 1
+            } else {
+                mutated_neural_net.hidden_length()
+            };
+            if number_of_layers == 0 {
+                new_population.push(individual.clone());
+                continue;
+            }
+
+            let layer_index = self.seed.gen_range(0, number_of_layers);
+            let activation = self.candidates[self.seed.gen_range(0, self.candidates.len())];
+            if layer_index == mutated_neural_net.hidden_length() {
+                mutated_neural_net.set_output_activation(activation);
+            } else {
+                mutated_neural_net.set_hidden_activation(layer_index, activation);
+            }
+
+            let new_fitness = fitness_function.calculate_fitness(&mutated_neural_net);
+            new_population.push(Individual::new(mutated_neural_net, new_fitness));
+        }
+
+        new_population
+    }
+}
+
+#[cfg(test)]
+mod change_activation_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::neural_mutation::change_activation::ChangeActivation;
+    use crate::neural_network::activation::Activation;
+    use crate::neural_network::neural_network::NeuralNetwork;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeuralNetworkFitnessFunction;
+    impl FitnessFunction for TestNeuralNetworkFitnessFunction {
+        type T = NeuralNetwork;
+
+        fn calculate_fitness(&mut self, _: &NeuralNetwork) -> f64 {
+            1.0
+        }
+    }
+
+    #[test]
+    fn mutation_change_activation_test() {
+        let xs: [u32; 1] = [2];
+        let net = NeuralNetwork::new(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        let fitness_function = Box::new(TestNeuralNetworkFitnessFunction::default());
+        let mut new_pop = Population::new(vec![Individual::new(net, 1.0)], ProblemType::Max);
+
+        let mut change_activation =
+            ChangeActivation::new(0.0, vec![Activation::Relu], true, *DEFAULT_SEED);
+        new_pop.mutate(&mut change_activation, fitness_function.clone());
+        let net = new_pop.list_of_individuals()[0].retrieve_individual();
+        assert_eq!(net.hidden_activation(0), Activation::Sigmoid);
+
+        let mut change_activation =
+            ChangeActivation::new(1.0, vec![Activation::Relu], false, *DEFAULT_SEED);
+        new_pop.mutate(&mut change_activation, fitness_function.clone());
+        let net = new_pop.list_of_individuals()[0].retrieve_individual();
+        assert_eq!(net.hidden_activation(0), Activation::Relu);
+        assert_eq!(net.output_activation(), Activation::Sigmoid);
+
+        let mut change_activation =
+            ChangeActivation::new(1.0, vec![Activation::Tanh], true, *DEFAULT_SEED);
+        for _ in 0..10 {
+            new_pop.mutate(&mut change_activation, fitness_function.clone());
+        }
+        let net = new_pop.list_of_individuals()[0].retrieve_individual();
+        assert_eq!(net.hidden_activation(0), Activation::Tanh);
+        assert_eq!(net.output_activation(), Activation::Tanh);
+    }
+
+    #[test]
+    fn mutation_change_activation_no_hidden_layers() {
+        let net = NeuralNetwork::new(2, &[], 1, *DEFAULT_SEED);
+        let fitness_function = Box::new(TestNeuralNetworkFitnessFunction::default());
+        let mut new_pop = Population::new(vec![Individual::new(net, 1.0)], ProblemType::Max);
+
+        let mut change_activation =
+            ChangeActivation::new(1.0, vec![Activation::Linear], false, *DEFAULT_SEED);
+        new_pop.mutate(&mut change_activation, fitness_function);
+        let net = new_pop.list_of_individuals()[0].retrieve_individual();
+        assert_eq!(net.output_activation(), Activation::Sigmoid);
+    }
+}
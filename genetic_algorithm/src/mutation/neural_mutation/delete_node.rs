@@ -8,13 +8,31 @@ use serde::{Deserialize, Serialize};
 
 pub struct DeleteNode {
     delete_node_mutation_rate: f64,
+    node_removal: NodeRemoval,
     seed: StdRng,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum NodeRemoval {
+    // The last node of a random hidden layer is removed.
+    LastNode,
+    // Any node of any hidden layer can be removed, see NeuralNetwork::remove_hidden_node_at.
+    RandomNode,
+}
+
 impl DeleteNode {
     pub fn new(delete_node_mutation_rate: f64, seed: [u8; 32]) -> DeleteNode {
+        DeleteNode::with_node_removal(delete_node_mutation_rate, NodeRemoval::LastNode, seed)
+    }
+
+    pub fn with_node_removal(
+        delete_node_mutation_rate: f64,
+        node_removal: NodeRemoval,
+        seed: [u8; 32],
+    ) -> DeleteNode {
         DeleteNode {
             delete_node_mutation_rate,
+            node_removal,
             seed: SeedableRng::from_seed(seed),
         }
     }
@@ -42,8 +60,22 @@ impl Mutate for DeleteNode {
                 new_population.push(individual.clone());
                 continue;
             }
-            let node_index = self.seed.gen_range(0, mutated_neural_net.hidden_length());
-            mutated_neural_net.remove_hidden_node(node_index);
+            let layer_index = self.seed.gen_range(0, mutated_neural_net.hidden_length());
+            match self.node_removal {
+                NodeRemoval::LastNode => {
+                    mutated_neural_net.remove_hidden_node(layer_index, &mut self.seed)
+                }
+                NodeRemoval::RandomNode => {
+                    let node_index = self
+                        .seed
+                        .gen_range(0, mutated_neural_net.hidden_layer_length(layer_index));
+                    mutated_neural_net.remove_hidden_node_at(
+                        layer_index,
+                        node_index,
+                        &mut self.seed,
+                    );
+                }
+            }
             let new_fitness = fitness_function.calculate_fitness(&mutated_neural_net);
             let new_individual = Individual::new(mutated_neural_net, new_fitness);
             new_population.push(new_individual);
@@ -57,7 +89,7 @@ impl Mutate for DeleteNode {
 mod delete_node_test {
     use crate::genome::fitness_function::FitnessFunction;
     use crate::genome::population::{Individual, Population, ProblemType};
-    use crate::mutation::neural_mutation::delete_node::DeleteNode;
+    use crate::mutation::neural_mutation::delete_node::{DeleteNode, NodeRemoval};
     use crate::neural_network::neural_network::NeuralNetwork;
     use std::convert::AsRef;
 
@@ -109,4 +141,24 @@ mod delete_node_test {
         let net = new_pop.list_of_individuals()[0].retrieve_individual();
         assert_eq!(net.is_hidden_layer_empty(), true);
     }
+
+    #[test]
+    fn mutation_delete_random_node_test() {
+        let xs: [u32; 2] = [4, 3];
+        let net = NeuralNetwork::new(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        let fitness_function = Box::new(TestNeuralNetworkFitnessFunction::default());
+        let mut new_pop = Population::new(vec![Individual::new(net, 1.0)], ProblemType::Max);
+
+        let mut delete_node =
+            DeleteNode::with_node_removal(1.0, NodeRemoval::RandomNode, *DEFAULT_SEED);
+        for _ in 0..4 {
+            new_pop.mutate(&mut delete_node, fitness_function.clone());
+        }
+        let net = new_pop.list_of_individuals()[0].retrieve_individual();
+        let remaining_nodes: usize = (0..net.hidden_length())
+            .map(|layer_index| net.hidden_layer_length(layer_index))
+            .sum();
+        assert_eq!(remaining_nodes, 3);
+        assert_eq!(net.feedforward(&[0.5, 0.5]).len(), 1);
+    }
 }
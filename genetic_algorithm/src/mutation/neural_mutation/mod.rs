@@ -1,2 +1,5 @@
 pub mod add_node;
+pub mod backprop_training;
+pub mod change_activation;
 pub mod delete_node;
+pub mod perturb_weights;
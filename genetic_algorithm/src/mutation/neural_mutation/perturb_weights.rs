@@ -0,0 +1,167 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use crate::neural_network::neural_network::NeuralNetwork;
+use rand::distributions::StandardNormal;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Every connection weight (input, hidden and bias nodes) is checked on its own.
+// A weight is first checked against the reset rate and replaced by a weight drawn with the weight initializer of the
+// network, otherwise it is checked against the perturb rate and gets gaussian noise with the given sigma added to it.
+pub struct PerturbWeights {
+    perturb_rate: f64,
+    sigma: f64,
+    reset_rate: f64,
+    seed: StdRng,
+}
+
+impl PerturbWeights {
+    pub fn new(perturb_rate: f64, sigma: f64, reset_rate: f64, seed: [u8; 32]) -> PerturbWeights {
+        PerturbWeights {
+            perturb_rate,
+            sigma,
+            reset_rate,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Mutate for PerturbWeights {
+    type T = NeuralNetwork;
+
+    fn mutate(
+        &mut self,
+        population: &Population<NeuralNetwork>,
+        mut fitness_function: Box<dyn FitnessFunction<T = NeuralNetwork>>,
+    ) -> Vec<Individual<NeuralNetwork>> {
+        let mut new_population = Vec::new();
+
+        for individual in population.list_of_individuals().iter() {
+            let mut mutated_neural_net = individual.retrieve_individual().clone();
+            let mut is_mutated = false;
+            let initializer = mutated_neural_net.weight_initializer();
+            let fans = mutated_neural_net.weight_fans();
+
+            for (weight, &(fan_in, fan_out)) in mutated_neural_net.weights_mut().zip(fans.iter()) {
+                if self.seed.gen::<f64>() < self.reset_rate {
+                    *weight = initializer.sample(fan_in, fan_out, &mut self.seed);
+                    is_mutated = true;
+                } else if self.seed.gen::<f64>() < self.perturb_rate {
+                    *weight += self.sigma * self.seed.sample::<f64, _>(StandardNormal);
+                    is_mutated = true;
+                }
+            }
+
+            if !is_mutated {
+                new_population.push(individual.clone());
+                continue;
+            }
+
+            let new_fitness = fitness_function.calculate_fitness(&mutated_neural_net);
+            new_population.push(Individual::new(mutated_neural_net, new_fitness));
+        }
+
+        new_population
+    }
+}
+
+#[cfg(test)]
+mod perturb_weights_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::neural_mutation::perturb_weights::PerturbWeights;
+    use crate::neural_network::neural_network::NeuralNetwork;
+    use crate::neural_network::weight_initializer::WeightInitializer;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeuralNetworkFitnessFunction;
+    impl FitnessFunction for TestNeuralNetworkFitnessFunction {
+        type T = NeuralNetwork;
+
+        fn calculate_fitness(&mut self, _: &NeuralNetwork) -> f64 {
+            2.0
+        }
+    }
+
+    #[test]
+    fn mutation_perturb_weights_test() {
+        let xs: [u32; 2] = [3, 2];
+        let net = NeuralNetwork::new(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        let start_weights = net.weights();
+
+        let fitness_function = Box::new(TestNeuralNetworkFitnessFunction::default());
+        let mut new_pop = Population::new(vec![Individual::new(net, 1.0)], ProblemType::Max);
+
+        let mut perturb_weights = PerturbWeights::new(0.0, 1.0, 0.0, *DEFAULT_SEED);
+        new_pop.mutate(&mut perturb_weights, fitness_function.clone());
+        let indv = &new_pop.list_of_individuals()[0];
+        assert_eq!(indv.retrieve_individual().weights(), start_weights);
+        assert_eq!(*indv.fitness(), 1.0);
+
+        let mut perturb_weights = PerturbWeights::new(1.0, 1.0, 0.0, *DEFAULT_SEED);
+        new_pop.mutate(&mut perturb_weights, fitness_function.clone());
+        let indv = &new_pop.list_of_individuals()[0];
+        let weights = indv.retrieve_individual().weights();
+        assert_eq!(weights.len(), start_weights.len());
+        for (weight, start_weight) in weights.iter().zip(start_weights.iter()) {
+            assert_ne!(weight, start_weight);
+        }
+        assert_eq!(*indv.fitness(), 2.0);
+    }
+
+    #[test]
+    fn mutation_reset_weights_test() {
+        let xs: [u32; 1] = [4];
+        let mut net = NeuralNetwork::new(3, xs.as_ref(), 2, *DEFAULT_SEED);
+        let large_weights = vec![5.0; net.weight_count()];
+        net.set_weights(&large_weights);
+
+        let fitness_function = Box::new(TestNeuralNetworkFitnessFunction::default());
+        let mut new_pop = Population::new(vec![Individual::new(net, 1.0)], ProblemType::Max);
+        let mut perturb_weights = PerturbWeights::new(1.0, 1.0, 1.0, *DEFAULT_SEED);
+        new_pop.mutate(&mut perturb_weights, fitness_function);
+
+        // Input, hidden and bias weights are all reset into the initial weight range.
+        for weight in new_pop.list_of_individuals()[0]
+            .retrieve_individual()
+            .weights()
+        {
+            assert!(weight >= -0.1 && weight <= 0.1);
+        }
+    }
+
+    #[test]
+    fn mutation_reset_weights_initializer_test() {
+        let xs: [u32; 1] = [4];
+        let fitness_function = Box::new(TestNeuralNetworkFitnessFunction::default());
+        for &(initializer, min, max) in [
+            (WeightInitializer::Zeros, 0.0, 0.0),
+            (WeightInitializer::Uniform { min: 1.0, max: 2.0 }, 1.0, 2.0),
+            // sqrt(6 / (3 + 4)) bounds the input weights and sqrt(6 / (4 + 2)) = 1 the others.
+            (WeightInitializer::Xavier, -1.0, 1.0),
+        ]
+        .iter()
+        {
+            let mut net =
+                NeuralNetwork::with_initializer(3, xs.as_ref(), 2, initializer, *DEFAULT_SEED);
+            let large_weights = vec![5.0; net.weight_count()];
+            net.set_weights(&large_weights);
+
+            let mut new_pop = Population::new(vec![Individual::new(net, 1.0)], ProblemType::Max);
+            let mut perturb_weights = PerturbWeights::new(0.0, 1.0, 1.0, *DEFAULT_SEED);
+            new_pop.mutate(&mut perturb_weights, fitness_function.clone());
+
+            for weight in new_pop.list_of_individuals()[0]
+                .retrieve_individual()
+                .weights()
+            {
+                assert!((min..=max).contains(&weight));
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// Hands out the historical markings of a NEAT run. The same structural change (a connection between the same two nodes,
+// or the split of the same connection) always gets the same innovation number and node id, no matter which genome makes
+// it. Clones share their state, so give a clone of one tracker to every genome and operator of a run.
+#[derive(Clone, Debug, Default)]
+pub struct InnovationTracker {
+    state: Arc<Mutex<InnovationState>>,
+}
+
+#[derive(Debug, Default)]
+struct InnovationState {
+    next_innovation: u64,
+    next_node_id: u64,
+    connections: HashMap<(u64, u64), u64>,
+    splits: HashMap<u64, u64>,
+}
+
+impl InnovationTracker {
+    pub fn new() -> InnovationTracker {
+        InnovationTracker::default()
+    }
+
+    pub fn connection_innovation(&self, in_node: u64, out_node: u64) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        if let Some(innovation) = state.connections.get(&(in_node, out_node)) {
+            return *innovation;
+        }
+        let innovation = state.next_innovation;
+        state.next_innovation += 1;
+        state.connections.insert((in_node, out_node), innovation);
+        innovation
+    }
+
+    // Id of the node that is created when the connection with the given innovation number is split.
+    pub fn split_node_id(&self, connection_innovation: u64) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        if let Some(node_id) = state.splits.get(&connection_innovation) {
+            return *node_id;
+        }
+        let node_id = state.next_node_id;
+        state.next_node_id += 1;
+        state.splits.insert(connection_innovation, node_id);
+        node_id
+    }
+
+    // A node id that has never been handed out before. Used when a genome splits a connection it already split once.
+    pub fn new_node_id(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let node_id = state.next_node_id;
+        state.next_node_id += 1;
+        node_id
+    }
+
+    // Makes sure the ids below number_of_nodes, which are used by the input, bias and output nodes, are never handed out.
+    pub fn reserve_node_ids(&self, number_of_nodes: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.next_node_id = state.next_node_id.max(number_of_nodes);
+    }
+
+    pub fn number_of_innovations(&self) -> u64 {
+        self.state.lock().unwrap().next_innovation
+    }
+}
+
+#[cfg(test)]
+mod innovation_test {
+    use crate::neat::innovation::InnovationTracker;
+
+    #[test]
+    fn innovation_tracker_test() {
+        let tracker = InnovationTracker::new();
+        let shared_tracker = tracker.clone();
+
+        assert_eq!(tracker.connection_innovation(0, 3), 0);
+        assert_eq!(tracker.connection_innovation(1, 3), 1);
+        assert_eq!(shared_tracker.connection_innovation(0, 3), 0);
+        assert_eq!(shared_tracker.number_of_innovations(), 2);
+
+        tracker.reserve_node_ids(4);
+        assert_eq!(tracker.split_node_id(1), 4);
+        assert_eq!(shared_tracker.split_node_id(1), 4);
+        assert_eq!(tracker.new_node_id(), 5);
+        assert_eq!(tracker.split_node_id(0), 6);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod innovation;
+pub mod neat_genome;
+pub mod speciation;
@@ -0,0 +1,607 @@
+use crate::neat::innovation::InnovationTracker;
+use crate::neat::speciation::CompatibilityConfig;
+use crate::neural_network::activation::Activation;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+const BIAS_VALUE: f64 = 1.0;
+pub(crate) const MIN_WEIGHT: f64 = -1.0;
+pub(crate) const MAX_WEIGHT: f64 = 1.0;
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NodeKind {
+    Input,
+    Bias,
+    Hidden,
+    Output,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeGene {
+    pub id: u64,
+    pub kind: NodeKind,
+    pub activation: Activation,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionGene {
+    pub innovation: u64,
+    pub in_node: u64,
+    pub out_node: u64,
+    pub weight: f64,
+    pub enabled: bool,
+}
+
+// Graph genome of a feedforward network. Nodes are numbered inputs first, then the bias node, then the outputs; hidden
+// nodes get their ids from the InnovationTracker. Connections never form a cycle, disabled ones included, so any
+// connection can be enabled again safely.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NeatGenome {
+    num_inputs: usize,
+    num_outputs: usize,
+    // Sorted by id.
+    nodes: Vec<NodeGene>,
+    // Sorted by innovation number.
+    connections: Vec<ConnectionGene>,
+}
+
+impl NeatGenome {
+    // Starts minimal: the inputs and the bias node are connected straight to every output.
+    pub fn new(
+        num_inputs: u32,
+        num_outputs: u32,
+        tracker: &InnovationTracker,
+        seed: [u8; 32],
+    ) -> NeatGenome {
+        if num_inputs == 0 || num_outputs == 0 {
+            panic!("A NEAT genome needs at least one input and one output.");
+        }
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let num_inputs = num_inputs as usize;
+        let num_outputs = num_outputs as usize;
+        tracker.reserve_node_ids((num_inputs + 1 + num_outputs) as u64);
+
+        let mut nodes = Vec::with_capacity(num_inputs + 1 + num_outputs);
+        for id in 0..num_inputs {
+            nodes.push(NodeGene {
+                id: id as u64,
+                kind: NodeKind::Input,
+                activation: Activation::Linear,
+            });
+        }
+        nodes.push(NodeGene {
+            id: num_inputs as u64,
+            kind: NodeKind::Bias,
+            activation: Activation::Linear,
+        });
+        for id in num_inputs + 1..num_inputs + 1 + num_outputs {
+            nodes.push(NodeGene {
+                id: id as u64,
+                kind: NodeKind::Output,
+                activation: Activation::default(),
+            });
+        }
+
+        let mut connections = Vec::with_capacity((num_inputs + 1) * num_outputs);
+        for out_node in num_inputs + 1..num_inputs + 1 + num_outputs {
+            for in_node in 0..=num_inputs {
+                connections.push(ConnectionGene {
+                    innovation: tracker.connection_innovation(in_node as u64, out_node as u64),
+                    in_node: in_node as u64,
+                    out_node: out_node as u64,
+                    weight: rng.gen_range(MIN_WEIGHT, MAX_WEIGHT),
+                    enabled: true,
+                });
+            }
+        }
+        connections.sort_by_key(|connection| connection.innovation);
+
+        NeatGenome {
+            num_inputs,
+            num_outputs,
+            nodes,
+            connections,
+        }
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    pub fn num_outputs(&self) -> usize {
+        self.num_outputs
+    }
+
+    pub fn nodes(&self) -> &Vec<NodeGene> {
+        &self.nodes
+    }
+
+    pub fn connections(&self) -> &Vec<ConnectionGene> {
+        &self.connections
+    }
+
+    pub fn number_of_hidden_nodes(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Hidden)
+            .count()
+    }
+
+    pub fn weights_mut(&mut self) -> impl Iterator<Item = &mut f64> {
+        self.connections
+            .iter_mut()
+            .map(|connection| &mut connection.weight)
+    }
+
+    pub fn set_connection_enabled(&mut self, connection_index: usize, enabled: bool) {
+        self.connections[connection_index].enabled = enabled;
+    }
+
+    pub fn node(&self, id: u64) -> Option<&NodeGene> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+            .map(|index| &self.nodes[index])
+    }
+
+    pub fn has_connection(&self, in_node: u64, out_node: u64) -> bool {
+        self.connections
+            .iter()
+            .any(|connection| connection.in_node == in_node && connection.out_node == out_node)
+    }
+
+    // A connection from in_node to out_node creates a cycle when in_node can already be reached from out_node.
+    pub fn creates_cycle(&self, in_node: u64, out_node: u64) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![out_node];
+        while let Some(node) = stack.pop() {
+            if node == in_node {
+                return true;
+            }
+            if !visited.insert(node) {
+                continue;
+            }
+            for connection in self.connections.iter() {
+                if connection.in_node == node {
+                    stack.push(connection.out_node);
+                }
+            }
+        }
+        false
+    }
+
+    // Pairs of nodes that can be connected without creating a duplicate connection or a cycle. Connections always start
+    // at an input, bias or hidden node and end at a hidden or output node.
+    pub fn possible_new_connections(&self) -> Vec<(u64, u64)> {
+        let mut possible_connections = Vec::new();
+        for in_node in self
+            .nodes
+            .iter()
+            .filter(|node| node.kind != NodeKind::Output)
+        {
+            for out_node in self
+                .nodes
+                .iter()
+                .filter(|node| node.kind == NodeKind::Hidden || node.kind == NodeKind::Output)
+            {
+                if !self.has_connection(in_node.id, out_node.id)
+                    && !self.creates_cycle(in_node.id, out_node.id)
+                {
+                    possible_connections.push((in_node.id, out_node.id));
+                }
+            }
+        }
+        possible_connections
+    }
+
+    // Returns false and leaves the genome untouched when the connection is not in possible_new_connections.
+    pub fn add_connection(
+        &mut self,
+        in_node: u64,
+        out_node: u64,
+        weight: f64,
+        tracker: &InnovationTracker,
+    ) -> bool {
+        let valid_in_node = match self.node(in_node) {
+            Some(node) => node.kind != NodeKind::Output,
+            None => false,
+        };
+        let valid_out_node = match self.node(out_node) {
+            Some(node) => node.kind == NodeKind::Hidden || node.kind == NodeKind::Output,
+            None => false,
+        };
+        if !valid_in_node
+            || !valid_out_node
+            || self.has_connection(in_node, out_node)
+            || self.creates_cycle(in_node, out_node)
+        {
+            return false;
+        }
+
+        self.insert_connection(ConnectionGene {
+            innovation: tracker.connection_innovation(in_node, out_node),
+            in_node,
+            out_node,
+            weight,
+            enabled: true,
+        });
+        true
+    }
+
+    // Splits an enabled connection with a new hidden node and returns its id. The old connection is disabled, the
+    // connection into the new node gets weight 1 and the connection out of it keeps the old weight.
+    pub fn split_connection(
+        &mut self,
+        connection_index: usize,
+        tracker: &InnovationTracker,
+    ) -> u64 {
+        let connection = self.connections[connection_index];
+        if !connection.enabled {
+            panic!("Only enabled connections can be split.");
+        }
+        let mut node_id = tracker.split_node_id(connection.innovation);
+        if self.node(node_id).is_some() {
+            // This genome already split this connection once before.
+            node_id = tracker.new_node_id();
+        }
+
+        self.connections[connection_index].enabled = false;
+        self.insert_node(NodeGene {
+            id: node_id,
+            kind: NodeKind::Hidden,
+            activation: Activation::default(),
+        });
+        self.insert_connection(ConnectionGene {
+            innovation: tracker.connection_innovation(connection.in_node, node_id),
+            in_node: connection.in_node,
+            out_node: node_id,
+            weight: 1.0,
+            enabled: true,
+        });
+        self.insert_connection(ConnectionGene {
+            innovation: tracker.connection_innovation(node_id, connection.out_node),
+            in_node: node_id,
+            out_node: connection.out_node,
+            weight: connection.weight,
+            enabled: true,
+        });
+        node_id
+    }
+
+    // Evaluates the nodes in topological order over the enabled connections.
+    pub fn feedforward(&self, inputs: &[f64]) -> Vec<f64> {
+        if inputs.len() != self.num_inputs {
+            panic!(
+                "The genome has {} inputs but {} were given.",
+                self.num_inputs,
+                inputs.len()
+            );
+        }
+
+        let mut outgoing: HashMap<u64, Vec<&ConnectionGene>> = HashMap::new();
+        let mut in_degree: HashMap<u64, usize> =
+            self.nodes.iter().map(|node| (node.id, 0)).collect();
+        for connection in self
+            .connections
+            .iter()
+            .filter(|connection| connection.enabled)
+        {
+            outgoing
+                .entry(connection.in_node)
+                .or_insert_with(Vec::new)
+                .push(connection);
+            *in_degree.get_mut(&connection.out_node).unwrap() += 1;
+        }
+
+        let mut sums: HashMap<u64, f64> = HashMap::new();
+        let mut values: HashMap<u64, f64> = HashMap::new();
+        let mut ready: Vec<u64> = self
+            .nodes
+            .iter()
+            .rev()
+            .filter(|node| in_degree[&node.id] == 0)
+            .map(|node| node.id)
+            .collect();
+        while let Some(id) = ready.pop() {
+            let node = self.node(id).unwrap();
+            let value = match node.kind {
+                NodeKind::Input => inputs[id as usize],
+                NodeKind::Bias => BIAS_VALUE,
+                _ => node
+                    .activation
+                    .activate(sums.get(&id).cloned().unwrap_or(0.0)),
+            };
+            values.insert(id, value);
+
+            if let Some(connections) = outgoing.get(&id) {
+                for connection in connections.iter() {
+                    *sums.entry(connection.out_node).or_insert(0.0) += value * connection.weight;
+                    let degree = in_degree.get_mut(&connection.out_node).unwrap();
+                    *degree -= 1;
+                    if *degree == 0 {
+                        ready.push(connection.out_node);
+                    }
+                }
+            }
+        }
+
+        self.nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Output)
+            .map(|node| values[&node.id])
+            .collect()
+    }
+
+    // delta = c1 * E / N + c2 * D / N + c3 * W, with E the excess genes, D the disjoint genes, W the mean weight
+    // difference of the matching genes and N the size of the larger genome.
+    pub fn compatibility_distance(&self, other: &NeatGenome, config: &CompatibilityConfig) -> f64 {
+        let (mut index, mut other_index) = (0, 0);
+        let mut matching = 0;
+        let mut disjoint = 0;
+        let mut weight_difference = 0.0;
+        while index < self.connections.len() && other_index < other.connections.len() {
+            let connection = &self.connections[index];
+            let other_connection = &other.connections[other_index];
+            match connection.innovation.cmp(&other_connection.innovation) {
+                Ordering::Equal => {
+                    matching += 1;
+                    weight_difference += (connection.weight - other_connection.weight).abs();
+                    index += 1;
+                    other_index += 1;
+                }
+                Ordering::Less => {
+                    disjoint += 1;
+                    index += 1;
+                }
+                Ordering::Greater => {
+                    disjoint += 1;
+                    other_index += 1;
+                }
+            }
+        }
+        let excess = (self.connections.len() - index) + (other.connections.len() - other_index);
+
+        let larger_genome = std::cmp::max(self.connections.len(), other.connections.len());
+        let genome_size = if larger_genome < config.small_genome_size {
+            1.0
+        } else {
+            larger_genome as f64
+        };
+        let mean_weight_difference = if matching == 0 {
+            0.0
+        } else {
+            weight_difference / matching as f64
+        };
+
+        config.excess_coefficient * excess as f64 / genome_size
+            + config.disjoint_coefficient * disjoint as f64 / genome_size
+            + config.weight_coefficient * mean_weight_difference
+    }
+
+    // Lines up the connections of both parents by innovation number, with self being the fitter parent. Matching genes
+    // come from either parent at random, disjoint and excess genes only from self. When both parents are equally fit the
+    // disjoint and excess genes of both parents are inherited at random instead. A gene disabled in either parent stays
+    // disabled in the child with disable_rate.
+    pub fn crossover(
+        &self,
+        other: &NeatGenome,
+        equal_fitness: bool,
+        disable_rate: f64,
+        rng: &mut impl Rng,
+    ) -> NeatGenome {
+        let other_connections: HashMap<u64, &ConnectionGene> = other
+            .connections
+            .iter()
+            .map(|connection| (connection.innovation, connection))
+            .collect();
+
+        let mut child = NeatGenome {
+            num_inputs: self.num_inputs,
+            num_outputs: self.num_outputs,
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|node| node.kind != NodeKind::Hidden)
+                .cloned()
+                .collect(),
+            connections: Vec::with_capacity(self.connections.len()),
+        };
+
+        for connection in self.connections.iter() {
+            let new_connection = match other_connections.get(&connection.innovation) {
+                Some(other_connection) => {
+                    let mut new_connection = if rng.gen_bool(0.5) {
+                        *connection
+                    } else {
+                        **other_connection
+                    };
+                    let disabled_in_parent = !connection.enabled || !other_connection.enabled;
+                    new_connection.enabled =
+                        !(disabled_in_parent && rng.gen::<f64>() < disable_rate);
+                    new_connection
+                }
+                None => {
+                    if equal_fitness && rng.gen_bool(0.5) {
+                        continue;
+                    }
+                    *connection
+                }
+            };
+            child.connections.push(new_connection);
+        }
+
+        if equal_fitness {
+            let own_innovations: HashSet<u64> = self
+                .connections
+                .iter()
+                .map(|connection| connection.innovation)
+                .collect();
+            for connection in other.connections.iter() {
+                if own_innovations.contains(&connection.innovation) || !rng.gen_bool(0.5) {
+                    continue;
+                }
+                if !child.has_connection(connection.in_node, connection.out_node)
+                    && !child.creates_cycle(connection.in_node, connection.out_node)
+                {
+                    child.insert_connection(*connection);
+                }
+            }
+        }
+
+        let used_nodes: HashSet<u64> = child
+            .connections
+            .iter()
+            .flat_map(|connection| vec![connection.in_node, connection.out_node])
+            .collect();
+        for node in self.nodes.iter().chain(other.nodes.iter()) {
+            if node.kind == NodeKind::Hidden
+                && used_nodes.contains(&node.id)
+                && child.node(node.id).is_none()
+            {
+                child.insert_node(*node);
+            }
+        }
+
+        child
+    }
+
+    fn insert_node(&mut self, node: NodeGene) {
+        let index = match self.nodes.binary_search_by_key(&node.id, |node| node.id) {
+            Ok(_) => panic!("The genome already has a node with id {}.", node.id),
+            Err(index) => index,
+        };
+        self.nodes.insert(index, node);
+    }
+
+    fn insert_connection(&mut self, connection: ConnectionGene) {
+        let index = match self
+            .connections
+            .binary_search_by_key(&connection.innovation, |connection| connection.innovation)
+        {
+            Ok(index) | Err(index) => index,
+        };
+        self.connections.insert(index, connection);
+    }
+}
+
+impl std::fmt::Display for NeatGenome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "Nodes: {:#?}", self.nodes).unwrap();
+        writeln!(f, "Connections: {:#?}", self.connections)
+    }
+}
+
+#[cfg(test)]
+mod neat_genome_test {
+    use crate::neat::innovation::InnovationTracker;
+    use crate::neat::neat_genome::{NeatGenome, NodeKind};
+    use crate::neat::speciation::CompatibilityConfig;
+    use crate::neural_network::activation::Activation;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    #[test]
+    fn new_genome_test() {
+        let tracker = InnovationTracker::new();
+        let genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+
+        assert_eq!(genome.nodes().len(), 4);
+        assert_eq!(genome.node(2).unwrap().kind, NodeKind::Bias);
+        assert_eq!(genome.node(3).unwrap().kind, NodeKind::Output);
+        assert_eq!(genome.connections().len(), 3);
+        assert_eq!(tracker.number_of_innovations(), 3);
+
+        // A second genome of the same shape shares the innovation numbers.
+        let other_genome = NeatGenome::new(2, 1, &tracker, [7; 32]);
+        assert_eq!(tracker.number_of_innovations(), 3);
+        assert_eq!(
+            genome.compatibility_distance(&other_genome, &CompatibilityConfig::default()) > 0.0,
+            true
+        );
+    }
+
+    #[test]
+    fn split_and_add_connection_test() {
+        let tracker = InnovationTracker::new();
+        let mut genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+        let mut other_genome = genome.clone();
+
+        let node_id = genome.split_connection(0, &tracker);
+        assert_eq!(node_id, 4);
+        assert_eq!(genome.number_of_hidden_nodes(), 1);
+        assert_eq!(genome.connections().len(), 5);
+        assert!(!genome.connections()[0].enabled);
+        assert_eq!(genome.connections()[3].weight, 1.0);
+        assert_eq!(
+            genome.connections()[4].weight,
+            genome.connections()[0].weight
+        );
+
+        // The same split in another genome gets the same node id and innovation numbers.
+        assert_eq!(other_genome.split_connection(0, &tracker), 4);
+        assert_eq!(other_genome.connections(), genome.connections());
+
+        // A cycle or a duplicate is refused.
+        assert!(!genome.add_connection(3, 4, 0.5, &tracker));
+        assert!(!genome.add_connection(0, 3, 0.5, &tracker));
+        assert_eq!(genome.possible_new_connections(), vec![(1, 4), (2, 4)]);
+        assert!(genome.add_connection(1, 4, 0.5, &tracker));
+        assert_eq!(genome.connections().len(), 6);
+    }
+
+    #[test]
+    fn feedforward_test() {
+        let tracker = InnovationTracker::new();
+        let mut genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+        for weight in genome.weights_mut() {
+            *weight = 0.5;
+        }
+        genome.nodes[3].activation = Activation::Linear;
+        assert_eq!(genome.feedforward(&[1.0, 2.0]), vec![2.0]);
+
+        // Splitting keeps the output the same for a linear hidden node.
+        genome.split_connection(0, &tracker);
+        genome.nodes[4].activation = Activation::Linear;
+        assert_eq!(genome.feedforward(&[1.0, 2.0]), vec![2.0]);
+
+        genome.set_connection_enabled(1, false);
+        assert_eq!(genome.feedforward(&[1.0, 2.0]), vec![1.0]);
+    }
+
+    #[test]
+    fn crossover_test() {
+        let tracker = InnovationTracker::new();
+        let mut fitter_genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+        let mut other_genome = fitter_genome.clone();
+        fitter_genome.split_connection(0, &tracker);
+        other_genome.split_connection(1, &tracker);
+        let mut rng: StdRng = SeedableRng::from_seed(*DEFAULT_SEED);
+
+        for _ in 0..10 {
+            let child = fitter_genome.crossover(&other_genome, false, 0.75, &mut rng);
+            let innovations: Vec<u64> = child.connections().iter().map(|c| c.innovation).collect();
+            let fitter_innovations: Vec<u64> = fitter_genome
+                .connections()
+                .iter()
+                .map(|c| c.innovation)
+                .collect();
+            assert_eq!(innovations, fitter_innovations);
+            assert_eq!(child.nodes().len(), fitter_genome.nodes().len());
+        }
+
+        let child = fitter_genome.crossover(&other_genome, true, 0.75, &mut rng);
+        for connection in child.connections().iter() {
+            assert!(child.node(connection.in_node).is_some());
+            assert!(child.node(connection.out_node).is_some());
+        }
+        assert_eq!(child.feedforward(&[0.5, 0.5]).len(), 1);
+    }
+}
@@ -0,0 +1,328 @@
+use crate::genome::population::{Population, ProblemType};
+use crate::neat::neat_genome::NeatGenome;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct CompatibilityConfig {
+    pub excess_coefficient: f64,
+    pub disjoint_coefficient: f64,
+    pub weight_coefficient: f64,
+    // Genomes closer than this to the representative of a species belong to that species.
+    pub threshold: f64,
+    // Below this many connections the excess and disjoint genes are not normalized by the genome size.
+    pub small_genome_size: usize,
+}
+
+impl Default for CompatibilityConfig {
+    fn default() -> CompatibilityConfig {
+        CompatibilityConfig {
+            excess_coefficient: 1.0,
+            disjoint_coefficient: 1.0,
+            weight_coefficient: 0.4,
+            threshold: 3.0,
+            small_genome_size: 20,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Species {
+    id: u64,
+    representative: NeatGenome,
+    // Indices into the population that was last speciated.
+    members: Vec<usize>,
+    best_fitness: Option<f64>,
+    generations_without_improvement: u32,
+}
+
+impl Species {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn representative(&self) -> &NeatGenome {
+        &self.representative
+    }
+
+    pub fn members(&self) -> &Vec<usize> {
+        &self.members
+    }
+
+    pub fn best_fitness(&self) -> Option<f64> {
+        self.best_fitness
+    }
+
+    pub fn generations_without_improvement(&self) -> u32 {
+        self.generations_without_improvement
+    }
+}
+
+// Keeps the species of a NEAT run across generations. Call speciate once per generation, after which share_fitness and
+// offspring_counts work on that same population.
+pub struct Speciation {
+    config: CompatibilityConfig,
+    // Species that have not improved for more generations than this get no offspring, unless they hold the best individual.
+    max_stagnation: u32,
+    species: Vec<Species>,
+    next_species_id: u64,
+    seed: StdRng,
+}
+
+impl Speciation {
+    pub fn new(config: CompatibilityConfig, max_stagnation: u32, seed: [u8; 32]) -> Speciation {
+        Speciation {
+            config,
+            max_stagnation,
+            species: Vec::new(),
+            next_species_id: 0,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+
+    pub fn config(&self) -> &CompatibilityConfig {
+        &self.config
+    }
+
+    pub fn species(&self) -> &Vec<Species> {
+        &self.species
+    }
+
+    // Index into species() of the species the individual at population_index belongs to.
+    pub fn species_of(&self, population_index: usize) -> Option<usize> {
+        self.species
+            .iter()
+            .position(|species| species.members.contains(&population_index))
+    }
+
+    // Puts every individual in the first species whose representative is within the compatibility threshold, or in a new
+    // species when there is none. Empty species die out and the others pick a random member as their next representative.
+    pub fn speciate(&mut self, population: &Population<NeatGenome>) {
+        let config = self.config;
+        for species in self.species.iter_mut() {
+            species.members.clear();
+        }
+
+        for (index, individual) in population.list_of_individuals().iter().enumerate() {
+            let genome = individual.retrieve_individual();
+            match self.species.iter_mut().find(|species| {
+                genome.compatibility_distance(&species.representative, &config) < config.threshold
+            }) {
+                Some(species) => species.members.push(index),
+                None => {
+                    self.species.push(Species {
+                        id: self.next_species_id,
+                        representative: genome.clone(),
+                        members: vec![index],
+                        best_fitness: None,
+                        generations_without_improvement: 0,
+                    });
+                    self.next_species_id += 1;
+                }
+            }
+        }
+        self.species.retain(|species| !species.members.is_empty());
+
+        let problem_type = population.problem_type();
+        let individuals = population.list_of_individuals();
+        for species in self.species.iter_mut() {
+            let representative = species.members[self.seed.gen_range(0, species.members.len())];
+            species.representative = individuals[representative].retrieve_individual().clone();
+
+            let best_fitness = species
+                .members
+                .iter()
+                .map(|&member| *individuals[member].fitness())
+                .fold(None, |best: Option<f64>, fitness| match best {
+                    Some(best) if !problem_type.is_better(fitness, best) => Some(best),
+                    _ => Some(fitness),
+                })
+                .unwrap();
+            match species.best_fitness {
+                Some(previous_best) if !problem_type.is_better(best_fitness, previous_best) => {
+                    species.generations_without_improvement += 1
+                }
+                _ => {
+                    species.best_fitness = Some(best_fitness);
+                    species.generations_without_improvement = 0;
+                }
+            }
+        }
+    }
+
+    // Explicit fitness sharing: every individual shares its fitness with the rest of its species. For Max problems the
+    // fitness is divided by the species size and for Min problems it is multiplied by it, so large species are penalised
+    // either way. Assumes non negative fitness values. The returned population works with any selection.
+    pub fn share_fitness(&self, population: &Population<NeatGenome>) -> Population<NeatGenome> {
+        let problem_type = population.problem_type();
+        let mut shared_population = population.clone();
+        let individuals = shared_population.list_of_individuals_mut();
+        for species in self.species.iter() {
+            let species_size = species.members.len() as f64;
+            for &member in species.members.iter() {
+                let individual = &mut individuals[member];
+                individual.fitness = match problem_type {
+                    ProblemType::Max => individual.fitness / species_size,
+                    ProblemType::Min => individual.fitness * species_size,
+                };
+            }
+        }
+        shared_population
+    }
+
+    // Splits number_of_offspring over the species in proportion to the mean fitness of their members, measured from the
+    // worst individual of the population. Stagnant species get nothing unless they hold the best individual.
+    pub fn offspring_counts(
+        &self,
+        population: &Population<NeatGenome>,
+        number_of_offspring: usize,
+    ) -> Vec<usize> {
+        let problem_type = population.problem_type();
+        let scores: Vec<f64> = population
+            .list_of_individuals()
+            .iter()
+            .map(|individual| match problem_type {
+                ProblemType::Max => *individual.fitness(),
+                ProblemType::Min => -*individual.fitness(),
+            })
+            .collect();
+        let lowest_score = scores.iter().cloned().fold(std::f64::INFINITY, f64::min);
+        let best_index = (0..scores.len()).fold(0, |best, index| {
+            if scores[index] > scores[best] {
+                index
+            } else {
+                best
+            }
+        });
+
+        let eligible: Vec<bool> = self
+            .species
+            .iter()
+            .map(|species| {
+                species.generations_without_improvement <= self.max_stagnation
+                    || species.members.contains(&best_index)
+            })
+            .collect();
+        let mut species_scores: Vec<f64> = self
+            .species
+            .iter()
+            .zip(eligible.iter())
+            .map(|(species, &eligible)| {
+                if !eligible {
+                    return 0.0;
+                }
+                species
+                    .members
+                    .iter()
+                    .map(|&member| scores[member] - lowest_score)
+                    .sum::<f64>()
+                    / species.members.len() as f64
+            })
+            .collect();
+        let mut total_score: f64 = species_scores.iter().sum();
+        if total_score <= 0.0 {
+            species_scores = eligible
+                .iter()
+                .map(|&eligible| if eligible { 1.0 } else { 0.0 })
+                .collect();
+            total_score = species_scores.iter().sum();
+        }
+
+        // Largest remainder rounding, so the counts always add up to number_of_offspring.
+        let quotas: Vec<f64> = species_scores
+            .iter()
+            .map(|score| score / total_score * number_of_offspring as f64)
+            .collect();
+        let mut counts: Vec<usize> = quotas.iter().map(|quota| quota.floor() as usize).collect();
+        let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+        by_remainder.sort_by(|&first, &second| {
+            (quotas[second] - quotas[second].floor())
+                .partial_cmp(&(quotas[first] - quotas[first].floor()))
+                .unwrap()
+        });
+        let remaining = number_of_offspring - counts.iter().sum::<usize>();
+        for &index in by_remainder.iter().take(remaining) {
+            counts[index] += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod speciation_test {
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::neat::innovation::InnovationTracker;
+    use crate::neat::neat_genome::NeatGenome;
+    use crate::neat::speciation::{CompatibilityConfig, Speciation};
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    fn create_population() -> Population<NeatGenome> {
+        let tracker = InnovationTracker::new();
+        let genome = NeatGenome::new(2, 1, &tracker, *DEFAULT_SEED);
+        let mut grown_genome = genome.clone();
+        grown_genome.split_connection(0, &tracker);
+        grown_genome.split_connection(1, &tracker);
+        grown_genome.split_connection(2, &tracker);
+
+        Population::new(
+            vec![
+                Individual::new(genome.clone(), 4.0),
+                Individual::new(grown_genome.clone(), 1.0),
+                Individual::new(genome, 2.0),
+                Individual::new(grown_genome, 3.0),
+            ],
+            ProblemType::Max,
+        )
+    }
+
+    #[test]
+    fn speciate_test() {
+        let population = create_population();
+        let config = CompatibilityConfig {
+            threshold: 2.0,
+            ..CompatibilityConfig::default()
+        };
+        let mut speciation = Speciation::new(config, 15, *DEFAULT_SEED);
+        speciation.speciate(&population);
+
+        assert_eq!(speciation.species().len(), 2);
+        assert_eq!(*speciation.species()[0].members(), vec![0, 2]);
+        assert_eq!(*speciation.species()[1].members(), vec![1, 3]);
+        assert_eq!(speciation.species()[0].best_fitness(), Some(4.0));
+        assert_eq!(speciation.species_of(3), Some(1));
+
+        let shared_population = speciation.share_fitness(&population);
+        let shared_fitnesses: Vec<f64> = shared_population
+            .list_of_individuals()
+            .iter()
+            .map(|individual| *individual.fitness())
+            .collect();
+        assert_eq!(shared_fitnesses, vec![2.0, 0.5, 1.0, 1.5]);
+
+        // Mean distance from the worst individual is 2 for the first species and 1 for the second.
+        assert_eq!(speciation.offspring_counts(&population, 10), vec![7, 3]);
+
+        speciation.speciate(&population);
+        assert_eq!(speciation.species()[0].generations_without_improvement(), 1);
+    }
+
+    #[test]
+    fn stagnant_species_test() {
+        let population = create_population();
+        let config = CompatibilityConfig {
+            threshold: 2.0,
+            ..CompatibilityConfig::default()
+        };
+        let mut speciation = Speciation::new(config, 0, *DEFAULT_SEED);
+        speciation.speciate(&population);
+        speciation.speciate(&population);
+
+        // Both species are stagnant, but the first one keeps the best individual.
+        assert_eq!(speciation.offspring_counts(&population, 10), vec![10, 0]);
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Activation {
+    Sigmoid,
+    Relu,
+    LeakyRelu { slope: f64 },
+    Tanh,
+    // Normalizes the whole layer into a probability distribution. Meant for the output layer.
+    Softmax,
+    Linear,
+    Step,
+    Gaussian,
+}
+
+impl Default for Activation {
+    fn default() -> Activation {
+        Activation::Sigmoid
+    }
+}
+
+impl Activation {
+    // Applies the activation to every value of a layer in place.
+    pub fn apply(&self, layer: &mut [f64]) {
+        match self {
+            Activation::Softmax => {
+                let max = layer.iter().cloned().fold(std::f64::NEG_INFINITY, f64::max);
+                let mut sum = 0.0;
+                for value in layer.iter_mut() {
+                    *value = (*value - max).exp();
+                    sum += *value;
+                }
+                for value in layer.iter_mut() {
+                    *value /= sum;
+                }
+            }
+            _ => {
+                for value in layer.iter_mut() {
+                    *value = self.activate(*value);
+                }
+            }
+        }
+    }
+
+    // Activation of a single value. Softmax depends on the whole layer, so a lone value always maps to 1.
+    pub fn activate(&self, value: f64) -> f64 {
+        match self {
+            Activation::Sigmoid => 1.0 / (1.0 + (-value).exp()),
+            Activation::Relu => value.max(0.0),
+            Activation::LeakyRelu { slope } => {
+                if value > 0.0 {
+                    value
+                } else {
+                    slope * value
+                }
+            }
+            Activation::Tanh => value.tanh(),
+            Activation::Softmax => 1.0,
+            Activation::Linear => value,
+            Activation::Step => {
+                if value >= 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Activation::Gaussian => (-value * value).exp(),
+        }
+    }
+
+    // Turns the derivative of a loss with respect to the activated values of a layer into the derivative with respect to
+    // the values before activation, in place.
+    pub fn backpropagate(&self, layer: &[f64], activated_layer: &[f64], gradient: &mut [f64]) {
+        match self {
+            Activation::Softmax => {
+                let weighted_sum: f64 = gradient
+                    .iter()
+                    .zip(activated_layer.iter())
+                    .map(|(gradient, activated)| gradient * activated)
+                    .sum();
+                for (gradient, activated) in gradient.iter_mut().zip(activated_layer.iter()) {
+                    *gradient = activated * (*gradient - weighted_sum);
+                }
+            }
+            _ => {
+                for ((gradient, value), activated) in gradient
+                    .iter_mut()
+                    .zip(layer.iter())
+                    .zip(activated_layer.iter())
+                {
+                    *gradient *= self.derivative(*value, *activated);
+                }
+            }
+        }
+    }
+
+    // Derivative of a single value, given the value before and after activation. Softmax is handled by backpropagate.
+    pub fn derivative(&self, value: f64, activated: f64) -> f64 {
+        match self {
+            Activation::Sigmoid => activated * (1.0 - activated),
+            Activation::Relu => {
+                if value > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Activation::LeakyRelu { slope } => {
+                if value > 0.0 {
+                    1.0
+                } else {
+                    *slope
+                }
+            }
+            Activation::Tanh => 1.0 - activated * activated,
+            Activation::Softmax => activated * (1.0 - activated),
+            Activation::Linear => 1.0,
+            Activation::Step => 0.0,
+            Activation::Gaussian => -2.0 * value * activated,
+        }
+    }
+}
+
+impl std::fmt::Display for Activation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(test)]
+mod activation_test {
+    use crate::neural_network::activation::Activation;
+
+    #[test]
+    fn activate_test() {
+        assert_eq!(Activation::Sigmoid.activate(0.0), 0.5);
+        assert_eq!(Activation::Relu.activate(-2.0), 0.0);
+        assert_eq!(Activation::Relu.activate(2.0), 2.0);
+        assert_eq!(Activation::LeakyRelu { slope: 0.1 }.activate(-2.0), -0.2);
+        assert_eq!(Activation::Tanh.activate(0.0), 0.0);
+        assert_eq!(Activation::Linear.activate(-3.5), -3.5);
+        assert_eq!(Activation::Step.activate(-0.1), 0.0);
+        assert_eq!(Activation::Step.activate(0.0), 1.0);
+        assert_eq!(Activation::Gaussian.activate(0.0), 1.0);
+    }
+
+    #[test]
+    fn softmax_test() {
+        let mut layer = [1.0, 2.0, 3.0];
+        Activation::Softmax.apply(&mut layer);
+
+        assert!((layer.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(layer[0] < layer[1] && layer[1] < layer[2]);
+
+        let mut large_layer = [1000.0, 1000.0];
+        Activation::Softmax.apply(&mut large_layer);
+        assert_eq!(large_layer, [0.5, 0.5]);
+    }
+}
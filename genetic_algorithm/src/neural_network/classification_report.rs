@@ -0,0 +1,489 @@
+use crate::dataset::dataset::Dataset;
+use crate::neural_network::dataset_fitness::{target_class, Decoding};
+use crate::neural_network::neural_network::NeuralNetwork;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClassMetrics {
+    pub name: String,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+    // Number of samples of the class.
+    pub support: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TopKAccuracy {
+    pub k: usize,
+    pub accuracy: f64,
+}
+
+// Samples whose confidence falls into [lower, upper). The last bin includes 1.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_confidence: f64,
+    pub accuracy: f64,
+}
+
+// How well the output for the predicted class, taken as the confidence, matches how often the prediction is right. The
+// expected calibration error is the mean gap between the confidence and the accuracy of the bins, weighted by count.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    pub bins: Vec<CalibrationBin>,
+    pub expected_calibration_error: f64,
+}
+
+// Evaluation of a classifier on a labeled dataset. Outputs are decoded into classes as DatasetFitness does: one output per
+// class with Decoding::Argmax, or a single output for two classes with Decoding::Threshold. The rows of the confusion
+// matrix are the actual classes and the columns the predicted classes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClassificationReport {
+    pub number_of_samples: usize,
+    pub confusion_matrix: Vec<Vec<usize>>,
+    pub classes: Vec<ClassMetrics>,
+    pub accuracy: f64,
+    pub macro_precision: f64,
+    pub macro_recall: f64,
+    pub macro_f1: f64,
+    pub top_k_accuracy: Vec<TopKAccuracy>,
+    pub calibration: Calibration,
+}
+
+impl ClassificationReport {
+    // Labels are the classes of the targets, so a binary dataset may have a single 0 or 1 target and two class names.
+    pub fn new(
+        net: &NeuralNetwork,
+        dataset: &Dataset,
+        decoding: Decoding,
+        top_k: &[usize],
+        number_of_calibration_bins: usize,
+    ) -> ClassificationReport {
+        let outputs: Vec<Vec<f64>> = dataset
+            .inputs()
+            .iter()
+            .map(|inputs| net.feedforward(inputs))
+            .collect();
+        let labels: Vec<usize> = dataset
+            .targets()
+            .iter()
+            .map(|target| target_class(target))
+            .collect();
+        ClassificationReport::from_outputs(
+            &outputs,
+            &labels,
+            dataset.class_names(),
+            decoding,
+            top_k,
+            number_of_calibration_bins,
+        )
+    }
+
+    // Report for outputs that were already computed, one row of outputs per label.
+    pub fn from_outputs(
+        outputs: &[Vec<f64>],
+        labels: &[usize],
+        class_names: &[String],
+        decoding: Decoding,
+        top_k: &[usize],
+        number_of_calibration_bins: usize,
+    ) -> ClassificationReport {
+        let number_of_classes = class_names.len();
+        if outputs.is_empty() || outputs.len() != labels.len() {
+            panic!(
+                "Can not evaluate {} outputs against {} labels.",
+                outputs.len(),
+                labels.len()
+            );
+        }
+        let number_of_outputs = match decoding {
+            Decoding::Argmax => number_of_classes,
+            Decoding::Threshold(_) if number_of_classes == 2 => 1,
+            Decoding::Threshold(_) => panic!(
+                "Threshold decoding is meant for 2 classes but got {} class names.",
+                number_of_classes
+            ),
+        };
+        if outputs
+            .iter()
+            .any(|outputs| outputs.len() != number_of_outputs)
+        {
+            panic!(
+                "The classifier needs {} outputs for {} classes.",
+                number_of_outputs, number_of_classes
+            );
+        }
+        if number_of_calibration_bins == 0 {
+            panic!("The calibration needs at least 1 bin.");
+        }
+
+        let predictions: Vec<usize> = outputs
+            .iter()
+            .map(|outputs| decoding.decode(outputs))
+            .collect();
+        let mut confusion_matrix = vec![vec![0; number_of_classes]; number_of_classes];
+        for (&label, &prediction) in labels.iter().zip(predictions.iter()) {
+            confusion_matrix[label][prediction] += 1;
+        }
+
+        let classes: Vec<ClassMetrics> = (0..number_of_classes)
+            .map(|class| {
+                let true_positives = confusion_matrix[class][class] as f64;
+                let support: usize = confusion_matrix[class].iter().sum();
+                let predicted: usize = confusion_matrix.iter().map(|row| row[class]).sum();
+                let precision = ratio(true_positives, predicted as f64);
+                let recall = ratio(true_positives, support as f64);
+                ClassMetrics {
+                    name: class_names[class].clone(),
+                    precision,
+                    recall,
+                    f1: ratio(2.0 * precision * recall, precision + recall),
+                    support,
+                }
+            })
+            .collect();
+
+        let number_of_samples = labels.len();
+        let correct = (0..number_of_classes)
+            .map(|class| confusion_matrix[class][class])
+            .sum::<usize>();
+        let macro_average = |metric: fn(&ClassMetrics) -> f64| {
+            classes.iter().map(metric).sum::<f64>() / number_of_classes as f64
+        };
+
+        ClassificationReport {
+            number_of_samples,
+            accuracy: correct as f64 / number_of_samples as f64,
+            macro_precision: macro_average(|class| class.precision),
+            macro_recall: macro_average(|class| class.recall),
+            macro_f1: macro_average(|class| class.f1),
+            top_k_accuracy: top_k
+                .iter()
+                .map(|&k| TopKAccuracy {
+                    k,
+                    accuracy: top_k_accuracy(outputs, labels, &predictions, decoding, k),
+                })
+                .collect(),
+            calibration: calibration(
+                outputs,
+                labels,
+                &predictions,
+                decoding,
+                number_of_calibration_bins,
+            ),
+            confusion_matrix,
+            classes,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<ClassificationReport> {
+        serde_json::from_str(json)
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator > 0.0 {
+        numerator / denominator
+    } else {
+        0.0
+    }
+}
+
+// A sample counts when fewer than k classes rank above its actual class. Ties rank the lower class first, as argmax does.
+// With a threshold the predicted class ranks first and the other class second.
+fn top_k_accuracy(
+    outputs: &[Vec<f64>],
+    labels: &[usize],
+    predictions: &[usize],
+    decoding: Decoding,
+    k: usize,
+) -> f64 {
+    let hits = outputs
+        .iter()
+        .zip(labels.iter())
+        .zip(predictions.iter())
+        .filter(|((outputs, &label), &prediction)| {
+            let rank = match decoding {
+                Decoding::Argmax => (0..outputs.len())
+                    .filter(|&class| {
+                        outputs[class] > outputs[label]
+                            || (outputs[class] == outputs[label] && class < label)
+                    })
+                    .count(),
+                Decoding::Threshold(_) => (prediction != label) as usize,
+            };
+            rank < k
+        })
+        .count();
+    hits as f64 / labels.len() as f64
+}
+
+// The confidence of a sample is the output of its predicted class, clamped to [0, 1]. With a threshold the single output
+// is the confidence in class 1, and one minus it the confidence in class 0.
+fn calibration(
+    outputs: &[Vec<f64>],
+    labels: &[usize],
+    predictions: &[usize],
+    decoding: Decoding,
+    number_of_bins: usize,
+) -> Calibration {
+    let mut counts = vec![0; number_of_bins];
+    let mut confidence_sums = vec![0.0; number_of_bins];
+    let mut correct = vec![0; number_of_bins];
+    for ((outputs, &label), &prediction) in outputs.iter().zip(labels).zip(predictions) {
+        let confidence = match decoding {
+            Decoding::Argmax => outputs[prediction],
+            Decoding::Threshold(_) if prediction == 1 => outputs[0],
+            Decoding::Threshold(_) => 1.0 - outputs[0],
+        }
+        .clamp(0.0, 1.0);
+        let bin = ((confidence * number_of_bins as f64) as usize).min(number_of_bins - 1);
+        counts[bin] += 1;
+        confidence_sums[bin] += confidence;
+        if prediction == label {
+            correct[bin] += 1;
+        }
+    }
+
+    let bins: Vec<CalibrationBin> = (0..number_of_bins)
+        .map(|bin| CalibrationBin {
+            lower: bin as f64 / number_of_bins as f64,
+            upper: (bin + 1) as f64 / number_of_bins as f64,
+            count: counts[bin],
+            mean_confidence: ratio(confidence_sums[bin], counts[bin] as f64),
+            accuracy: ratio(correct[bin] as f64, counts[bin] as f64),
+        })
+        .collect();
+    let expected_calibration_error = bins
+        .iter()
+        .map(|bin| bin.count as f64 * (bin.accuracy - bin.mean_confidence).abs())
+        .sum::<f64>()
+        / labels.len() as f64;
+    Calibration {
+        bins,
+        expected_calibration_error,
+    }
+}
+
+impl fmt::Display for ClassificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name_width = self
+            .classes
+            .iter()
+            .map(|class| class.name.len())
+            .chain(std::iter::once(9))
+            .max()
+            .unwrap();
+        writeln!(
+            f,
+            "{:>width$} {:>9} {:>9} {:>9} {:>9}",
+            "",
+            "precision",
+            "recall",
+            "f1",
+            "support",
+            width = name_width
+        )?;
+        for class in self.classes.iter() {
+            writeln!(
+                f,
+                "{:>width$} {:>9.4} {:>9.4} {:>9.4} {:>9}",
+                class.name,
+                class.precision,
+                class.recall,
+                class.f1,
+                class.support,
+                width = name_width
+            )?;
+        }
+        writeln!(
+            f,
+            "{:>width$} {:>9.4} {:>9.4} {:>9.4} {:>9}",
+            "macro avg",
+            self.macro_precision,
+            self.macro_recall,
+            self.macro_f1,
+            self.number_of_samples,
+            width = name_width
+        )?;
+        writeln!(f, "\nAccuracy: {:.4}", self.accuracy)?;
+        for top_k in self.top_k_accuracy.iter() {
+            writeln!(f, "Top {} accuracy: {:.4}", top_k.k, top_k.accuracy)?;
+        }
+
+        writeln!(
+            f,
+            "\nConfusion matrix (rows are actual, columns predicted classes):"
+        )?;
+        for (class, row) in self.classes.iter().zip(self.confusion_matrix.iter()) {
+            write!(f, "{:>width$}", class.name, width = name_width)?;
+            for count in row.iter() {
+                write!(f, " {:>6}", count)?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(
+            f,
+            "\nCalibration, expected calibration error {:.4}:",
+            self.calibration.expected_calibration_error
+        )?;
+        for bin in self.calibration.bins.iter().filter(|bin| bin.count > 0) {
+            let closing_bracket = if bin.upper >= 1.0 { ']' } else { ')' };
+            writeln!(
+                f,
+                "[{:.2}, {:.2}{} count {:>6}, confidence {:.4}, accuracy {:.4}",
+                bin.lower, bin.upper, closing_bracket, bin.count, bin.mean_confidence, bin.accuracy
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod classification_report_test {
+    use crate::dataset::dataset::Dataset;
+    use crate::neural_network::classification_report::ClassificationReport;
+    use crate::neural_network::dataset_fitness::Decoding;
+    use crate::neural_network::neural_network::NeuralNetwork;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    fn create_class_names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn create_report() -> ClassificationReport {
+        let outputs = vec![
+            vec![0.9, 0.05, 0.05],
+            vec![0.6, 0.3, 0.1],
+            vec![0.2, 0.7, 0.1],
+            vec![0.1, 0.1, 0.8],
+            vec![0.3, 0.4, 0.3],
+        ];
+        let labels = [0, 1, 1, 2, 2];
+        let class_names = create_class_names(&["zero", "one", "two"]);
+        ClassificationReport::from_outputs(
+            &outputs,
+            &labels,
+            &class_names,
+            Decoding::Argmax,
+            &[1, 2],
+            2,
+        )
+    }
+
+    #[test]
+    fn classification_report_test() {
+        let report = create_report();
+        assert_eq!(
+            report.confusion_matrix,
+            vec![vec![1, 0, 0], vec![1, 1, 0], vec![0, 1, 1]]
+        );
+        assert_eq!(report.accuracy, 0.6);
+        assert_eq!(report.classes[0].precision, 0.5);
+        assert_eq!(report.classes[0].recall, 1.0);
+        assert_eq!(report.classes[2].precision, 1.0);
+        assert_eq!(report.classes[2].recall, 0.5);
+        assert!((report.classes[1].f1 - 0.5).abs() < 1e-12);
+        assert_eq!(report.classes[1].support, 2);
+
+        assert_eq!(report.top_k_accuracy[0].accuracy, 0.6);
+        // The second sample has its class second. The last sample has its class tied with the first class, which ranks
+        // above it.
+        assert_eq!(report.top_k_accuracy[1].k, 2);
+        assert_eq!(report.top_k_accuracy[1].accuracy, 0.8);
+
+        // Confidence 0.4 is in the first bin, 0.9, 0.6, 0.7 and 0.8 are in the second.
+        let bins = &report.calibration.bins;
+        assert_eq!((bins[0].count, bins[1].count), (1, 4));
+        assert_eq!(bins[0].accuracy, 0.0);
+        assert!((bins[1].mean_confidence - 0.75).abs() < 1e-12);
+        assert_eq!(bins[1].accuracy, 0.75);
+        assert!((report.calibration.expected_calibration_error - 0.4 / 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn classification_report_output_test() {
+        let report = create_report();
+        let loaded_report = ClassificationReport::from_json(&report.to_json()).unwrap();
+        assert_eq!(loaded_report, report);
+
+        let text = report.to_string();
+        assert!(text.contains("Accuracy: 0.6000"));
+        assert!(text.contains("Top 2 accuracy: 0.8000"));
+        assert!(text.contains("     zero    0.5000    1.0000"));
+        assert!(text.contains("[0.00, 0.50) count      1"));
+        assert!(text.contains("[0.50, 1.00] count      4"));
+    }
+
+    #[test]
+    fn classification_report_threshold_test() {
+        let outputs = vec![vec![0.9], vec![0.4], vec![0.7], vec![0.2]];
+        let labels = [1, 1, 0, 0];
+        let class_names = create_class_names(&["no", "yes"]);
+        let report = ClassificationReport::from_outputs(
+            &outputs,
+            &labels,
+            &class_names,
+            Decoding::Threshold(0.5),
+            &[1, 2],
+            2,
+        );
+        assert_eq!(report.confusion_matrix, vec![vec![1, 1], vec![1, 1]]);
+        assert_eq!(report.accuracy, 0.5);
+        assert_eq!(report.top_k_accuracy[0].accuracy, 0.5);
+        assert_eq!(report.top_k_accuracy[1].accuracy, 1.0);
+        // Confidences are 0.9, 1 - 0.4, 0.7 and 1 - 0.2, an output of 0.4 is a confidence of 0.6 in class 0.
+        let bins = &report.calibration.bins;
+        assert_eq!((bins[0].count, bins[1].count), (0, 4));
+        assert!((bins[1].mean_confidence - 0.75).abs() < 1e-12);
+        assert_eq!(bins[1].accuracy, 0.5);
+
+        let report = ClassificationReport::from_outputs(
+            &outputs,
+            &labels,
+            &class_names,
+            Decoding::Threshold(0.3),
+            &[],
+            1,
+        );
+        assert_eq!(report.confusion_matrix, vec![vec![1, 1], vec![0, 2]]);
+
+        // A single 0 or 1 target is the class itself. The network outputs sigmoid(x), so only x = -2 is class 0.
+        let mut net = NeuralNetwork::new(1, &[], 1, *DEFAULT_SEED);
+        net.set_weights(&[1.0, 0.0]);
+        let dataset = Dataset::with_class_names(
+            vec![vec![2.0], vec![-2.0], vec![1.0]],
+            vec![vec![1.0], vec![0.0], vec![0.0]],
+            class_names,
+        );
+        let report = ClassificationReport::new(&net, &dataset, Decoding::Threshold(0.5), &[], 1);
+        assert_eq!(report.confusion_matrix, vec![vec![1, 1], vec![0, 1]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn classification_report_threshold_classes_test() {
+        let class_names = create_class_names(&["zero", "one", "two"]);
+        ClassificationReport::from_outputs(
+            &[vec![0.5]],
+            &[0],
+            &class_names,
+            Decoding::Threshold(0.5),
+            &[],
+            1,
+        );
+    }
+}
@@ -0,0 +1,427 @@
+use crate::dataset::dataset::{argmax, Dataset};
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::ProblemType;
+use crate::neural_network::neural_network::NeuralNetwork;
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+// Keeps the logarithm of the cross entropy finite when an output is exactly 0 or 1.
+const CROSS_ENTROPY_EPSILON: f64 = 1e-12;
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Metric {
+    Accuracy,
+    // Mean recall over every class that occurs in the targets, so rare classes count as much as common ones.
+    BalancedAccuracy,
+    // Macro F1: the F1 score of every class that occurs in the targets or the predictions, averaged.
+    F1,
+    // Categorical cross entropy, or binary cross entropy for networks with a single output.
+    CrossEntropy,
+    MeanSquaredError,
+    MeanAbsoluteError,
+}
+
+// How the outputs of a network are turned into a class.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Decoding {
+    // The class of the largest output.
+    Argmax,
+    // Binary classification with a single output: class 1 when the output is at least the threshold, else class 0.
+    Threshold(f64),
+}
+
+impl Decoding {
+    pub fn decode(&self, outputs: &[f64]) -> usize {
+        match *self {
+            Decoding::Argmax => argmax(outputs),
+            Decoding::Threshold(threshold) => {
+                if outputs.len() != 1 {
+                    panic!(
+                        "Threshold decoding needs a single output but got {}.",
+                        outputs.len()
+                    );
+                }
+                (outputs[0] >= threshold) as usize
+            }
+        }
+    }
+}
+
+// Class of a target row: one hot targets are decoded with argmax and a single 0 or 1 target is the class itself.
+pub(crate) fn target_class(target: &[f64]) -> usize {
+    if target.len() == 1 {
+        (target[0] >= 0.5) as usize
+    } else {
+        argmax(target)
+    }
+}
+
+impl Metric {
+    // Direction of the metric, to use for the population.
+    pub fn problem_type(&self) -> ProblemType {
+        match self {
+            Metric::Accuracy | Metric::BalancedAccuracy | Metric::F1 => ProblemType::Max,
+            Metric::CrossEntropy | Metric::MeanSquaredError | Metric::MeanAbsoluteError => {
+                ProblemType::Min
+            }
+        }
+    }
+
+    // Targets can be owned rows or rows borrowed from a dataset, so a batch does not need to copy them.
+    pub fn evaluate<T: AsRef<[f64]>>(
+        &self,
+        outputs: &[Vec<f64>],
+        targets: &[T],
+        decoding: Decoding,
+    ) -> f64 {
+        if outputs.len() != targets.len() || outputs.is_empty() {
+            panic!(
+                "Can not evaluate {} outputs against {} targets.",
+                outputs.len(),
+                targets.len()
+            );
+        }
+        if let Some((output, target)) = outputs
+            .iter()
+            .zip(targets.iter())
+            .find(|(output, target)| output.len() != target.as_ref().len())
+        {
+            panic!(
+                "Got {} outputs for a target of {} values, the network needs one output per target value.",
+                output.len(),
+                target.as_ref().len()
+            );
+        }
+        let number_of_samples = outputs.len() as f64;
+        let samples = outputs
+            .iter()
+            .zip(targets.iter().map(|target| target.as_ref()));
+        match self {
+            Metric::Accuracy => {
+                samples
+                    .filter(|(outputs, target)| decoding.decode(outputs) == target_class(target))
+                    .count() as f64
+                    / number_of_samples
+            }
+            Metric::BalancedAccuracy | Metric::F1 => {
+                let number_of_classes = targets[0].as_ref().len().max(2);
+                // Per class: true positives, predictions and occurrences in the targets.
+                let mut true_positives = vec![0.0; number_of_classes];
+                let mut predicted = vec![0.0; number_of_classes];
+                let mut actual = vec![0.0; number_of_classes];
+                for (outputs, target) in samples {
+                    let predicted_class = decoding.decode(outputs);
+                    let actual_class = target_class(target);
+                    predicted[predicted_class] += 1.0;
+                    actual[actual_class] += 1.0;
+                    if predicted_class == actual_class {
+                        true_positives[actual_class] += 1.0;
+                    }
+                }
+                let scores: Vec<f64> = (0..number_of_classes)
+                    .filter_map(|class| match self {
+                        Metric::BalancedAccuracy if actual[class] > 0.0 => {
+                            Some(true_positives[class] / actual[class])
+                        }
+                        Metric::F1 if actual[class] + predicted[class] > 0.0 => {
+                            Some(2.0 * true_positives[class] / (actual[class] + predicted[class]))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                scores.iter().sum::<f64>() / scores.len() as f64
+            }
+            Metric::CrossEntropy => {
+                samples
+                    .map(|(outputs, target)| {
+                        if outputs.len() == 1 {
+                            let output = outputs[0]
+                                .clamp(CROSS_ENTROPY_EPSILON, 1.0 - CROSS_ENTROPY_EPSILON);
+                            -(target[0] * output.ln() + (1.0 - target[0]) * (1.0 - output).ln())
+                        } else {
+                            -outputs
+                                .iter()
+                                .zip(target.iter())
+                                .map(|(output, target)| {
+                                    target * output.max(CROSS_ENTROPY_EPSILON).ln()
+                                })
+                                .sum::<f64>()
+                        }
+                    })
+                    .sum::<f64>()
+                    / number_of_samples
+            }
+            Metric::MeanSquaredError | Metric::MeanAbsoluteError => {
+                samples
+                    .map(|(outputs, target)| {
+                        outputs
+                            .iter()
+                            .zip(target.iter())
+                            .map(|(output, target)| match self {
+                                Metric::MeanSquaredError => (output - target).powi(2),
+                                _ => (output - target).abs(),
+                            })
+                            .sum::<f64>()
+                            / outputs.len() as f64
+                    })
+                    .sum::<f64>()
+                    / number_of_samples
+            }
+        }
+    }
+}
+
+// Fitness of a network on a training dataset, measured with one of the metrics. Use metric.problem_type() as problem
+// type of the population.
+//
+// With a batch size the fitness only looks at a random mini-batch of the training set. The batch stays the same until
+// next_batch is called, so call it once per generation to keep the individuals of one generation comparable. The
+// datasets are shared between clones, so cloning the fitness function for every operator is cheap.
+#[derive(Clone, Debug)]
+pub struct DatasetFitness {
+    training: Arc<Dataset>,
+    validation: Option<Arc<Dataset>>,
+    metric: Metric,
+    decoding: Decoding,
+    batch_size: Option<usize>,
+    batch: Vec<usize>,
+    seed: StdRng,
+}
+
+impl DatasetFitness {
+    pub fn new(training: Dataset, metric: Metric, seed: [u8; 32]) -> DatasetFitness {
+        if training.is_empty() {
+            panic!("The training dataset has no samples.");
+        }
+        DatasetFitness {
+            batch: (0..training.len()).collect(),
+            training: Arc::new(training),
+            validation: None,
+            metric,
+            decoding: Decoding::Argmax,
+            batch_size: None,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    pub fn set_decoding(&mut self, decoding: Decoding) {
+        self.decoding = decoding;
+    }
+
+    // Validation samples are never part of the fitness, see validation_metric.
+    pub fn set_validation(&mut self, validation: Dataset) {
+        if validation.is_empty() {
+            panic!("The validation dataset has no samples.");
+        }
+        self.validation = Some(Arc::new(validation));
+    }
+
+    // None evaluates the whole training set. Draws the first batch right away.
+    pub fn set_batch_size(&mut self, batch_size: Option<usize>) {
+        if batch_size == Some(0) {
+            panic!("The batch size has to be at least 1.");
+        }
+        self.batch_size = batch_size;
+        self.next_batch();
+    }
+
+    pub fn next_batch(&mut self) {
+        self.batch = match self.batch_size {
+            Some(batch_size) if batch_size < self.training.len() => {
+                let mut batch = sample(&mut self.seed, self.training.len(), batch_size).into_vec();
+                batch.sort();
+                batch
+            }
+            _ => (0..self.training.len()).collect(),
+        };
+    }
+
+    // Indices into the training set of the current batch.
+    pub fn batch(&self) -> &Vec<usize> {
+        &self.batch
+    }
+
+    pub fn evaluate(&self, net: &NeuralNetwork, dataset: &Dataset) -> f64 {
+        let outputs: Vec<Vec<f64>> = dataset
+            .inputs()
+            .iter()
+            .map(|inputs| net.feedforward(inputs))
+            .collect();
+        self.metric
+            .evaluate(&outputs, dataset.targets(), self.decoding)
+    }
+
+    // The metric on the whole training set, whatever the batch size.
+    pub fn training_metric(&self, net: &NeuralNetwork) -> f64 {
+        self.evaluate(net, &self.training)
+    }
+
+    pub fn validation_metric(&self, net: &NeuralNetwork) -> Option<f64> {
+        self.validation
+            .as_ref()
+            .map(|validation| self.evaluate(net, validation))
+    }
+}
+
+impl FitnessFunction for DatasetFitness {
+    type T = NeuralNetwork;
+
+    fn calculate_fitness(&mut self, net: &NeuralNetwork) -> f64 {
+        let outputs: Vec<Vec<f64>> = self
+            .batch
+            .iter()
+            .map(|&index| net.feedforward(&self.training.inputs()[index]))
+            .collect();
+        let targets: Vec<&Vec<f64>> = self
+            .batch
+            .iter()
+            .map(|&index| &self.training.targets()[index])
+            .collect();
+        self.metric.evaluate(&outputs, &targets, self.decoding)
+    }
+}
+
+#[cfg(test)]
+mod dataset_fitness_test {
+    use crate::dataset::dataset::Dataset;
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::ProblemType;
+    use crate::neural_network::activation::Activation;
+    use crate::neural_network::dataset_fitness::{DatasetFitness, Decoding, Metric};
+    use crate::neural_network::neural_network::NeuralNetwork;
+    use crate::neural_network::portable_model::{
+        PortableLayer, PortableModel, PORTABLE_MODEL_VERSION,
+    };
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    // Passes its two inputs through unchanged.
+    fn create_identity_net() -> NeuralNetwork {
+        let model = PortableModel {
+            format_version: PORTABLE_MODEL_VERSION,
+            number_of_inputs: 2,
+            recurrent: false,
+            layers: vec![PortableLayer {
+                activation: Activation::Linear,
+                weights: vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+                biases: vec![0.0, 0.0],
+                context: Vec::new(),
+            }],
+        };
+        NeuralNetwork::from_portable_model(&model).unwrap()
+    }
+
+    fn create_dataset() -> Dataset {
+        // The identity network predicts class 0, 1, 1 and 1 for classes 0, 0, 1 and 1.
+        let inputs = vec![
+            vec![0.9, 0.1],
+            vec![0.4, 0.6],
+            vec![0.2, 0.8],
+            vec![0.3, 0.7],
+        ];
+        let labels = [0, 0, 1, 1];
+        Dataset::from_labels(inputs, &labels, vec![String::from("a"), String::from("b")])
+    }
+
+    #[test]
+    fn metric_test() {
+        let net = create_identity_net();
+        let dataset = create_dataset();
+        let outputs: Vec<Vec<f64>> = dataset
+            .inputs()
+            .iter()
+            .map(|inputs| net.feedforward(inputs))
+            .collect();
+        let targets = dataset.targets();
+        let evaluate = |metric: Metric| metric.evaluate(&outputs, targets, Decoding::Argmax);
+
+        assert_eq!(evaluate(Metric::Accuracy), 0.75);
+        // Recall of class a is 1 / 2 and of class b 2 / 2.
+        assert_eq!(evaluate(Metric::BalancedAccuracy), 0.75);
+        // F1 of class a is 2 / 3 and of class b 4 / 5.
+        assert!((evaluate(Metric::F1) - (2.0 / 3.0 + 0.8) / 2.0).abs() < 1e-12);
+        assert!(
+            (evaluate(Metric::MeanAbsoluteError) - (0.1 + 0.6 + 0.2 + 0.3) / 4.0).abs() < 1e-12
+        );
+        assert!(
+            (evaluate(Metric::MeanSquaredError) - (0.01 + 0.36 + 0.04 + 0.09) / 4.0).abs() < 1e-12
+        );
+        let cross_entropy = -(0.9f64.ln() + 0.4f64.ln() + 0.8f64.ln() + 0.7f64.ln()) / 4.0;
+        assert!((evaluate(Metric::CrossEntropy) - cross_entropy).abs() < 1e-12);
+        match Metric::CrossEntropy.problem_type() {
+            ProblemType::Min => {}
+            _ => panic!("Cross entropy has to be minimized."),
+        }
+
+        let binary_outputs = vec![vec![0.7], vec![0.2], vec![0.6]];
+        let binary_targets = vec![vec![1.0], vec![0.0], vec![0.0]];
+        assert_eq!(
+            Metric::Accuracy.evaluate(&binary_outputs, &binary_targets, Decoding::Threshold(0.5)),
+            2.0 / 3.0
+        );
+        assert_eq!(
+            Metric::Accuracy.evaluate(&binary_outputs, &binary_targets, Decoding::Threshold(0.65)),
+            1.0
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn metric_output_length_test() {
+        let outputs = vec![vec![0.2, 0.3, 0.5]];
+        let targets = vec![vec![0.0, 1.0]];
+        Metric::MeanSquaredError.evaluate(&outputs, &targets, Decoding::Argmax);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_validation_test() {
+        let mut fitness_function =
+            DatasetFitness::new(create_dataset(), Metric::Accuracy, *DEFAULT_SEED);
+        fitness_function.set_validation(Dataset::new(Vec::new(), Vec::new()));
+    }
+
+    #[test]
+    fn dataset_fitness_test() {
+        let net = create_identity_net();
+        let mut fitness_function =
+            DatasetFitness::new(create_dataset(), Metric::Accuracy, *DEFAULT_SEED);
+        assert_eq!(fitness_function.calculate_fitness(&net), 0.75);
+        assert_eq!(fitness_function.validation_metric(&net), None);
+
+        let validation = Dataset::from_labels(
+            vec![vec![0.1, 0.9]],
+            &[0],
+            vec![String::from("a"), String::from("b")],
+        );
+        fitness_function.set_validation(validation);
+        assert_eq!(fitness_function.validation_metric(&net), Some(0.0));
+        assert_eq!(fitness_function.calculate_fitness(&net), 0.75);
+
+        fitness_function.set_batch_size(Some(2));
+        let mut batches = Vec::new();
+        for _ in 0..20 {
+            let batch = fitness_function.batch().clone();
+            assert_eq!(batch.len(), 2);
+            // The fitness only changes with the batch.
+            let fitness = fitness_function.calculate_fitness(&net);
+            assert_eq!(fitness_function.calculate_fitness(&net), fitness);
+            assert!(fitness == 0.5 || fitness == 1.0);
+            batches.push(batch);
+            fitness_function.next_batch();
+        }
+        batches.dedup();
+        assert!(batches.len() > 1);
+        assert_eq!(fitness_function.training_metric(&net), 0.75);
+    }
+}
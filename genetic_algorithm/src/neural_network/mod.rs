@@ -1 +1,9 @@
-pub mod neural_network;
\ No newline at end of file
+pub mod activation;
+pub mod classification_report;
+pub mod compiled_network;
+pub mod dataset_fitness;
+pub mod neural_network;
+pub mod portable_model;
+pub mod trainer;
+pub mod weight_initializer;
+pub mod weights_fitness;
@@ -1,25 +1,53 @@
+use crate::neural_network::activation::Activation;
+use crate::neural_network::compiled_network::{CompiledNetwork, DenseLayer, Scalar};
+use crate::neural_network::portable_model::{
+    PortableLayer, PortableModel, PortableModelError, PORTABLE_MODEL_VERSION,
+};
+use crate::neural_network::weight_initializer::WeightInitializer;
 use rand::prelude::ThreadRng;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
 use serde::{Deserialize, Serialize};
-use std::borrow::Borrow;
 use std::cmp;
 use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
 
 const BIAS_VALUE: f64 = 1.0;
-const MIN_NEGATIVE_VALUE: f64 = -0.1;
-const MAX_POSITIVE_VALUE: f64 = 0.1;
+pub(crate) const MIN_NEGATIVE_VALUE: f64 = -0.1;
+pub(crate) const MAX_POSITIVE_VALUE: f64 = 0.1;
 
+// The network holds no random generator, every method that draws new weights takes one. Networks saved with the random
+// generator state of older versions still load, the state is ignored.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NeuralNetwork {
     inputs: Vec<NeuralNode>,
     hidden: Vec<Vec<NeuralNode>>,
     outputs: Vec<NeuralNode>,
     bias: Vec<Vec<NeuralNode>>,
-    seed: ChaChaRng,
+    // Bias node connected to every output node. Networks saved before it existed load with an empty output bias, which
+    // acts as a zero bias, and get zero weights filled in the next time their weights are fixed.
+    #[serde(default)]
+    output_bias: NeuralNode,
+    // Networks saved before activations were configurable have no activations stored and fall back to sigmoid.
+    #[serde(default)]
+    hidden_activations: Vec<Activation>,
+    #[serde(default)]
+    output_activation: Activation,
+    // Elman style recurrence: context[i][j] holds the weights from the previous activation of node j of hidden layer i
+    // to every node of hidden layer i, and state holds those previous activations. Both stay empty when the network is
+    // not recurrent.
+    #[serde(default)]
+    recurrent: bool,
+    #[serde(default)]
+    context: Vec<Vec<NeuralNode>>,
+    #[serde(default)]
+    state: Vec<Vec<f64>>,
+    // Networks saved before the initializer was configurable use the uniform range of new.
+    #[serde(default)]
+    weight_initializer: WeightInitializer,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 struct NeuralNode {
     connection_weights: Vec<f64>,
 }
@@ -31,36 +59,42 @@ impl NeuralNode {
 
 impl NeuralNetwork {
     pub fn hidden_layer_swap_and_create_new_from(
-        &mut self,
+        &self,
         net_to_exchange: &NeuralNetwork,
+        rng: &mut impl Rng,
     ) -> Option<NeuralNetwork> {
-        // TODO: Swap inner nodes in a layer
-
         if self.is_hidden_layer_empty() && net_to_exchange.is_hidden_layer_empty() {
             return None;
         }
 
         // Randomly pick inputs from the first or second network
-        let gen_val = self.seed.gen::<f64>();
-        let new_inputs = if self.seed.gen_bool(gen_val) {
+        let gen_val = rng.gen::<f64>();
+        let new_inputs = if rng.gen_bool(gen_val) {
             self.inputs.clone()
         } else {
             net_to_exchange.inputs.clone()
         };
 
         // TODO: Refactor to it's own method to use for hidden and bias logic.
-        let new_hidden: Vec<Vec<NeuralNode>> = {
+        // Activations travel together with the hidden layer they belong to.
+        let (new_hidden, new_hidden_activations, new_context) = {
             let mut new_hidden_layer: Vec<Vec<NeuralNode>> = Vec::new();
+            let mut new_hidden_activations: Vec<Activation> = Vec::new();
+            let mut new_context: Vec<Vec<NeuralNode>> = Vec::new();
 
             // randomly choose between the first net or the second.
-            let gen_val = self.seed.gen::<f64>();
-            let mut toggle_value = self.seed.gen_bool(gen_val);
+            let gen_val = rng.gen::<f64>();
+            let mut toggle_value = rng.gen_bool(gen_val);
             let min_length = cmp::min(self.hidden.len(), net_to_exchange.hidden.len());
             for layer_index in 0..min_length {
                 if toggle_value {
-                    new_hidden_layer.push(self.hidden[layer_index].clone())
+                    new_hidden_layer.push(self.hidden[layer_index].clone());
+                    new_hidden_activations.push(self.hidden_activation(layer_index));
+                    new_context.push(self.context_layer(layer_index));
                 } else {
-                    new_hidden_layer.push(net_to_exchange.hidden[layer_index].clone())
+                    new_hidden_layer.push(net_to_exchange.hidden[layer_index].clone());
+                    new_hidden_activations.push(net_to_exchange.hidden_activation(layer_index));
+                    new_context.push(net_to_exchange.context_layer(layer_index));
                 }
                 toggle_value ^= true;
             }
@@ -68,23 +102,27 @@ impl NeuralNetwork {
             if self.hidden.len() > net_to_exchange.hidden.len() {
                 let start = (self.hidden.len() - net_to_exchange.hidden.len()) - 1;
                 for layer_index in start..self.hidden.len() {
-                    new_hidden_layer.push(self.hidden[layer_index].clone())
+                    new_hidden_layer.push(self.hidden[layer_index].clone());
+                    new_hidden_activations.push(self.hidden_activation(layer_index));
+                    new_context.push(self.context_layer(layer_index));
                 }
             } else if self.hidden.len() < net_to_exchange.hidden.len() {
                 let start = (net_to_exchange.hidden.len() - self.hidden.len()) - 1;
                 for layer_index in start..net_to_exchange.hidden.len() {
-                    new_hidden_layer.push(net_to_exchange.hidden[layer_index].clone())
+                    new_hidden_layer.push(net_to_exchange.hidden[layer_index].clone());
+                    new_hidden_activations.push(net_to_exchange.hidden_activation(layer_index));
+                    new_context.push(net_to_exchange.context_layer(layer_index));
                 }
             }
 
-            new_hidden_layer
+            (new_hidden_layer, new_hidden_activations, new_context)
         };
 
         let new_bias: Vec<Vec<NeuralNode>> = {
             let mut new_bias_layer: Vec<Vec<NeuralNode>> = Vec::new();
             // randomly choose between the first net or the second.
-            let gen_val = self.seed.gen::<f64>();
-            let mut toggle_value = self.seed.gen_bool(gen_val);
+            let gen_val = rng.gen::<f64>();
+            let mut toggle_value = rng.gen_bool(gen_val);
             let min_length = cmp::min(self.bias.len(), net_to_exchange.bias.len());
             for layer_index in 0..min_length {
                 if toggle_value {
@@ -109,106 +147,294 @@ impl NeuralNetwork {
             new_bias_layer
         };
 
+        // Randomly pick the output bias from the first or second network
+        let gen_val = rng.gen::<f64>();
+        let new_output_bias = if rng.gen_bool(gen_val) {
+            self.output_bias.clone()
+        } else {
+            net_to_exchange.output_bias.clone()
+        };
+
+        // The child is recurrent when either parent is. Layers coming from a parent that is not recurrent get a zero
+        // context layer.
+        let recurrent = self.recurrent || net_to_exchange.recurrent;
         let mut new_net = NeuralNetwork {
             inputs: new_inputs,
             hidden: new_hidden,
             outputs: self.outputs.clone(),
             bias: new_bias,
-            seed: self.seed.clone(),
+            output_bias: new_output_bias,
+            hidden_activations: new_hidden_activations,
+            output_activation: self.output_activation,
+            recurrent,
+            context: if recurrent { new_context } else { Vec::new() },
+            state: Vec::new(),
+            weight_initializer: self.weight_initializer,
         };
 
-        new_net.fix_weights();
+        new_net.fix_weights(rng);
 
         Some(new_net)
     }
-    fn fix_weights(&mut self) {
-        // TODO: Refactor methods
+    // Creates a network with the topology of this network. Nodes are aligned with `net_to_exchange` by layer and node
+    // position, and every aligned pair is merged by `combine_nodes`, which receives this node's weights and the other node's
+    // weights resized to the same length (missing weights are taken from this node, extra weights are dropped).
+    // Nodes without a counterpart are copied unchanged.
+    pub fn recombine_nodes_and_create_new_from(
+        &self,
+        net_to_exchange: &NeuralNetwork,
+        mut combine_nodes: impl FnMut(&[f64], &[f64]) -> Vec<f64>,
+    ) -> NeuralNetwork {
+        let mut new_net = self.clone();
+
+        recombine_layer(
+            &mut new_net.inputs,
+            &net_to_exchange.inputs,
+            &mut combine_nodes,
+        );
+        for (layer, other_layer) in new_net.hidden.iter_mut().zip(net_to_exchange.hidden.iter()) {
+            recombine_layer(layer, other_layer, &mut combine_nodes);
+        }
+        for (layer, other_layer) in new_net.bias.iter_mut().zip(net_to_exchange.bias.iter()) {
+            recombine_layer(layer, other_layer, &mut combine_nodes);
+        }
+        recombine_layer(
+            std::slice::from_mut(&mut new_net.output_bias),
+            std::slice::from_ref(&net_to_exchange.output_bias),
+            &mut combine_nodes,
+        );
+        for (layer, other_layer) in new_net
+            .context
+            .iter_mut()
+            .zip(net_to_exchange.context.iter())
+        {
+            recombine_layer(layer, other_layer, &mut combine_nodes);
+        }
+
+        new_net
+    }
+
+    fn fix_weights(&mut self, rng: &mut impl Rng) {
+        self.fix_output_bias_weights();
+        self.fix_context_weights();
+        let initializer = self.weight_initializer;
         if self.is_hidden_layer_empty() {
-            for node in self.inputs.iter_mut() {
-                if self.outputs.len() > node.connection_weights.len() {
-                    let weights_to_add = self.outputs.len() - node.connection_weights.len();
-                    for _ in 0..weights_to_add {
-                        node.connection_weights.push(self.seed.gen::<f64>());
-                    }
-                } else if self.outputs.len() < node.connection_weights.len() {
-                    let weights_to_remove = node.connection_weights.len() - self.outputs.len();
-                    for _ in 0..weights_to_remove {
-                        node.connection_weights
-                            .remove(node.connection_weights.len() - 1);
-                    }
-                }
-            }
+            let fan_in = self.inputs.len();
+            fit_connection_weights(
+                &mut self.inputs,
+                self.outputs.len(),
+                fan_in,
+                initializer,
+                rng,
+            );
             return;
         }
 
-        for node in self.inputs.iter_mut() {
-            if self.hidden[0].len() > node.connection_weights.len() {
-                let weights_to_add = self.hidden[0].len() - node.connection_weights.len();
-                for _ in 0..weights_to_add {
-                    node.connection_weights.push(self.seed.gen::<f64>());
-                }
-            } else if self.hidden[0].len() < node.connection_weights.len() {
-                let weights_to_remove = node.connection_weights.len() - self.hidden[0].len();
-                for _ in 0..weights_to_remove {
-                    node.connection_weights
-                        .remove(node.connection_weights.len() - 1);
-                }
-            }
+        let fan_in = self.inputs.len();
+        fit_connection_weights(
+            &mut self.inputs,
+            self.hidden[0].len(),
+            fan_in,
+            initializer,
+            rng,
+        );
+
+        for hidden_layer in 0..self.hidden.len() {
+            let fan_in = self.hidden[hidden_layer].len();
+            let fan_out = if hidden_layer + 1 < self.hidden.len() {
+                self.hidden[hidden_layer + 1].len()
+            } else {
+                self.outputs.len()
+            };
+            fit_connection_weights(
+                &mut self.hidden[hidden_layer],
+                fan_out,
+                fan_in,
+                initializer,
+                rng,
+            );
         }
 
-        for hidden_layer in 0..self.hidden.len() - 1 {
-            let next_hidden_layer_len = self.hidden[hidden_layer + 1].len();
-            for node in self.hidden[hidden_layer].iter_mut() {
-                if next_hidden_layer_len > node.connection_weights.len() {
-                    let weights_to_add = next_hidden_layer_len - node.connection_weights.len();
-                    for _ in 0..weights_to_add {
-                        node.connection_weights.push(self.seed.gen::<f64>());
-                    }
-                } else if next_hidden_layer_len < node.connection_weights.len() {
-                    let weights_to_remove = node.connection_weights.len() - next_hidden_layer_len;
-                    for _ in 0..weights_to_remove {
-                        node.connection_weights
-                            .remove(node.connection_weights.len() - 1);
-                    }
-                }
-            }
+        for bias_layer_index in 0..self.bias.len() {
+            let fan_in = self.fan_in(bias_layer_index);
+            let fan_out = self.hidden[bias_layer_index].len();
+            fit_connection_weights(
+                &mut self.bias[bias_layer_index],
+                fan_out,
+                fan_in,
+                initializer,
+                rng,
+            );
         }
-        let last_index = self.hidden.len() - 1;
+    }
 
-        for node in self.hidden[last_index].iter_mut() {
-            if self.outputs.len() > node.connection_weights.len() {
-                let weights_to_add = self.outputs.len() - node.connection_weights.len();
-                for _ in 0..weights_to_add {
-                    node.connection_weights.push(self.seed.gen::<f64>());
-                }
-            } else if self.outputs.len() < node.connection_weights.len() {
-                let weights_to_remove = node.connection_weights.len() - self.outputs.len();
-                for _ in 0..weights_to_remove {
-                    node.connection_weights
-                        .remove(node.connection_weights.len() - 1);
+    // Number of nodes feeding into hidden layer layer_index, or into the outputs for the index after the last hidden layer.
+    fn fan_in(&self, layer_index: usize) -> usize {
+        if layer_index == 0 {
+            self.inputs.len()
+        } else {
+            self.hidden[layer_index - 1].len()
+        }
+    }
+
+    pub fn weight_initializer(&self) -> WeightInitializer {
+        self.weight_initializer
+    }
+
+    // Only affects weights created from now on, by growing the network or by feedforward_new_inputs.
+    pub fn set_weight_initializer(&mut self, initializer: WeightInitializer) {
+        self.weight_initializer = initializer;
+    }
+
+    // Flattens every connection weight of the network in the order inputs, hidden layers, bias layers, output bias and
+    // context layers.
+    pub fn weights(&self) -> Vec<f64> {
+        self.nodes()
+            .flat_map(|node| node.connection_weights.iter().cloned())
+            .collect()
+    }
+
+    // Overwrites every connection weight using the same order as `weights`.
+    pub fn set_weights(&mut self, weights: &[f64]) {
+        if weights.len() != self.weight_count() {
+            panic!(
+                "The number of weights given does not match the network. Expected {} weights but got {}",
+                self.weight_count(),
+                weights.len()
+            );
+        }
+        for (weight, new_weight) in self.weights_mut().zip(weights.iter()) {
+            *weight = *new_weight;
+        }
+    }
+
+    // Every connection weight of the input, hidden and bias nodes.
+    pub fn weights_mut(&mut self) -> impl Iterator<Item = &mut f64> {
+        self.nodes_mut()
+            .flat_map(|node| node.connection_weights.iter_mut())
+    }
+
+    // Fan in and fan out the weight initializer draws every connection weight with, in the same order as `weights`.
+    pub fn weight_fans(&self) -> Vec<(usize, usize)> {
+        let mut fans = Vec::with_capacity(self.weight_count());
+        let mut push_layer = |layer: &[NeuralNode], fan_in: usize| {
+            for node in layer.iter() {
+                let fan_out = node.connection_weights.len();
+                for _ in 0..fan_out {
+                    fans.push((fan_in, fan_out));
                 }
             }
+        };
+        push_layer(&self.inputs, self.inputs.len());
+        for layer in self.hidden.iter() {
+            push_layer(layer, layer.len());
+        }
+        for (layer_index, layer) in self.bias.iter().enumerate() {
+            push_layer(layer, self.fan_in(layer_index));
         }
+        push_layer(
+            std::slice::from_ref(&self.output_bias),
+            self.fan_in(self.hidden.len()),
+        );
+        for layer in self.context.iter() {
+            push_layer(layer, layer.len());
+        }
+        fans
+    }
 
-        for bias_layer_index in 0..self.bias.len() {
-            for node in self.bias[bias_layer_index].iter_mut() {
-                if self.hidden[bias_layer_index].len() > node.connection_weights.len() {
-                    let weights_to_add =
-                        self.hidden[bias_layer_index].len() - node.connection_weights.len();
-                    for _ in 0..weights_to_add {
-                        node.connection_weights.push(self.seed.gen::<f64>());
-                    }
-                } else if self.hidden[bias_layer_index].len() < node.connection_weights.len() {
-                    let weights_to_remove =
-                        node.connection_weights.len() - self.hidden[bias_layer_index].len();
-                    for _ in 0..weights_to_remove {
-                        node.connection_weights
-                            .remove(node.connection_weights.len() - 1);
-                    }
-                }
+    pub fn weight_count(&self) -> usize {
+        self.nodes().map(|node| node.connection_weights.len()).sum()
+    }
+
+    // Every trainable weight, including bias and context weights. Same as weight_count.
+    pub fn parameter_count(&self) -> usize {
+        self.weight_count()
+    }
+
+    // Input, hidden and output nodes. Bias nodes are not counted.
+    pub fn node_count(&self) -> usize {
+        self.inputs.len() + self.hidden.iter().map(Vec::len).sum::<usize>() + self.outputs.len()
+    }
+
+    // Number of weight layers between the inputs and the outputs.
+    pub fn depth(&self) -> usize {
+        self.hidden.len() + 1
+    }
+
+    // Floating point operations of one feedforward: a multiply and an add per weight and one activation per hidden and
+    // output node.
+    pub fn flops(&self) -> usize {
+        2 * self.weight_count() + self.node_count() - self.inputs.len()
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = &NeuralNode> {
+        self.inputs
+            .iter()
+            .chain(self.hidden.iter().flatten())
+            .chain(self.bias.iter().flatten())
+            .chain(std::iter::once(&self.output_bias))
+            .chain(self.context.iter().flatten())
+    }
+
+    fn nodes_mut(&mut self) -> impl Iterator<Item = &mut NeuralNode> {
+        self.inputs
+            .iter_mut()
+            .chain(self.hidden.iter_mut().flatten())
+            .chain(self.bias.iter_mut().flatten())
+            .chain(std::iter::once(&mut self.output_bias))
+            .chain(self.context.iter_mut().flatten())
+    }
+
+    // The output bias only loses or gains weights when the number of outputs changes or when an older network without an
+    // output bias is loaded. New weights are 0 so the outputs of an older network do not change.
+    fn fix_output_bias_weights(&mut self) {
+        self.output_bias
+            .connection_weights
+            .resize(self.outputs.len(), 0.0);
+    }
+
+    // Keeps every hidden layer of a recurrent network paired with a square context layer. Context weights are only ever
+    // added at 0, so a new node or layer does not start out with recurrence. The state no longer fits after a topology
+    // change and is reset.
+    fn fix_context_weights(&mut self) {
+        if !self.recurrent {
+            return;
+        }
+        self.context.resize(self.hidden.len(), Vec::new());
+        for (layer_index, context_layer) in self.context.iter_mut().enumerate() {
+            let layer_length = self.hidden[layer_index].len();
+            context_layer.resize(layer_length, NeuralNode::default());
+            for node in context_layer.iter_mut() {
+                node.connection_weights.resize(layer_length, 0.0);
             }
         }
+        self.reset_state();
+    }
+
+    fn context_layer(&self, layer_index: usize) -> Vec<NeuralNode> {
+        self.context.get(layer_index).cloned().unwrap_or_default()
+    }
+
+    pub fn is_recurrent(&self) -> bool {
+        self.recurrent
+    }
+
+    // Activations of every hidden layer from the last step.
+    pub fn state(&self) -> &Vec<Vec<f64>> {
+        &self.state
+    }
+
+    pub fn reset_state(&mut self) {
+        if !self.recurrent {
+            return;
+        }
+        self.state = self
+            .hidden
+            .iter()
+            .map(|hidden_layer| vec![0.0; hidden_layer.len()])
+            .collect();
     }
+
     pub fn hidden_layer_length(&self, index: usize) -> usize {
         self.hidden[index].len()
     }
@@ -219,42 +445,178 @@ impl NeuralNetwork {
         self.hidden.is_empty()
     }
 
+    pub fn hidden_activation(&self, layer_index: usize) -> Activation {
+        self.hidden_activations
+            .get(layer_index)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_hidden_activation(&mut self, layer_index: usize, activation: Activation) {
+        if self.hidden_activations.len() < self.hidden.len() {
+            self.hidden_activations
+                .resize(self.hidden.len(), Activation::default());
+        }
+        self.hidden_activations[layer_index] = activation;
+    }
+
+    pub fn output_activation(&self) -> Activation {
+        self.output_activation
+    }
+
+    pub fn set_output_activation(&mut self, activation: Activation) {
+        self.output_activation = activation;
+    }
+
     // Will delete hidden node layer if the current hidden node layer has 1 node.
-    pub fn remove_hidden_node(&mut self, layer_index: usize) {
+    pub fn remove_hidden_node(&mut self, layer_index: usize, rng: &mut impl Rng) {
         if !self.is_hidden_layer_empty() && self.hidden[layer_index].len() == 1 {
             self.hidden.remove(layer_index);
             self.bias.remove(layer_index);
+            if layer_index < self.hidden_activations.len() {
+                self.hidden_activations.remove(layer_index);
+            }
+            if layer_index < self.context.len() {
+                self.context.remove(layer_index);
+            }
         } else {
             let last_element_index = self.hidden[layer_index].len() - 1;
             self.hidden[layer_index].remove(last_element_index);
         }
 
-        self.fix_weights();
+        self.fix_weights(rng);
+    }
+
+    // Removes the given node of a hidden layer together with the weights going into it from the layer before, its bias
+    // layer and its context layer. Like remove_hidden_node the whole layer is removed when it only has this node.
+    pub fn remove_hidden_node_at(
+        &mut self,
+        layer_index: usize,
+        node_index: usize,
+        rng: &mut impl Rng,
+    ) {
+        if node_index >= self.hidden[layer_index].len() {
+            panic!(
+                "Hidden layer {} has no node {}, it only has {} nodes.",
+                layer_index,
+                node_index,
+                self.hidden[layer_index].len()
+            );
+        }
+        if self.hidden[layer_index].len() == 1 {
+            self.remove_hidden_node(layer_index, rng);
+            return;
+        }
+
+        self.hidden[layer_index].remove(node_index);
+        let previous_layer = if layer_index == 0 {
+            &mut self.inputs
+        } else {
+            &mut self.hidden[layer_index - 1]
+        };
+        for node in previous_layer
+            .iter_mut()
+            .chain(self.bias[layer_index].iter_mut())
+        {
+            if node_index < node.connection_weights.len() {
+                node.connection_weights.remove(node_index);
+            }
+        }
+        if let Some(context_layer) = self.context.get_mut(layer_index) {
+            if node_index < context_layer.len() {
+                context_layer.remove(node_index);
+            }
+            for node in context_layer.iter_mut() {
+                if node_index < node.connection_weights.len() {
+                    node.connection_weights.remove(node_index);
+                }
+            }
+        }
+
+        self.fix_weights(rng);
     }
 
-    pub fn push_hidden_node(&mut self, layer_index: usize) {
+    // Inserts a hidden layer in front of hidden layer layer_index, or in front of the outputs when layer_index is the
+    // number of hidden layers. The new layer is an exact identity: it is as wide as the layer it feeds, takes over that
+    // layer's incoming weights and bias, uses a linear activation and passes its values on with weight 1. The outputs of
+    // the network do not change until the new layer is mutated.
+    pub fn insert_hidden_layer(&mut self, layer_index: usize) {
+        if layer_index > self.hidden.len() {
+            panic!(
+                "Can not insert a hidden layer at {}, the network only has {} hidden layers.",
+                layer_index,
+                self.hidden.len()
+            );
+        }
+        let layer_length = if layer_index < self.hidden.len() {
+            self.hidden[layer_index].len()
+        } else {
+            self.outputs.len()
+        };
+
+        let mut identity_layer = Vec::with_capacity(layer_length);
+        for node_index in 0..layer_length {
+            let mut weights = vec![0.0; layer_length];
+            weights[node_index] = 1.0;
+            identity_layer.push(NeuralNode::new(weights));
+        }
+
+        let zero_bias = NeuralNode::new(vec![0.0; layer_length]);
+        let moved_bias = if layer_index < self.hidden.len() {
+            std::mem::replace(&mut self.bias[layer_index], vec![zero_bias])
+        } else {
+            vec![std::mem::replace(&mut self.output_bias, zero_bias)]
+        };
+
+        if self.hidden_activations.len() < self.hidden.len() {
+            self.hidden_activations
+                .resize(self.hidden.len(), Activation::default());
+        }
+        self.hidden.insert(layer_index, identity_layer);
+        self.bias.insert(layer_index, moved_bias);
+        self.hidden_activations
+            .insert(layer_index, Activation::Linear);
+        if self.recurrent {
+            self.context.insert(
+                layer_index,
+                vec![NeuralNode::new(vec![0.0; layer_length]); layer_length],
+            );
+        }
+
+        // Every weight of the identity layer is already in place, only the state has to be reset.
+        self.fix_output_bias_weights();
+        self.fix_context_weights();
+    }
+
+    pub fn push_hidden_node(&mut self, layer_index: usize, rng: &mut impl Rng) {
         let number_of_conn_weights = if self.hidden.len() - 1 == layer_index {
             self.outputs.len()
         } else {
             self.hidden[layer_index + 1].len()
         };
+        let fan_in = self.hidden[layer_index].len() + 1;
 
         let mut weights = Vec::new();
 
         for _ in 0..number_of_conn_weights {
-            weights.push(self.seed.gen::<f64>());
+            weights.push(
+                self.weight_initializer
+                    .sample(fan_in, number_of_conn_weights, rng),
+            );
         }
 
         self.hidden[layer_index].push(NeuralNode::new(weights));
-        self.fix_weights();
+        self.fix_weights(rng);
     }
 
-    pub fn push_hidden_node_with_new_layer_at_end(&mut self) {
+    pub fn push_hidden_node_with_new_layer_at_end(&mut self, rng: &mut impl Rng) {
+        let initializer = self.weight_initializer;
+        let fan_in = self.fan_in(self.hidden.len());
         let mut new_layer = Vec::new();
 
         let mut weights = Vec::new();
         for _ in 0..self.outputs.len() {
-            weights.push(self.seed.gen::<f64>());
+            weights.push(initializer.sample(1, self.outputs.len(), rng));
         }
         new_layer.push(NeuralNode::new(weights));
 
@@ -262,20 +624,54 @@ impl NeuralNetwork {
         if self.hidden.len() > 1 {
             let layer_before_index = self.hidden.len() - 2;
             for nodes in self.hidden[layer_before_index].iter_mut() {
-                nodes.connection_weights.push(self.seed.gen::<f64>());
+                nodes
+                    .connection_weights
+                    .push(initializer.sample(fan_in, 1, rng));
             }
         } else {
             for nodes in self.inputs.iter_mut() {
-                nodes.connection_weights.push(self.seed.gen::<f64>());
+                nodes
+                    .connection_weights
+                    .push(initializer.sample(fan_in, 1, rng));
             }
         }
         let mut new_bias_layer = Vec::new();
-        new_bias_layer.push(NeuralNode::new(vec![self.seed.gen::<f64>()]));
+        new_bias_layer.push(NeuralNode::new(vec![initializer.sample(fan_in, 1, rng)]));
         self.bias.push(new_bias_layer);
+        self.fix_output_bias_weights();
+        self.hidden_activations
+            .resize(self.hidden.len(), Activation::default());
+        self.fix_context_weights();
     }
     pub fn new(num_inputs: u32, hidden: &[u32], num_outputs: u32, seed: [u8; 32]) -> NeuralNetwork {
+        NeuralNetwork::with_initializer(
+            num_inputs,
+            hidden,
+            num_outputs,
+            WeightInitializer::default(),
+            seed,
+        )
+    }
+
+    // The initializer is kept, so weights added later by growing the network are drawn on the same scale.
+    pub fn with_initializer(
+        num_inputs: u32,
+        hidden: &[u32],
+        num_outputs: u32,
+        initializer: WeightInitializer,
+        seed: [u8; 32],
+    ) -> NeuralNetwork {
         let mut rng: ChaChaRng = SeedableRng::from_seed(seed);
+        NeuralNetwork::create(num_inputs, hidden, num_outputs, initializer, &mut rng)
+    }
 
+    fn create(
+        num_inputs: u32,
+        hidden: &[u32],
+        num_outputs: u32,
+        initializer: WeightInitializer,
+        rng: &mut ChaChaRng,
+    ) -> NeuralNetwork {
         // Get the number of connections the input layer has to connect to. If we have a hidden layer defined, connect inputs to first layer of hidden.
         // else connect inputs to outputs.
         let num_input_connections = if !hidden.is_empty() {
@@ -284,7 +680,8 @@ impl NeuralNetwork {
             u32::try_from(num_outputs).unwrap()
         };
 
-        let input_layer = NeuralNetwork::create_inputs(num_inputs, num_input_connections, &mut rng);
+        let input_layer =
+            NeuralNetwork::create_inputs(num_inputs, num_input_connections, initializer, rng);
 
         let mut hidden_layer: Vec<Vec<NeuralNode>> = Vec::new();
         if !hidden.is_empty() {
@@ -293,7 +690,11 @@ impl NeuralNetwork {
                 for _ in 0..hidden[current_layer] {
                     let mut weights = Vec::new();
                     for _ in 0..hidden[current_layer + 1] {
-                        weights.push(rng.gen_range(MIN_NEGATIVE_VALUE, MAX_POSITIVE_VALUE))
+                        weights.push(initializer.sample(
+                            hidden[current_layer] as usize,
+                            hidden[current_layer + 1] as usize,
+                            rng,
+                        ))
                     }
                     inter_hidden_layer.push(NeuralNode::new(weights))
                 }
@@ -303,7 +704,11 @@ impl NeuralNetwork {
             for _ in 0..hidden[hidden.len() - 1] {
                 let mut weights = Vec::new();
                 for _ in 0..num_outputs {
-                    weights.push(rng.gen_range(MIN_NEGATIVE_VALUE, MAX_POSITIVE_VALUE))
+                    weights.push(initializer.sample(
+                        hidden[hidden.len() - 1] as usize,
+                        num_outputs as usize,
+                        rng,
+                    ))
                 }
                 inter_hidden_layer.push(NeuralNode::new(weights));
             }
@@ -319,43 +724,105 @@ impl NeuralNetwork {
         for current_layer in 0..hidden.len() {
             let mut inter_bias_layer = Vec::new();
             // Create 1 bias node per hidden layer
-            // TODO: Add bias to output layer
             for _ in 0..1 {
                 let mut weights = Vec::new();
+                let fan_in = if current_layer == 0 {
+                    num_inputs
+                } else {
+                    hidden[current_layer - 1]
+                };
                 for _ in 0..hidden[current_layer] {
-                    weights.push(rng.gen_range(MIN_NEGATIVE_VALUE, MAX_POSITIVE_VALUE))
+                    weights.push(initializer.sample(
+                        fan_in as usize,
+                        hidden[current_layer] as usize,
+                        rng,
+                    ))
                 }
                 inter_bias_layer.push(NeuralNode::new(weights));
             }
             bias_layer.push(inter_bias_layer);
         }
 
+        // Create 1 bias node for the output layer. Its weights are drawn last, so the weights above stay the same for a
+        // given seed.
+        let output_fan_in = hidden.last().cloned().unwrap_or(num_inputs);
+        let output_bias_weights = (0..num_outputs)
+            .map(|_| initializer.sample(output_fan_in as usize, num_outputs as usize, rng))
+            .collect();
+
         NeuralNetwork {
             inputs: input_layer,
             hidden: hidden_layer,
             outputs: output_layer,
             bias: bias_layer,
-            seed: rng,
+            output_bias: NeuralNode::new(output_bias_weights),
+            hidden_activations: vec![Activation::default(); hidden.len()],
+            output_activation: Activation::default(),
+            recurrent: false,
+            context: Vec::new(),
+            state: Vec::new(),
+            weight_initializer: initializer,
         }
     }
 
+    // Same as new, but every hidden layer also gets a context layer that feeds its activations of the previous step back
+    // into it. The weights drawn by new stay the same for a given seed.
+    pub fn new_recurrent(
+        num_inputs: u32,
+        hidden: &[u32],
+        num_outputs: u32,
+        seed: [u8; 32],
+    ) -> NeuralNetwork {
+        let mut rng: ChaChaRng = SeedableRng::from_seed(seed);
+        let mut net = NeuralNetwork::create(
+            num_inputs,
+            hidden,
+            num_outputs,
+            WeightInitializer::default(),
+            &mut rng,
+        );
+        net.recurrent = true;
+        for layer_size in hidden.iter() {
+            let mut context_layer = Vec::new();
+            for _ in 0..*layer_size {
+                let mut weights = Vec::new();
+                for _ in 0..*layer_size {
+                    weights.push(net.weight_initializer.sample(
+                        *layer_size as usize,
+                        *layer_size as usize,
+                        &mut rng,
+                    ));
+                }
+                context_layer.push(NeuralNode::new(weights));
+            }
+            net.context.push(context_layer);
+        }
+        net.reset_state();
+        net
+    }
+
     fn create_inputs(
         num_inputs: u32,
         num_input_connections: u32,
-        rng: &mut ChaChaRng,
+        initializer: WeightInitializer,
+        rng: &mut impl Rng,
     ) -> Vec<NeuralNode> {
         let mut input_layer = Vec::new();
         for _ in 0..num_inputs {
             let mut weights = Vec::new();
             for _ in 0..num_input_connections {
-                weights.push(rng.gen_range(MIN_NEGATIVE_VALUE, MAX_POSITIVE_VALUE))
+                weights.push(initializer.sample(
+                    num_inputs as usize,
+                    num_input_connections as usize,
+                    rng,
+                ))
             }
             input_layer.push(NeuralNode::new(weights));
         }
         input_layer
     }
 
-    pub fn feedforward_new_inputs(&mut self, inputs: &[f64]) -> Vec<f64> {
+    pub fn feedforward_new_inputs(&mut self, inputs: &[f64], rng: &mut impl Rng) -> Vec<f64> {
         // Get the number of connections the input layer has to connect to. If we have a hidden layer defined, connect inputs to first layer of hidden.
         // else connect inputs to outputs.
         let num_input_connections = if !self.hidden.is_empty() {
@@ -367,14 +834,31 @@ impl NeuralNetwork {
         let new_inputs_nodes = NeuralNetwork::create_inputs(
             inputs.len() as u32,
             num_input_connections as u32,
-            &mut self.seed,
+            self.weight_initializer,
+            rng,
         );
 
         self.inputs = new_inputs_nodes;
         self.feedforward(inputs)
     }
 
+    // For a recurrent network this uses the state of the last step without advancing it, see step.
     pub fn feedforward(&self, inputs: &[f64]) -> Vec<f64> {
+        self.forward(inputs, &mut Vec::new())
+    }
+
+    // Runs one time step. Every hidden layer also receives its own activations of the previous step through its context
+    // layer, and the new activations become the state for the next step. Same as feedforward when not recurrent.
+    pub fn step(&mut self, inputs: &[f64]) -> Vec<f64> {
+        let mut new_state = Vec::with_capacity(self.hidden.len());
+        let outputs = self.forward(inputs, &mut new_state);
+        if self.recurrent {
+            self.state = new_state;
+        }
+        outputs
+    }
+
+    fn forward(&self, inputs: &[f64], new_state: &mut Vec<Vec<f64>>) -> Vec<f64> {
         let mut layer_output: Vec<f64> = vec![0.0; self.inputs[0].connection_weights.len()];
         for (input_index, input_node) in self.inputs.iter().enumerate() {
             for (index, weights) in input_node.connection_weights.iter().enumerate() {
@@ -389,8 +873,22 @@ impl NeuralNetwork {
                         layer_output[weight_index] += weights * BIAS_VALUE;
                     }
                 }
-                for index in 0..layer_output.len() {
-                    layer_output[index] = sigmoid(layer_output[index].borrow());
+                if self.recurrent {
+                    let context_layer = &self.context[hidden_layer_index];
+                    let previous_state = &self.state[hidden_layer_index];
+                    for (context_node, previous) in context_layer.iter().zip(previous_state.iter())
+                    {
+                        for (weight_index, weights) in
+                            context_node.connection_weights.iter().enumerate()
+                        {
+                            layer_output[weight_index] += previous * weights;
+                        }
+                    }
+                }
+                self.hidden_activation(hidden_layer_index)
+                    .apply(&mut layer_output);
+                if self.recurrent {
+                    new_state.push(layer_output.clone());
                 }
 
                 let mut next_layer_output: Vec<f64> =
@@ -403,14 +901,356 @@ impl NeuralNetwork {
                 }
                 layer_output = next_layer_output;
             }
-        } else {
-            // TODO: Add bias layer to output
         }
-        for index in 0..layer_output.len() {
-            layer_output[index] = sigmoid(layer_output[index].borrow());
+        for (weight_index, weights) in self.output_bias.connection_weights.iter().enumerate() {
+            layer_output[weight_index] += weights * BIAS_VALUE;
         }
+        self.output_activation.apply(&mut layer_output);
         layer_output.to_vec()
     }
+
+    // Gradient of a loss with respect to every weight, in the same order as `weights`. output_gradient receives the
+    // outputs of the network and returns the derivative of the loss with respect to each of them. For a recurrent
+    // network the state of the last step is treated as a constant input, so the gradient is truncated after one step.
+    pub fn gradient(
+        &self,
+        inputs: &[f64],
+        output_gradient: impl FnOnce(&[f64]) -> Vec<f64>,
+    ) -> Vec<f64> {
+        // Every layer that has outgoing weights, starting with the inputs.
+        let layers: Vec<&Vec<NeuralNode>> = std::iter::once(&self.inputs)
+            .chain(self.hidden.iter())
+            .collect();
+        let last_layer_index = layers.len() - 1;
+
+        // Forward pass that keeps the values before and after activation of every layer.
+        let mut activated_layers: Vec<Vec<f64>> = vec![inputs.to_vec()];
+        let mut layer_sums: Vec<Vec<f64>> = vec![Vec::new()];
+        for (layer_index, layer) in layers.iter().enumerate() {
+            let mut layer_sum = vec![0.0; layer[0].connection_weights.len()];
+            for (node, value) in layer.iter().zip(activated_layers[layer_index].iter()) {
+                for (weight_index, weights) in node.connection_weights.iter().enumerate() {
+                    layer_sum[weight_index] += value * weights;
+                }
+            }
+
+            let activation = if layer_index < last_layer_index {
+                for bias_node in self.bias[layer_index].iter() {
+                    for (weight_index, weights) in bias_node.connection_weights.iter().enumerate() {
+                        layer_sum[weight_index] += weights * BIAS_VALUE;
+                    }
+                }
+                if self.recurrent {
+                    for (context_node, previous) in self.context[layer_index]
+                        .iter()
+                        .zip(self.state[layer_index].iter())
+                    {
+                        for (weight_index, weights) in
+                            context_node.connection_weights.iter().enumerate()
+                        {
+                            layer_sum[weight_index] += previous * weights;
+                        }
+                    }
+                }
+                self.hidden_activation(layer_index)
+            } else {
+                for (weight_index, weights) in
+                    self.output_bias.connection_weights.iter().enumerate()
+                {
+                    layer_sum[weight_index] += weights * BIAS_VALUE;
+                }
+                self.output_activation
+            };
+
+            let mut activated_layer = layer_sum.clone();
+            activation.apply(&mut activated_layer);
+            layer_sums.push(layer_sum);
+            activated_layers.push(activated_layer);
+        }
+
+        // Backward pass. delta holds the derivative of the loss with respect to the sums of the layer after layer_index.
+        let mut delta = output_gradient(&activated_layers[last_layer_index + 1]);
+        self.output_activation.backpropagate(
+            &layer_sums[last_layer_index + 1],
+            &activated_layers[last_layer_index + 1],
+            &mut delta,
+        );
+        let output_bias_gradient: Vec<f64> = delta.iter().map(|delta| delta * BIAS_VALUE).collect();
+
+        let mut layer_gradients: Vec<Vec<f64>> = vec![Vec::new(); layers.len()];
+        let mut bias_gradients: Vec<Vec<f64>> = vec![Vec::new(); self.hidden.len()];
+        let mut context_gradients: Vec<Vec<f64>> = vec![Vec::new(); self.context.len()];
+        for layer_index in (0..layers.len()).rev() {
+            let layer = layers[layer_index];
+            for (node, value) in layer.iter().zip(activated_layers[layer_index].iter()) {
+                for weight_index in 0..node.connection_weights.len() {
+                    layer_gradients[layer_index].push(value * delta[weight_index]);
+                }
+            }
+            if layer_index == 0 {
+                break;
+            }
+
+            let hidden_layer_index = layer_index - 1;
+            let mut previous_delta: Vec<f64> = layer
+                .iter()
+                .map(|node| {
+                    node.connection_weights
+                        .iter()
+                        .zip(delta.iter())
+                        .map(|(weights, delta)| weights * delta)
+                        .sum()
+                })
+                .collect();
+            self.hidden_activation(hidden_layer_index).backpropagate(
+                &layer_sums[layer_index],
+                &activated_layers[layer_index],
+                &mut previous_delta,
+            );
+
+            for bias_node in self.bias[hidden_layer_index].iter() {
+                for weight_index in 0..bias_node.connection_weights.len() {
+                    bias_gradients[hidden_layer_index]
+                        .push(BIAS_VALUE * previous_delta[weight_index]);
+                }
+            }
+            if self.recurrent {
+                for (context_node, previous) in self.context[hidden_layer_index]
+                    .iter()
+                    .zip(self.state[hidden_layer_index].iter())
+                {
+                    for weight_index in 0..context_node.connection_weights.len() {
+                        context_gradients[hidden_layer_index]
+                            .push(previous * previous_delta[weight_index]);
+                    }
+                }
+            }
+            delta = previous_delta;
+        }
+
+        layer_gradients
+            .into_iter()
+            .chain(bias_gradients.into_iter())
+            .chain(std::iter::once(output_bias_gradient))
+            .chain(context_gradients.into_iter())
+            .flatten()
+            .collect()
+    }
+
+    // Weights, biases and activations of the network in the versioned portable model format, without the random
+    // generator. The current recurrent state is not part of the model.
+    pub fn to_portable_model(&self) -> PortableModel {
+        let mut layers = Vec::new();
+        for layer_index in 0..=self.hidden.len() {
+            let source_layer = if layer_index == 0 {
+                &self.inputs
+            } else {
+                &self.hidden[layer_index - 1]
+            };
+            let is_output_layer = layer_index == self.hidden.len();
+            let number_of_nodes = if is_output_layer {
+                self.outputs.len()
+            } else {
+                self.hidden[layer_index].len()
+            };
+            let incoming_weights = |nodes: &[NeuralNode], node_index: usize| -> Vec<f64> {
+                nodes
+                    .iter()
+                    .map(|node| {
+                        node.connection_weights
+                            .get(node_index)
+                            .cloned()
+                            .unwrap_or(0.0)
+                    })
+                    .collect()
+            };
+
+            let bias_nodes: Vec<&NeuralNode> = if is_output_layer {
+                vec![&self.output_bias]
+            } else {
+                self.bias[layer_index].iter().collect()
+            };
+            let biases = (0..number_of_nodes)
+                .map(|node_index| {
+                    bias_nodes.iter().fold(0.0, |bias, bias_node| {
+                        bias + bias_node
+                            .connection_weights
+                            .get(node_index)
+                            .cloned()
+                            .unwrap_or(0.0)
+                            * BIAS_VALUE
+                    })
+                })
+                .collect();
+            let context = if self.recurrent && !is_output_layer {
+                (0..number_of_nodes)
+                    .map(|node_index| incoming_weights(&self.context[layer_index], node_index))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            layers.push(PortableLayer {
+                activation: if is_output_layer {
+                    self.output_activation
+                } else {
+                    self.hidden_activation(layer_index)
+                },
+                weights: (0..number_of_nodes)
+                    .map(|node_index| incoming_weights(source_layer, node_index))
+                    .collect(),
+                biases,
+                context,
+            });
+        }
+
+        PortableModel {
+            format_version: PORTABLE_MODEL_VERSION,
+            number_of_inputs: self.inputs.len(),
+            recurrent: self.recurrent,
+            layers,
+        }
+    }
+
+    // Every hidden layer of the network gets a single bias node.
+    pub fn from_portable_model(model: &PortableModel) -> Result<NeuralNetwork, PortableModelError> {
+        model.validate()?;
+        let outgoing_weights = |layer: &PortableLayer, node_index: usize| -> NeuralNode {
+            NeuralNode::new(layer.weights.iter().map(|row| row[node_index]).collect())
+        };
+        let hidden_layers = &model.layers[..model.layers.len() - 1];
+        let output_layer = &model.layers[model.layers.len() - 1];
+
+        let hidden = (0..hidden_layers.len())
+            .map(|layer_index| {
+                (0..hidden_layers[layer_index].number_of_nodes())
+                    .map(|node_index| outgoing_weights(&model.layers[layer_index + 1], node_index))
+                    .collect()
+            })
+            .collect();
+        let context = if model.recurrent {
+            hidden_layers
+                .iter()
+                .map(|layer| {
+                    (0..layer.number_of_nodes())
+                        .map(|node_index| {
+                            NeuralNode::new(
+                                layer.context.iter().map(|row| row[node_index]).collect(),
+                            )
+                        })
+                        .collect()
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut net = NeuralNetwork {
+            inputs: (0..model.number_of_inputs)
+                .map(|node_index| outgoing_weights(&model.layers[0], node_index))
+                .collect(),
+            hidden,
+            outputs: vec![NeuralNode::default(); output_layer.number_of_nodes()],
+            bias: hidden_layers
+                .iter()
+                .map(|layer| vec![NeuralNode::new(layer.biases.clone())])
+                .collect(),
+            output_bias: NeuralNode::new(output_layer.biases.clone()),
+            hidden_activations: hidden_layers.iter().map(|layer| layer.activation).collect(),
+            output_activation: output_layer.activation,
+            recurrent: model.recurrent,
+            context,
+            state: Vec::new(),
+            weight_initializer: WeightInitializer::default(),
+        };
+        net.reset_state();
+        Ok(net)
+    }
+
+    // Graphviz DOT graph of the network, see PortableModel::to_dot.
+    pub fn to_dot(&self, pruning_threshold: f64) -> String {
+        self.to_portable_model().to_dot(pruning_threshold)
+    }
+
+    // Flattens the network into dense weight matrices for fast repeated inference. Use compile::<f32>() to trade
+    // precision for speed.
+    pub fn compile<S: Scalar>(&self) -> CompiledNetwork<S> {
+        if self.recurrent {
+            panic!("Recurrent networks keep state between steps and cannot be compiled.");
+        }
+        let mut layers = Vec::with_capacity(self.hidden.len() + 1);
+        let mut previous_layer = &self.inputs;
+        for (hidden_layer_index, hidden_layer) in self.hidden.iter().enumerate() {
+            layers.push(create_dense_layer(
+                previous_layer,
+                self.bias[hidden_layer_index].iter().collect(),
+                self.hidden_activation(hidden_layer_index),
+            ));
+            previous_layer = hidden_layer;
+        }
+        layers.push(create_dense_layer(
+            previous_layer,
+            vec![&self.output_bias],
+            self.output_activation,
+        ));
+        CompiledNetwork::new(layers)
+    }
+}
+
+// Two networks are equal when they have the same topology, activations and recurrence and bit for bit the same weights.
+// The recurrent state and the initializer for future weights are not compared.
+impl PartialEq for NeuralNetwork {
+    fn eq(&self, other: &NeuralNetwork) -> bool {
+        self.comparison_key() == other.comparison_key()
+    }
+}
+
+impl Eq for NeuralNetwork {}
+
+impl Hash for NeuralNetwork {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.comparison_key().hash(state);
+    }
+}
+
+impl NeuralNetwork {
+    fn comparison_key(&self) -> (Vec<usize>, Vec<u64>) {
+        let mut shape = vec![
+            self.inputs.len(),
+            self.hidden.len(),
+            self.outputs.len(),
+            self.bias.len(),
+            self.context.len(),
+            self.recurrent as usize,
+        ];
+        shape.extend(self.hidden.iter().map(Vec::len));
+        shape.extend(self.bias.iter().map(Vec::len));
+        shape.extend(self.nodes().map(|node| node.connection_weights.len()));
+
+        let mut values: Vec<u64> = self
+            .nodes()
+            .flat_map(|node| node.connection_weights.iter())
+            .map(|weight| weight.to_bits())
+            .collect();
+        for layer_index in 0..self.hidden.len() {
+            values.extend(activation_key(self.hidden_activation(layer_index)).iter());
+        }
+        values.extend(activation_key(self.output_activation).iter());
+        (shape, values)
+    }
+}
+
+fn activation_key(activation: Activation) -> [u64; 2] {
+    match activation {
+        Activation::Sigmoid => [0, 0],
+        Activation::Relu => [1, 0],
+        Activation::LeakyRelu { slope } => [2, slope.to_bits()],
+        Activation::Tanh => [3, 0],
+        Activation::Softmax => [4, 0],
+        Activation::Linear => [5, 0],
+        Activation::Step => [6, 0],
+        Activation::Gaussian => [7, 0],
+    }
 }
 
 impl std::fmt::Display for NeuralNetwork {
@@ -419,7 +1259,10 @@ impl std::fmt::Display for NeuralNetwork {
         writeln!(f, "Hidden Layer: {:#?}", self.hidden).unwrap();
         writeln!(f, "Output Layer: {:#?}", self.outputs).unwrap();
         writeln!(f, "Bias Layer: {:#?}", self.bias).unwrap();
-        writeln!(f, "Seed: {:#?}", self.seed)
+        writeln!(f, "Output Bias: {}", self.output_bias).unwrap();
+        writeln!(f, "Hidden Activations: {:?}", self.hidden_activations).unwrap();
+        writeln!(f, "Output Activation: {}", self.output_activation).unwrap();
+        writeln!(f, "Context Layer: {:#?}", self.context)
     }
 }
 
@@ -429,18 +1272,88 @@ impl std::fmt::Display for NeuralNode {
     }
 }
 
-fn sigmoid(a: &f64) -> f64 {
-    1.0 / (1.0 + (-a).exp())
+// Transposes a layer of nodes, each holding its outgoing weights, into a row-major matrix of incoming weights.
+fn create_dense_layer<S: Scalar>(
+    layer: &[NeuralNode],
+    bias_nodes: Vec<&NeuralNode>,
+    activation: Activation,
+) -> DenseLayer<S> {
+    let number_of_outputs = layer[0].connection_weights.len();
+    let mut weights = Vec::with_capacity(layer.len() * number_of_outputs);
+    for node in layer.iter() {
+        for output_index in 0..number_of_outputs {
+            let weight = node
+                .connection_weights
+                .get(output_index)
+                .cloned()
+                .unwrap_or(0.0);
+            weights.push(S::from_f64(weight));
+        }
+    }
+    let biases = bias_nodes
+        .iter()
+        .map(|bias_node| {
+            bias_node
+                .connection_weights
+                .iter()
+                .map(|weight| S::from_f64(weight * BIAS_VALUE))
+                .collect()
+        })
+        .collect();
+    DenseLayer::new(layer.len(), number_of_outputs, weights, biases, activation)
+}
+
+// Drops the surplus connection weights of every node, or draws the missing ones with the initializer.
+fn fit_connection_weights(
+    layer: &mut [NeuralNode],
+    number_of_weights: usize,
+    fan_in: usize,
+    initializer: WeightInitializer,
+    rng: &mut impl Rng,
+) {
+    for node in layer.iter_mut() {
+        node.connection_weights.truncate(number_of_weights);
+        while node.connection_weights.len() < number_of_weights {
+            node.connection_weights
+                .push(initializer.sample(fan_in, number_of_weights, rng));
+        }
+    }
+}
+
+fn recombine_layer(
+    layer: &mut [NeuralNode],
+    other_layer: &[NeuralNode],
+    combine_nodes: &mut impl FnMut(&[f64], &[f64]) -> Vec<f64>,
+) {
+    for (node, other_node) in layer.iter_mut().zip(other_layer.iter()) {
+        let mut aligned_weights = node.connection_weights.clone();
+        for (weight, other_weight) in aligned_weights
+            .iter_mut()
+            .zip(other_node.connection_weights.iter())
+        {
+            *weight = *other_weight;
+        }
+        node.connection_weights = combine_nodes(&node.connection_weights, &aligned_weights);
+    }
 }
 
 #[cfg(test)]
 mod neural_network_test {
+    use crate::neural_network::activation::Activation;
     use crate::neural_network::neural_network::NeuralNetwork;
+    use crate::neural_network::weight_initializer::WeightInitializer;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
     use std::borrow::Borrow;
+    use std::collections::HashSet;
     const DEFAULT_SEED: &[u8; 32] = &[
         1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
         3, 4,
     ];
+
+    fn create_rng() -> StdRng {
+        SeedableRng::from_seed(*DEFAULT_SEED)
+    }
     #[test]
     fn network_creation_test() {
         let xs: [u32; 5] = [1, 2, 3, 4, 5];
@@ -476,6 +1389,7 @@ mod neural_network_test {
         assert_eq!(net.inputs[2].connection_weights.len(), 1);
         assert_eq!(net.inputs[3].connection_weights.len(), 1);
         assert_eq!(net.inputs[4].connection_weights.len(), 1);
+        assert_eq!(net.output_bias.connection_weights.len(), 1);
         let xs: [u32; 0] = [];
         let data = 2;
         let net = NeuralNetwork::new(5, xs.as_ref(), data, *DEFAULT_SEED);
@@ -488,13 +1402,16 @@ mod neural_network_test {
         assert_eq!(net.inputs[2].connection_weights.len(), 2);
         assert_eq!(net.inputs[3].connection_weights.len(), 2);
         assert_eq!(net.inputs[4].connection_weights.len(), 2);
+        assert_eq!(net.output_bias.connection_weights.len(), 2);
     }
 
     #[test]
     fn network_feedforward_test() {
+        // The output bias is drawn after every other weight, so without it the outputs are the same as before it existed.
         let xs: [u32; 1] = [1];
         let data = 1;
-        let net = NeuralNetwork::new(1, xs.as_ref(), data, *DEFAULT_SEED);
+        let mut net = NeuralNetwork::new(1, xs.as_ref(), data, *DEFAULT_SEED);
+        net.output_bias.connection_weights[0] = 0.0;
         let inputs = [1.0];
 
         let mut output = net.feedforward(inputs.as_ref());
@@ -503,9 +1420,20 @@ mod neural_network_test {
         assert_eq!(output.len(), 1);
         assert_eq!(output[0], 0.5032105036547196);
 
+        net.output_bias.connection_weights[0] = 0.25;
+        let hidden_output = Activation::Sigmoid.activate(
+            inputs[0] * net.inputs[0].connection_weights[0] + net.bias[0][0].connection_weights[0],
+        );
+        let expected_output = Activation::Sigmoid.activate(
+            hidden_output * net.hidden[0][0].connection_weights[0]
+                + net.output_bias.connection_weights[0],
+        );
+        assert_eq!(net.feedforward(inputs.as_ref())[0], expected_output);
+
         let xs: [u32; 5] = [10, 10, 10, 10, 10];
         let data = 1;
-        let net = NeuralNetwork::new(1, xs.as_ref(), data, *DEFAULT_SEED);
+        let mut net = NeuralNetwork::new(1, xs.as_ref(), data, *DEFAULT_SEED);
+        net.output_bias.connection_weights[0] = 0.0;
         let inputs = [0.10];
 
         let mut output = net.feedforward(inputs.as_ref());
@@ -513,10 +1441,85 @@ mod neural_network_test {
         output = net.feedforward(inputs.as_ref());
         assert_eq!(output.len(), 1);
         assert_eq!(output[0], 0.5215843362050597);
+
+        net.output_bias.connection_weights[0] = -0.25;
+        let pre_activation = (output[0] / (1.0 - output[0])).ln();
+        assert!(
+            (net.feedforward(inputs.as_ref())[0]
+                - Activation::Sigmoid
+                    .activate(pre_activation + net.output_bias.connection_weights[0]))
+            .abs()
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn network_output_bias_initializer_test() {
+        let xs: [u32; 0] = [];
+        let net = NeuralNetwork::new(4, xs.as_ref(), 3, *DEFAULT_SEED);
+        assert!(net
+            .output_bias
+            .connection_weights
+            .iter()
+            .all(|weight| (-0.1..0.1).contains(weight) && *weight != 0.0));
+
+        let net = NeuralNetwork::with_initializer(
+            4,
+            xs.as_ref(),
+            3,
+            WeightInitializer::Zeros,
+            *DEFAULT_SEED,
+        );
+        assert_eq!(net.output_bias.connection_weights, vec![0.0; 3]);
+    }
+
+    #[test]
+    fn network_feedforward_no_hidden_layer_test() {
+        let xs: [u32; 0] = [];
+        let data = 2;
+        let mut net = NeuralNetwork::new(2, xs.as_ref(), data, *DEFAULT_SEED);
+        net.output_bias.connection_weights = vec![0.25, -0.5];
+        let inputs = [0.5, -1.0];
+
+        let output = net.feedforward(inputs.as_ref());
+        assert_eq!(output.len(), 2);
+        for output_index in 0..2 {
+            let expected_output = Activation::Sigmoid.activate(
+                inputs[0] * net.inputs[0].connection_weights[output_index]
+                    + inputs[1] * net.inputs[1].connection_weights[output_index]
+                    + net.output_bias.connection_weights[output_index],
+            );
+            assert_eq!(output[output_index], expected_output);
+        }
+    }
+
+    #[test]
+    fn network_output_bias_migration_test() {
+        let mut rng = create_rng();
+        let xs: [u32; 1] = [3];
+        let data = 2;
+        let mut net = NeuralNetwork::new(2, xs.as_ref(), data, *DEFAULT_SEED);
+        net.output_bias.connection_weights = vec![0.25, -0.5];
+
+        let mut json = serde_json::to_value(&net).unwrap();
+        json.as_object_mut().unwrap().remove("output_bias");
+        let mut legacy_net: NeuralNetwork = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy_net.output_bias.connection_weights.len(), 0);
+
+        let mut net_without_output_bias = net.clone();
+        net_without_output_bias.output_bias.connection_weights = vec![0.0; 2];
+        assert_eq!(
+            legacy_net.feedforward(&[1.0, 2.0]),
+            net_without_output_bias.feedforward(&[1.0, 2.0])
+        );
+
+        legacy_net.push_hidden_node(0, &mut rng);
+        assert_eq!(legacy_net.output_bias.connection_weights, vec![0.0, 0.0]);
     }
 
     #[test]
     fn network_add_node() {
+        let mut rng = create_rng();
         let xs: [u32; 1] = [1];
         let data = 1;
         let mut net = NeuralNetwork::new(1, xs.as_ref(), data, *DEFAULT_SEED);
@@ -526,14 +1529,14 @@ mod neural_network_test {
         assert_eq!(net.inputs[0].connection_weights.len(), 1);
         assert_eq!(net.bias[0][0].connection_weights.len(), 1);
 
-        net.push_hidden_node(0);
+        net.push_hidden_node(0, &mut rng);
 
         assert_eq!(net.hidden[0].len(), 2);
         assert_eq!(net.inputs[0].connection_weights.len(), 2);
         assert_eq!(net.hidden[0][1].connection_weights.len(), 1);
         assert_eq!(net.bias[0][0].connection_weights.len(), 2);
 
-        net.push_hidden_node(0);
+        net.push_hidden_node(0, &mut rng);
 
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.inputs[0].connection_weights.len(), 3);
@@ -549,7 +1552,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][1].connection_weights.len(), 2);
         assert_eq!(net.bias[0][0].connection_weights.len(), 2);
 
-        net.push_hidden_node(1);
+        net.push_hidden_node(1, &mut rng);
 
         assert_eq!(net.hidden[0].len(), 2);
         assert_eq!(net.hidden[1].len(), 3);
@@ -560,6 +1563,7 @@ mod neural_network_test {
 
     #[test]
     fn network_add_node_with_new_layer() {
+        let mut rng = create_rng();
         let xs: [u32; 0] = [];
         let data = 1;
         let mut net = NeuralNetwork::new(1, xs.as_ref(), data, *DEFAULT_SEED);
@@ -567,13 +1571,13 @@ mod neural_network_test {
         assert_eq!(net.hidden.len(), 0);
         assert_eq!(net.bias.len(), 0);
 
-        net.push_hidden_node_with_new_layer_at_end();
+        net.push_hidden_node_with_new_layer_at_end(&mut rng);
 
         assert_eq!(net.hidden.len(), 1);
         assert_eq!(net.bias.len(), 1);
         assert_eq!(net.hidden[0].len(), 1);
         assert_eq!(net.hidden[0][0].connection_weights.len(), 1);
-        net.push_hidden_node_with_new_layer_at_end();
+        net.push_hidden_node_with_new_layer_at_end(&mut rng);
 
         assert_eq!(net.hidden.len(), 2);
         assert_eq!(net.bias.len(), 2);
@@ -583,6 +1587,7 @@ mod neural_network_test {
 
     #[test]
     fn network_remove_node() {
+        let mut rng = create_rng();
         let xs: [u32; 5] = [1, 2, 3, 4, 5];
         let data = 2;
         let mut net = NeuralNetwork::new(1, xs.as_ref(), data, *DEFAULT_SEED);
@@ -604,7 +1609,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[1][0].connection_weights.len(), 3);
         assert_eq!(net.hidden[2][0].connection_weights.len(), 4);
         assert_eq!(net.hidden[3][0].connection_weights.len(), 5);
-        net.remove_hidden_node(0);
+        net.remove_hidden_node(0, &mut rng);
 
         assert_eq!(net.hidden.len(), 4);
         assert_eq!(net.hidden[0].len(), 2);
@@ -620,7 +1625,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 3);
         assert_eq!(net.hidden[1][0].connection_weights.len(), 4);
         assert_eq!(net.hidden[2][0].connection_weights.len(), 5);
-        net.remove_hidden_node(0);
+        net.remove_hidden_node(0, &mut rng);
 
         assert_eq!(net.hidden.len(), 4);
         assert_eq!(net.hidden[0].len(), 1);
@@ -637,7 +1642,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[1][0].connection_weights.len(), 4);
         assert_eq!(net.hidden[2][0].connection_weights.len(), 5);
 
-        net.remove_hidden_node(0);
+        net.remove_hidden_node(0, &mut rng);
         assert_eq!(net.hidden.len(), 3);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.hidden[1].len(), 4);
@@ -650,7 +1655,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 4);
         assert_eq!(net.hidden[1][0].connection_weights.len(), 5);
 
-        net.remove_hidden_node(2);
+        net.remove_hidden_node(2, &mut rng);
         assert_eq!(net.hidden.len(), 3);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.hidden[1].len(), 4);
@@ -663,7 +1668,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 4);
         assert_eq!(net.hidden[1][0].connection_weights.len(), 4);
 
-        net.remove_hidden_node(2);
+        net.remove_hidden_node(2, &mut rng);
         assert_eq!(net.hidden.len(), 3);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.hidden[1].len(), 4);
@@ -676,7 +1681,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 4);
         assert_eq!(net.hidden[1][0].connection_weights.len(), 3);
 
-        net.remove_hidden_node(2);
+        net.remove_hidden_node(2, &mut rng);
         assert_eq!(net.hidden.len(), 3);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.hidden[1].len(), 4);
@@ -689,7 +1694,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 4);
         assert_eq!(net.hidden[1][0].connection_weights.len(), 2);
 
-        net.remove_hidden_node(2);
+        net.remove_hidden_node(2, &mut rng);
         assert_eq!(net.hidden.len(), 3);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.hidden[1].len(), 4);
@@ -702,7 +1707,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 4);
         assert_eq!(net.hidden[1][0].connection_weights.len(), 1);
 
-        net.remove_hidden_node(2);
+        net.remove_hidden_node(2, &mut rng);
         assert_eq!(net.hidden.len(), 2);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.hidden[1].len(), 4);
@@ -713,7 +1718,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 4);
         assert_eq!(net.hidden[1][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(1);
+        net.remove_hidden_node(1, &mut rng);
         assert_eq!(net.hidden.len(), 2);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.hidden[1].len(), 3);
@@ -724,7 +1729,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 3);
         assert_eq!(net.hidden[1][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(1);
+        net.remove_hidden_node(1, &mut rng);
         assert_eq!(net.hidden.len(), 2);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.hidden[1].len(), 2);
@@ -735,7 +1740,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 2);
         assert_eq!(net.hidden[1][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(1);
+        net.remove_hidden_node(1, &mut rng);
         assert_eq!(net.hidden.len(), 2);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.hidden[1].len(), 1);
@@ -746,7 +1751,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 1);
         assert_eq!(net.hidden[1][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(1);
+        net.remove_hidden_node(1, &mut rng);
         assert_eq!(net.hidden.len(), 1);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.bias.len(), 1);
@@ -754,7 +1759,7 @@ mod neural_network_test {
         assert_eq!(net.inputs[0].connection_weights.len(), 3);
         assert_eq!(net.hidden[0][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(0);
+        net.remove_hidden_node(0, &mut rng);
         assert_eq!(net.hidden.len(), 1);
         assert_eq!(net.hidden[0].len(), 2);
         assert_eq!(net.bias.len(), 1);
@@ -762,7 +1767,7 @@ mod neural_network_test {
         assert_eq!(net.inputs[0].connection_weights.len(), 2);
         assert_eq!(net.hidden[0][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(0);
+        net.remove_hidden_node(0, &mut rng);
         assert_eq!(net.hidden.len(), 1);
         assert_eq!(net.hidden[0].len(), 1);
         assert_eq!(net.bias.len(), 1);
@@ -770,15 +1775,16 @@ mod neural_network_test {
         assert_eq!(net.inputs[0].connection_weights.len(), 1);
         assert_eq!(net.hidden[0][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(0);
+        net.remove_hidden_node(0, &mut rng);
         assert_eq!(net.hidden.len(), 0);
         assert_eq!(net.bias.len(), 0);
         assert_eq!(net.inputs[0].connection_weights.len(), data as usize);
+        assert_eq!(net.output_bias.connection_weights.len(), data as usize);
 
         let xs: [u32; 6] = [1, 1, 1, 1, 1, 3];
         let data = 1;
         let mut net = NeuralNetwork::new(5, xs.as_ref(), data, *DEFAULT_SEED);
-        net.remove_hidden_node(1);
+        net.remove_hidden_node(1, &mut rng);
         assert_eq!(net.hidden.len(), 5);
         assert_eq!(net.hidden[0].len(), 1);
         assert_eq!(net.hidden[1].len(), 1);
@@ -795,7 +1801,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[3][0].connection_weights.len(), 3);
         assert_eq!(net.hidden[4][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(1);
+        net.remove_hidden_node(1, &mut rng);
         assert_eq!(net.hidden.len(), 4);
         assert_eq!(net.hidden[0].len(), 1);
         assert_eq!(net.hidden[1].len(), 1);
@@ -810,7 +1816,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[2][0].connection_weights.len(), 3);
         assert_eq!(net.hidden[3][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(1);
+        net.remove_hidden_node(1, &mut rng);
         assert_eq!(net.hidden.len(), 3);
         assert_eq!(net.hidden[0].len(), 1);
         assert_eq!(net.hidden[1].len(), 1);
@@ -823,7 +1829,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[1][0].connection_weights.len(), 3);
         assert_eq!(net.hidden[2][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(1);
+        net.remove_hidden_node(1, &mut rng);
         assert_eq!(net.hidden.len(), 2);
         assert_eq!(net.hidden[0].len(), 1);
         assert_eq!(net.hidden[1].len(), 3);
@@ -834,7 +1840,7 @@ mod neural_network_test {
         assert_eq!(net.hidden[0][0].connection_weights.len(), 3);
         assert_eq!(net.hidden[1][0].connection_weights.len(), data as usize);
 
-        net.remove_hidden_node(0);
+        net.remove_hidden_node(0, &mut rng);
         assert_eq!(net.hidden.len(), 1);
         assert_eq!(net.hidden[0].len(), 3);
         assert_eq!(net.bias.len(), 1);
@@ -844,19 +1850,20 @@ mod neural_network_test {
     }
     #[test]
     fn network_hidden_layer_swap_and_create_new_from_test() {
+        let mut rng = create_rng();
         let xs: [u32; 0] = [];
         let data = 1;
-        let mut net_one = NeuralNetwork::new(5, xs.as_ref(), data, *DEFAULT_SEED);
+        let net_one = NeuralNetwork::new(5, xs.as_ref(), data, *DEFAULT_SEED);
 
         let mut net_two = NeuralNetwork::new(5, xs.as_ref(), data, *DEFAULT_SEED);
 
-        let net_three = net_one.hidden_layer_swap_and_create_new_from(net_two.borrow());
+        let net_three = net_one.hidden_layer_swap_and_create_new_from(net_two.borrow(), &mut rng);
 
         assert_eq!(net_three.is_none(), true);
         let xs: [u32; 1] = [1];
         net_two = NeuralNetwork::new(5, xs.as_ref(), data, *DEFAULT_SEED);
 
-        let net_three = net_one.hidden_layer_swap_and_create_new_from(net_two.borrow());
+        let net_three = net_one.hidden_layer_swap_and_create_new_from(net_two.borrow(), &mut rng);
 
         let net_three_val = net_three.unwrap();
         assert_eq!(net_three_val.hidden.len(), 1);
@@ -871,12 +1878,12 @@ mod neural_network_test {
 
         let xs: [u32; 5] = [1, 2, 3, 4, 5];
         let data = 5;
-        let mut net_one = NeuralNetwork::new(10, xs.as_ref(), data, *DEFAULT_SEED);
+        let net_one = NeuralNetwork::new(10, xs.as_ref(), data, *DEFAULT_SEED);
         let xs: [u32; 2] = [8, 8];
         let data = 5;
         let net_two = NeuralNetwork::new(8, xs.as_ref(), data, *DEFAULT_SEED);
 
-        let net_three = net_one.hidden_layer_swap_and_create_new_from(net_two.borrow());
+        let net_three = net_one.hidden_layer_swap_and_create_new_from(net_two.borrow(), &mut rng);
 
         let net_three_val = net_three.unwrap();
 
@@ -901,5 +1908,465 @@ mod neural_network_test {
             net_three_val.hidden[4][0].connection_weights.len(),
             data as usize
         );
+        assert_eq!(
+            net_three_val.output_bias.connection_weights.len(),
+            data as usize
+        );
+    }
+
+    #[test]
+    fn network_recombine_nodes_test() {
+        let xs: [u32; 2] = [3, 2];
+        let data = 2;
+        let mut net_one = NeuralNetwork::new(2, xs.as_ref(), data, *DEFAULT_SEED);
+        let weights = vec![1.0; net_one.weight_count()];
+        net_one.set_weights(&weights);
+
+        let xs: [u32; 1] = [2];
+        let mut net_two = NeuralNetwork::new(3, xs.as_ref(), data, *DEFAULT_SEED);
+        let weights = vec![2.0; net_two.weight_count()];
+        net_two.set_weights(&weights);
+
+        let new_net = net_one
+            .recombine_nodes_and_create_new_from(net_two.borrow(), |_, other| other.to_vec());
+
+        // Topology comes from the first network.
+        assert_eq!(new_net.inputs.len(), 2);
+        assert_eq!(new_net.hidden.len(), 2);
+        assert_eq!(new_net.hidden[0].len(), 3);
+        assert_eq!(new_net.bias.len(), 2);
+
+        // The first network's inputs connect to 3 hidden nodes, the second network's only to 2.
+        assert_eq!(new_net.inputs[0].connection_weights, vec![2.0, 2.0, 1.0]);
+        assert_eq!(new_net.inputs[1].connection_weights, vec![2.0, 2.0, 1.0]);
+        assert_eq!(new_net.hidden[0][0].connection_weights, vec![2.0, 2.0]);
+        assert_eq!(new_net.hidden[0][1].connection_weights, vec![2.0, 2.0]);
+        // Node without a counterpart in the second network.
+        assert_eq!(new_net.hidden[0][2].connection_weights, vec![1.0, 1.0]);
+        // Layer without a counterpart in the second network.
+        assert_eq!(new_net.hidden[1][0].connection_weights, vec![1.0, 1.0]);
+        assert_eq!(new_net.bias[0][0].connection_weights, vec![2.0, 2.0, 1.0]);
+        assert_eq!(new_net.bias[1][0].connection_weights, vec![1.0, 1.0]);
+
+        let new_net =
+            net_one.recombine_nodes_and_create_new_from(net_two.borrow(), |own, other| {
+                own.iter()
+                    .zip(other.iter())
+                    .map(|(own_weight, other_weight)| (own_weight + other_weight) / 2.0)
+                    .collect()
+            });
+        assert_eq!(new_net.inputs[0].connection_weights, vec![1.5, 1.5, 1.0]);
+        assert_eq!(new_net.hidden[1][0].connection_weights, vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn network_activation_test() {
+        let mut rng = create_rng();
+        let xs: [u32; 2] = [2, 2];
+        let data = 3;
+        let mut net = NeuralNetwork::new(2, xs.as_ref(), data, *DEFAULT_SEED);
+        let weights = vec![1.0; net.weight_count()];
+        net.set_weights(&weights);
+
+        assert_eq!(net.hidden_activation(0), Activation::Sigmoid);
+        assert_eq!(net.output_activation(), Activation::Sigmoid);
+
+        net.set_hidden_activation(0, Activation::Linear);
+        net.set_hidden_activation(1, Activation::Relu);
+        net.set_output_activation(Activation::Linear);
+        // hidden one: 1 + 1 + bias 1 = 3, hidden two: 3 + 3 + bias 1 = 7, outputs: 7 + 7 + bias 1 = 15
+        assert_eq!(net.feedforward(&[1.0, 1.0]), vec![15.0, 15.0, 15.0]);
+
+        net.set_output_activation(Activation::Softmax);
+        let output = net.feedforward(&[1.0, 1.0]);
+        assert!((output.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert_eq!(output[0], output[1]);
+
+        // Activations follow their layer when the topology changes.
+        net.push_hidden_node_with_new_layer_at_end(&mut rng);
+        assert_eq!(net.hidden_activation(1), Activation::Relu);
+        assert_eq!(net.hidden_activation(2), Activation::Sigmoid);
+        net.remove_hidden_node(0, &mut rng);
+        net.remove_hidden_node(0, &mut rng);
+        assert_eq!(net.hidden_length(), 2);
+        assert_eq!(net.hidden_activation(0), Activation::Relu);
+    }
+
+    #[test]
+    fn network_activation_serialization_test() {
+        let xs: [u32; 1] = [2];
+        let mut net = NeuralNetwork::new(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        net.set_hidden_activation(0, Activation::Tanh);
+        net.set_output_activation(Activation::Step);
+
+        let json = serde_json::to_string(&net).unwrap();
+        let loaded_net: NeuralNetwork = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded_net.hidden_activation(0), Activation::Tanh);
+        assert_eq!(loaded_net.output_activation(), Activation::Step);
+
+        // Networks saved without activations load with sigmoid everywhere.
+        let mut json = serde_json::to_value(&net).unwrap();
+        json.as_object_mut().unwrap().remove("hidden_activations");
+        json.as_object_mut().unwrap().remove("output_activation");
+        let legacy_net: NeuralNetwork = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy_net.hidden_activation(0), Activation::Sigmoid);
+        assert_eq!(legacy_net.output_activation(), Activation::Sigmoid);
+    }
+
+    #[test]
+    fn network_weights_test() {
+        let xs: [u32; 2] = [3, 2];
+        let data = 2;
+        let mut net = NeuralNetwork::new(4, xs.as_ref(), data, *DEFAULT_SEED);
+
+        // 4 * 3 input weights, 3 * 2 + 2 * 2 hidden weights, 3 + 2 bias weights and 2 output bias weights.
+        assert_eq!(net.weight_count(), 29);
+
+        let weights = net.weights();
+        assert_eq!(weights.len(), 29);
+        assert_eq!(weights[0], net.inputs[0].connection_weights[0]);
+        assert_eq!(weights[26], net.bias[1][0].connection_weights[1]);
+        assert_eq!(weights[28], net.output_bias.connection_weights[1]);
+
+        let new_weights: Vec<f64> = (0..29).map(|weight| weight as f64).collect();
+        net.set_weights(&new_weights);
+        assert_eq!(net.weights(), new_weights);
+        assert_eq!(net.inputs[1].connection_weights[0], 3.0);
+        assert_eq!(net.hidden[0][0].connection_weights[1], 13.0);
+        assert_eq!(net.bias[0][0].connection_weights[2], 24.0);
+    }
+
+    #[test]
+    fn network_recurrent_step_test() {
+        let xs: [u32; 1] = [1];
+        let mut net = NeuralNetwork::new_recurrent(1, xs.as_ref(), 1, *DEFAULT_SEED);
+        assert!(net.is_recurrent());
+        // input, hidden, bias, output bias and context weight.
+        assert_eq!(net.weight_count(), 5);
+        net.set_weights(&[1.0, 1.0, 0.0, 0.0, 0.5]);
+        net.set_hidden_activation(0, Activation::Linear);
+        net.set_output_activation(Activation::Linear);
+
+        assert_eq!(net.step(&[1.0]), vec![1.0]);
+        assert_eq!(net.step(&[1.0]), vec![1.5]);
+        assert_eq!(net.state(), &vec![vec![1.5]]);
+        // feedforward looks at the state without advancing it.
+        assert_eq!(net.feedforward(&[1.0]), vec![1.75]);
+        assert_eq!(net.feedforward(&[1.0]), vec![1.75]);
+        assert_eq!(net.step(&[1.0]), vec![1.75]);
+
+        net.reset_state();
+        assert_eq!(net.step(&[1.0]), vec![1.0]);
+
+        // Networks that are not recurrent have no state and step is the same as feedforward.
+        let mut net = NeuralNetwork::new(1, xs.as_ref(), 1, *DEFAULT_SEED);
+        let output = net.feedforward(&[1.0]);
+        assert_eq!(net.step(&[1.0]), output);
+        assert!(net.state().is_empty());
+    }
+
+    #[test]
+    fn network_recurrent_topology_test() {
+        let mut rng = create_rng();
+        let xs: [u32; 2] = [3, 2];
+        let mut net = NeuralNetwork::new_recurrent(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        // The extra layers and nodes of new_recurrent do not change the weights drawn by new.
+        let feedforward_net = NeuralNetwork::new(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        assert_eq!(
+            net.weights()[..feedforward_net.weight_count()],
+            feedforward_net.weights()[..]
+        );
+        net.step(&[1.0, 1.0]);
+
+        net.push_hidden_node(0, &mut rng);
+        assert_eq!(net.context[0].len(), 4);
+        assert_eq!(net.context[0][3].connection_weights, vec![0.0; 4]);
+        assert_eq!(net.state(), &vec![vec![0.0; 4], vec![0.0; 2]]);
+
+        net.push_hidden_node_with_new_layer_at_end(&mut rng);
+        assert_eq!(net.context.len(), 3);
+        assert_eq!(net.context[2][0].connection_weights, vec![0.0]);
+
+        // Removing the lone node of the last layer removes its context layer as well.
+        let first_context = net.context[0].clone();
+        net.remove_hidden_node(2, &mut rng);
+        assert_eq!(net.context.len(), 2);
+        net.remove_hidden_node(0, &mut rng);
+        assert_eq!(net.context[0].len(), 3);
+        assert_eq!(
+            net.context[0][0].connection_weights[..],
+            first_context[0].connection_weights[..3]
+        );
+        assert_eq!(net.step(&[1.0, 1.0]).len(), 1);
+
+        // A child of a recurrent and a feedforward network stays recurrent.
+        let feedforward_net = NeuralNetwork::new(2, &[4], 1, *DEFAULT_SEED);
+        let new_net = feedforward_net
+            .hidden_layer_swap_and_create_new_from(&net, &mut rng)
+            .unwrap();
+        assert!(new_net.is_recurrent());
+        assert_eq!(new_net.context.len(), new_net.hidden.len());
+        for (context_layer, hidden_layer) in new_net.context.iter().zip(new_net.hidden.iter()) {
+            assert_eq!(context_layer.len(), hidden_layer.len());
+        }
+
+        let json = serde_json::to_string(&net).unwrap();
+        let loaded_net: NeuralNetwork = serde_json::from_str(&json).unwrap();
+        assert!(loaded_net.is_recurrent());
+        assert_eq!(loaded_net.weight_count(), net.weight_count());
+        assert_eq!(loaded_net.state(), net.state());
+    }
+
+    #[test]
+    fn network_remove_hidden_node_at_test() {
+        let mut rng = create_rng();
+        let xs: [u32; 2] = [3, 2];
+        let mut net = NeuralNetwork::new(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        net.set_output_activation(Activation::Linear);
+        let inputs = [0.3, -0.7];
+
+        // Removing a node gives the same outputs as cutting all of its outgoing weights.
+        let mut cut_net = net.clone();
+        for weight in cut_net.hidden[0][1].connection_weights.iter_mut() {
+            *weight = 0.0;
+        }
+        let removed_weight = net.inputs[0].connection_weights[2];
+        net.remove_hidden_node_at(0, 1, &mut rng);
+
+        assert_eq!(net.hidden_layer_length(0), 2);
+        assert_eq!(net.inputs[0].connection_weights.len(), 2);
+        assert_eq!(net.inputs[0].connection_weights[1], removed_weight);
+        assert_eq!(net.bias[0][0].connection_weights.len(), 2);
+        assert_eq!(net.feedforward(&inputs), cut_net.feedforward(&inputs));
+
+        net.remove_hidden_node_at(1, 0, &mut rng);
+        assert_eq!(net.hidden_layer_length(1), 1);
+        assert_eq!(net.hidden[0][0].connection_weights.len(), 1);
+    }
+
+    #[test]
+    fn network_insert_hidden_layer_test() {
+        let xs: [u32; 2] = [3, 2];
+        let mut net = NeuralNetwork::new(2, xs.as_ref(), 2, *DEFAULT_SEED);
+        net.set_hidden_activation(1, Activation::Tanh);
+        net.output_bias.connection_weights = vec![0.25, -0.5];
+        let inputs = [0.3, -0.7];
+        let outputs = net.feedforward(&inputs);
+
+        for &layer_index in [2, 0, 2].iter() {
+            net.insert_hidden_layer(layer_index);
+            assert_eq!(net.hidden_activation(layer_index), Activation::Linear);
+            assert_eq!(net.feedforward(&inputs), outputs);
+        }
+        assert_eq!(net.hidden_length(), 5);
+        assert_eq!(net.hidden_layer_length(0), 3);
+        assert_eq!(net.hidden_layer_length(3), 2);
+        assert_eq!(net.hidden_activation(3), Activation::Tanh);
+        assert_eq!(net.hidden_activation(4), Activation::Linear);
+        assert_eq!(net.output_bias.connection_weights, vec![0.0, 0.0]);
+
+        let mut net = NeuralNetwork::new_recurrent(2, &[], 1, *DEFAULT_SEED);
+        let outputs = net.feedforward(&inputs);
+        net.insert_hidden_layer(0);
+        assert_eq!(net.context.len(), 1);
+        assert_eq!(net.step(&inputs), outputs);
+    }
+
+    #[test]
+    fn network_gradient_test() {
+        let xs: [u32; 2] = [3, 2];
+        let inputs = [0.3, -0.7];
+        let targets = [0.2, 0.8];
+        let mut feedforward_net = NeuralNetwork::new(2, xs.as_ref(), 2, *DEFAULT_SEED);
+        let mut recurrent_net = NeuralNetwork::new_recurrent(2, xs.as_ref(), 2, *DEFAULT_SEED);
+        recurrent_net.step(&[0.5, 0.5]);
+
+        for net in [&mut feedforward_net, &mut recurrent_net].iter_mut() {
+            net.set_hidden_activation(0, Activation::Tanh);
+            net.set_output_activation(Activation::Softmax);
+            net.output_bias.connection_weights = vec![0.1, -0.2];
+            let loss = |outputs: &[f64]| -> f64 {
+                outputs
+                    .iter()
+                    .zip(targets.iter())
+                    .map(|(output, target)| (output - target).powi(2))
+                    .sum()
+            };
+
+            let gradient = net.gradient(&inputs, |outputs| {
+                outputs
+                    .iter()
+                    .zip(targets.iter())
+                    .map(|(output, target)| 2.0 * (output - target))
+                    .collect()
+            });
+            assert_eq!(gradient.len(), net.weight_count());
+
+            // Compare against central finite differences.
+            let weights = net.weights();
+            let step = 1e-6;
+            for weight_index in 0..weights.len() {
+                let mut changed_weights = weights.clone();
+                changed_weights[weight_index] += step;
+                net.set_weights(&changed_weights);
+                let loss_up = loss(&net.feedforward(&inputs));
+                changed_weights[weight_index] -= 2.0 * step;
+                net.set_weights(&changed_weights);
+                let loss_down = loss(&net.feedforward(&inputs));
+                let numeric_gradient = (loss_up - loss_down) / (2.0 * step);
+                assert!((gradient[weight_index] - numeric_gradient).abs() < 1e-7);
+            }
+            net.set_weights(&weights);
+        }
+    }
+
+    #[test]
+    fn network_weight_initializer_test() {
+        let mut rng = create_rng();
+        // Weights added by growing the network are drawn on the same scale as the ones from construction.
+        let xs: [u32; 1] = [2];
+        let mut net = NeuralNetwork::new(3, xs.as_ref(), 2, *DEFAULT_SEED);
+        net.push_hidden_node(0, &mut rng);
+        net.push_hidden_node_with_new_layer_at_end(&mut rng);
+        net.feedforward_new_inputs(&[0.5; 5], &mut rng);
+        assert!(net.weights().iter().all(|weight| weight.abs() <= 0.1));
+
+        let mut net = NeuralNetwork::with_initializer(
+            3,
+            xs.as_ref(),
+            2,
+            WeightInitializer::Zeros,
+            *DEFAULT_SEED,
+        );
+        assert_eq!(net.weight_initializer(), WeightInitializer::Zeros);
+        net.push_hidden_node(0, &mut rng);
+        net.push_hidden_node_with_new_layer_at_end(&mut rng);
+        net.feedforward_new_inputs(&[0.5; 4], &mut rng);
+        assert!(net.weights().iter().all(|&weight| weight == 0.0));
+
+        net.set_weight_initializer(WeightInitializer::Xavier);
+        net.push_hidden_node(1, &mut rng);
+        // sqrt(6 / (2 + 2)) for the new outgoing weights of the last hidden layer.
+        let limit = 1.5_f64.sqrt();
+        assert!(net.hidden[1][1]
+            .connection_weights
+            .iter()
+            .all(|weight| weight.abs() <= limit && *weight != 0.0));
+
+        let json = serde_json::to_string(&net).unwrap();
+        let loaded_net: NeuralNetwork = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded_net.weight_initializer(), WeightInitializer::Xavier);
+    }
+
+    #[test]
+    fn network_weight_fans_test() {
+        let xs: [u32; 1] = [4];
+        let net = NeuralNetwork::new(3, xs.as_ref(), 2, *DEFAULT_SEED);
+        let mut expected = vec![(3, 4); 12];
+        expected.extend(vec![(4, 2); 8]);
+        expected.extend(vec![(3, 4); 4]);
+        expected.extend(vec![(4, 2); 2]);
+        assert_eq!(net.weight_fans(), expected);
+
+        let net = NeuralNetwork::new_recurrent(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        let fans = net.weight_fans();
+        assert_eq!(fans.len(), net.weight_count());
+        assert!(fans[fans.len() - 16..].iter().all(|&fans| fans == (4, 4)));
+    }
+
+    #[test]
+    fn network_complexity_test() {
+        let xs: [u32; 2] = [3, 2];
+        let net = NeuralNetwork::new(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        // 2 * 3 + 3 * 2 + 2 * 1 connections, 3 + 2 hidden bias weights and 1 output bias weight.
+        assert_eq!(net.parameter_count(), 20);
+        assert_eq!(net.node_count(), 8);
+        assert_eq!(net.depth(), 3);
+        assert_eq!(net.flops(), 46);
+
+        let net = NeuralNetwork::new(2, &[], 1, *DEFAULT_SEED);
+        assert_eq!(net.parameter_count(), 3);
+        assert_eq!(net.depth(), 1);
+        assert_eq!(net.flops(), 7);
+    }
+
+    #[test]
+    fn network_portable_model_test() {
+        let xs: [u32; 2] = [3, 2];
+        let mut net = NeuralNetwork::new(2, xs.as_ref(), 2, *DEFAULT_SEED);
+        net.set_hidden_activation(1, Activation::Tanh);
+        net.set_output_activation(Activation::Softmax);
+        net.output_bias.connection_weights = vec![0.25, -0.5];
+        let inputs = [0.3, -0.7];
+
+        let model = net.to_portable_model();
+        assert_eq!(model.layers.len(), 3);
+        assert_eq!(model.layers[0].weights.len(), 3);
+        assert_eq!(model.layers[2].biases, vec![0.25, -0.5]);
+        assert_eq!(model.feedforward(&inputs), net.feedforward(&inputs));
+        assert!(!model.to_json().contains("seed"));
+
+        let loaded_net = NeuralNetwork::from_portable_model(&model).unwrap();
+        assert_eq!(loaded_net.weights(), net.weights());
+        assert_eq!(loaded_net.feedforward(&inputs), net.feedforward(&inputs));
+        assert_eq!(loaded_net.to_portable_model(), model);
+
+        let mut net = NeuralNetwork::new_recurrent(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        let model = net.to_portable_model();
+        assert!(model.validate().is_ok());
+        let mut state = Vec::new();
+        for _ in 0..3 {
+            assert_eq!(model.step(&inputs, &mut state), net.step(&inputs));
+        }
+        assert_eq!(&state, net.state());
+
+        let mut loaded_net = NeuralNetwork::from_portable_model(&model).unwrap();
+        assert!(loaded_net.is_recurrent());
+        assert_eq!(loaded_net.weights(), net.weights());
+        assert_eq!(loaded_net.step(&inputs), model.feedforward(&inputs));
+    }
+
+    #[test]
+    fn network_dot_test() {
+        let net = NeuralNetwork::new_recurrent(2, &[2], 1, *DEFAULT_SEED);
+        let dot = net.to_dot(0.0);
+        assert!(dot.contains("i1 -> h0_0"));
+        assert!(dot.contains("h0_1 -> o0"));
+        assert!(dot.contains("style=dashed"));
+        assert!(!dot.contains("Seed"));
+        assert_eq!(net.to_dot(1.0).matches("->").count(), 0);
+    }
+
+    #[test]
+    fn network_equality_test() {
+        let mut rng = create_rng();
+        let xs: [u32; 1] = [2];
+        let net = NeuralNetwork::new(2, xs.as_ref(), 1, *DEFAULT_SEED);
+        let mut first_clone = net.clone();
+        let mut second_clone = net.clone();
+        assert_eq!(first_clone, net);
+
+        // Clones no longer share a random generator, so growing them with different generators gives different weights.
+        first_clone.push_hidden_node(0, &mut rng);
+        second_clone.push_hidden_node(0, &mut rng);
+        assert_ne!(first_clone, second_clone);
+        assert_ne!(first_clone, net);
+
+        let mut hashes = HashSet::new();
+        hashes.insert(net.clone());
+        hashes.insert(first_clone.clone());
+        hashes.insert(net.clone());
+        assert_eq!(hashes.len(), 2);
+
+        let mut other_activation = net.clone();
+        other_activation.set_output_activation(Activation::Tanh);
+        assert_ne!(other_activation, net);
+
+        let json = serde_json::to_string(&net).unwrap();
+        assert!(!json.contains("seed"));
+        let mut legacy_json = serde_json::to_value(&net).unwrap();
+        legacy_json["seed"] = serde_json::json!({ "rng": [1, 2, 3] });
+        let legacy_net: NeuralNetwork = serde_json::from_value(legacy_json).unwrap();
+        assert_eq!(legacy_net.weight_count(), net.weight_count());
     }
 }
@@ -0,0 +1,360 @@
+use crate::neural_network::activation::Activation;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Portable model format, version 1.
+//
+// A JSON object with the fields
+//   format_version: 1
+//   number_of_inputs: number of inputs of the model
+//   recurrent: true for an Elman network, see step
+//   layers: every layer from the first hidden layer up to and including the output layer, each with
+//     activation: "Sigmoid", "Relu", "Tanh", "Softmax", "Linear", "Step", "Gaussian" or {"LeakyRelu": {"slope": 0.01}}
+//     weights: one row per node of the layer, weights[o][i] connects input i of the layer to node o
+//     biases: one bias per node of the layer
+//     context: only present in recurrent models, context[o][j] connects the previous activation of node j of the layer
+//              to node o. The output layer never has a context.
+// A layer computes activation(weights * inputs + biases + context * previous_activations). The inputs of a layer are the
+// outputs of the layer before it, or the inputs of the model for the first layer. Nothing else (random generator state,
+// initializer) is part of the format, and new fields only get added together with a new version.
+pub const PORTABLE_MODEL_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PortableLayer {
+    pub activation: Activation,
+    pub weights: Vec<Vec<f64>>,
+    pub biases: Vec<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<Vec<f64>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PortableModel {
+    pub format_version: u32,
+    pub number_of_inputs: usize,
+    #[serde(default)]
+    pub recurrent: bool,
+    pub layers: Vec<PortableLayer>,
+}
+
+#[derive(Debug)]
+pub enum PortableModelError {
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+    InvalidShape(String),
+}
+
+impl fmt::Display for PortableModelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PortableModelError::Json(error) => write!(f, "Invalid model json: {}", error),
+            PortableModelError::UnsupportedVersion(version) => write!(
+                f,
+                "Model format version {} is not supported, only version {} is",
+                version, PORTABLE_MODEL_VERSION
+            ),
+            PortableModelError::InvalidShape(reason) => write!(f, "Invalid model: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PortableModelError {}
+
+impl PortableLayer {
+    pub fn number_of_nodes(&self) -> usize {
+        self.weights.len()
+    }
+}
+
+impl PortableModel {
+    pub fn from_json(json: &str) -> Result<PortableModel, PortableModelError> {
+        let model: PortableModel = serde_json::from_str(json).map_err(PortableModelError::Json)?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    // Checks the version and that the sizes of all layers fit together.
+    pub fn validate(&self) -> Result<(), PortableModelError> {
+        if self.format_version != PORTABLE_MODEL_VERSION {
+            return Err(PortableModelError::UnsupportedVersion(self.format_version));
+        }
+        if self.layers.is_empty() {
+            return Err(PortableModelError::InvalidShape(String::from(
+                "a model needs at least an output layer",
+            )));
+        }
+        let mut number_of_layer_inputs = self.number_of_inputs;
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            let number_of_nodes = layer.number_of_nodes();
+            let invalid_shape = |reason: &str| {
+                Err(PortableModelError::InvalidShape(format!(
+                    "layer {} {}",
+                    layer_index, reason
+                )))
+            };
+            if number_of_nodes == 0 {
+                return invalid_shape("has no nodes");
+            }
+            if layer
+                .weights
+                .iter()
+                .any(|row| row.len() != number_of_layer_inputs)
+            {
+                return invalid_shape(&format!(
+                    "needs {} weights per node",
+                    number_of_layer_inputs
+                ));
+            }
+            if layer.biases.len() != number_of_nodes {
+                return invalid_shape(&format!("needs {} biases", number_of_nodes));
+            }
+            let is_output_layer = layer_index == self.layers.len() - 1;
+            let expects_context = self.recurrent && !is_output_layer;
+            if expects_context
+                && (layer.context.len() != number_of_nodes
+                    || layer.context.iter().any(|row| row.len() != number_of_nodes))
+            {
+                return invalid_shape(&format!("needs a {0} by {0} context", number_of_nodes));
+            }
+            if !expects_context && !layer.context.is_empty() {
+                return invalid_shape("can not have a context");
+            }
+            number_of_layer_inputs = number_of_nodes;
+        }
+        Ok(())
+    }
+
+    pub fn number_of_outputs(&self) -> usize {
+        self.layers[self.layers.len() - 1].number_of_nodes()
+    }
+
+    // For a recurrent model every previous activation is 0, which is the first step after a reset.
+    pub fn feedforward(&self, inputs: &[f64]) -> Vec<f64> {
+        let mut state = Vec::new();
+        self.step(inputs, &mut state)
+    }
+
+    // Runs one step of a recurrent model. state holds the activations of every hidden layer of the previous step and
+    // is replaced by the ones of this step. An empty state counts as all zeros.
+    pub fn step(&self, inputs: &[f64], state: &mut Vec<Vec<f64>>) -> Vec<f64> {
+        if inputs.len() != self.number_of_inputs {
+            panic!(
+                "The model has {} inputs but got {}",
+                self.number_of_inputs,
+                inputs.len()
+            );
+        }
+        let mut new_state = Vec::new();
+        let mut layer_input = inputs.to_vec();
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            let mut layer_output: Vec<f64> = layer
+                .weights
+                .iter()
+                .zip(layer.biases.iter())
+                .map(|(row, bias)| {
+                    let weighted_sum = row
+                        .iter()
+                        .zip(layer_input.iter())
+                        .fold(0.0, |sum, (weight, input)| sum + input * weight);
+                    weighted_sum + bias
+                })
+                .collect();
+            if let Some(previous_state) = state.get(layer_index) {
+                for (output, row) in layer_output.iter_mut().zip(layer.context.iter()) {
+                    for (weight, previous) in row.iter().zip(previous_state.iter()) {
+                        *output += previous * weight;
+                    }
+                }
+            }
+            layer.activation.apply(&mut layer_output);
+            if self.recurrent && layer_index < self.layers.len() - 1 {
+                new_state.push(layer_output.clone());
+            }
+            layer_input = layer_output;
+        }
+        if self.recurrent {
+            *state = new_state;
+        }
+        layer_input
+    }
+
+    // Graphviz DOT graph of the model, laid out from left to right. Edges are blue for positive and red for negative
+    // weights and get thicker with the absolute weight. Weights with an absolute value below pruning_threshold are left
+    // out. Context weights are dashed.
+    pub fn to_dot(&self, pruning_threshold: f64) -> String {
+        let largest_weight = self
+            .layers
+            .iter()
+            .flat_map(|layer| {
+                layer
+                    .weights
+                    .iter()
+                    .chain(layer.context.iter())
+                    .flatten()
+                    .chain(layer.biases.iter())
+            })
+            .fold(0.0, |largest: f64, weight| largest.max(weight.abs()));
+        let edge = |from: &str, to: &str, weight: f64, dashed: bool| -> Option<String> {
+            if weight.abs() < pruning_threshold || weight == 0.0 {
+                return None;
+            }
+            Some(format!(
+                "  {} -> {} [color=\"{}\", penwidth={:.2}{}, tooltip=\"{}\"];\n",
+                from,
+                to,
+                if weight > 0.0 { "#1f77b4" } else { "#d62728" },
+                0.5 + 4.5 * weight.abs() / largest_weight,
+                if dashed {
+                    ", style=dashed, constraint=false"
+                } else {
+                    ""
+                },
+                weight
+            ))
+        };
+        let node_name = |layer_index: usize, node_index: usize| {
+            if layer_index == 0 {
+                format!("i{}", node_index)
+            } else if layer_index == self.layers.len() {
+                format!("o{}", node_index)
+            } else {
+                format!("h{}_{}", layer_index - 1, node_index)
+            }
+        };
+
+        let mut dot = String::from("digraph neural_network {\n  rankdir=LR;\n  splines=line;\n");
+        dot.push_str("  node [shape=circle, fixedsize=true, width=0.5, fontsize=10];\n");
+        let mut layer_sizes = vec![self.number_of_inputs];
+        layer_sizes.extend(self.layers.iter().map(PortableLayer::number_of_nodes));
+        for (layer_index, &layer_size) in layer_sizes.iter().enumerate() {
+            dot.push_str(&format!(
+                "  subgraph cluster_{} {{\n    style=invis;\n",
+                layer_index
+            ));
+            for node_index in 0..layer_size {
+                let label = if layer_index == 0 {
+                    format!("x{}", node_index)
+                } else if layer_index == self.layers.len() {
+                    format!("y{}", node_index)
+                } else {
+                    String::new()
+                };
+                dot.push_str(&format!(
+                    "    {} [label=\"{}\"];\n",
+                    node_name(layer_index, node_index),
+                    label
+                ));
+            }
+            if layer_index > 0 {
+                let activation = self.layers[layer_index - 1].activation;
+                dot.push_str(&format!(
+                    "    label=\"{:?}\";\n    b{} [label=\"1\", shape=box, width=0.3];\n",
+                    activation, layer_index
+                ));
+            }
+            dot.push_str("  }\n");
+        }
+
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            for (node_index, row) in layer.weights.iter().enumerate() {
+                let to = node_name(layer_index + 1, node_index);
+                for (input_index, &weight) in row.iter().enumerate() {
+                    let from = node_name(layer_index, input_index);
+                    dot.extend(edge(&from, &to, weight, false));
+                }
+                let bias = format!("b{}", layer_index + 1);
+                dot.extend(edge(&bias, &to, layer.biases[node_index], false));
+                if let Some(context_row) = layer.context.get(node_index) {
+                    for (previous_index, &weight) in context_row.iter().enumerate() {
+                        let from = node_name(layer_index + 1, previous_index);
+                        dot.extend(edge(&from, &to, weight, true));
+                    }
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod portable_model_test {
+    use crate::neural_network::activation::Activation;
+    use crate::neural_network::portable_model::{
+        PortableLayer, PortableModel, PortableModelError, PORTABLE_MODEL_VERSION,
+    };
+
+    fn create_model() -> PortableModel {
+        PortableModel {
+            format_version: PORTABLE_MODEL_VERSION,
+            number_of_inputs: 2,
+            recurrent: false,
+            layers: vec![
+                PortableLayer {
+                    activation: Activation::Relu,
+                    weights: vec![vec![1.0, -1.0], vec![0.5, 0.5]],
+                    biases: vec![0.0, 1.0],
+                    context: Vec::new(),
+                },
+                PortableLayer {
+                    activation: Activation::Linear,
+                    weights: vec![vec![2.0, 0.25]],
+                    biases: vec![-1.0],
+                    context: Vec::new(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn portable_model_feedforward_test() {
+        let model = create_model();
+        // Hidden layer: relu(3 - 1) = 2 and relu(2 + 1) = 3, output: 2 * 2 + 0.25 * 3 - 1.
+        assert_eq!(model.feedforward(&[3.0, 1.0]), vec![3.75]);
+
+        let loaded_model = PortableModel::from_json(&model.to_json()).unwrap();
+        assert_eq!(loaded_model.layers.len(), 2);
+        assert_eq!(loaded_model.feedforward(&[3.0, 1.0]), vec![3.75]);
+    }
+
+    #[test]
+    fn portable_model_validation_test() {
+        let mut model = create_model();
+        model.format_version = PORTABLE_MODEL_VERSION + 1;
+        match PortableModel::from_json(&model.to_json()) {
+            Err(PortableModelError::UnsupportedVersion(version)) => {
+                assert_eq!(version, PORTABLE_MODEL_VERSION + 1)
+            }
+            _ => panic!("The version should not be supported"),
+        }
+
+        let mut model = create_model();
+        model.layers[1].weights[0].push(1.0);
+        match model.validate() {
+            Err(PortableModelError::InvalidShape(_)) => {}
+            _ => panic!("The weights of layer 1 do not fit"),
+        }
+
+        match PortableModel::from_json("{\"format_version\": 1}") {
+            Err(PortableModelError::Json(_)) => {}
+            _ => panic!("The json is missing fields"),
+        }
+    }
+
+    #[test]
+    fn portable_model_dot_test() {
+        let dot = create_model().to_dot(0.3);
+        assert!(dot.starts_with("digraph neural_network {"));
+        assert!(dot.contains("i0 -> h0_0"));
+        assert!(dot.contains("b2 -> o0"));
+        assert!(dot.contains("label=\"Relu\""));
+        // Below the threshold.
+        assert!(!dot.contains("h0_1 -> o0"));
+        // A zero bias has no edge.
+        assert!(!dot.contains("b1 -> h0_0"));
+    }
+}
@@ -0,0 +1,309 @@
+use crate::neural_network::neural_network::NeuralNetwork;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+// Keeps the logarithm of the cross entropy finite when an output is exactly 0.
+const CROSS_ENTROPY_EPSILON: f64 = 1e-12;
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Loss {
+    MeanSquaredError,
+    // Categorical cross entropy, meant for a softmax output layer and one hot targets.
+    CrossEntropy,
+}
+
+impl Loss {
+    pub fn value(&self, outputs: &[f64], targets: &[f64]) -> f64 {
+        match self {
+            Loss::MeanSquaredError => {
+                outputs
+                    .iter()
+                    .zip(targets.iter())
+                    .map(|(output, target)| (output - target).powi(2))
+                    .sum::<f64>()
+                    / outputs.len() as f64
+            }
+            Loss::CrossEntropy => -outputs
+                .iter()
+                .zip(targets.iter())
+                .map(|(output, target)| target * output.max(CROSS_ENTROPY_EPSILON).ln())
+                .sum::<f64>(),
+        }
+    }
+
+    // Derivative of the loss with respect to every output.
+    pub fn gradient(&self, outputs: &[f64], targets: &[f64]) -> Vec<f64> {
+        match self {
+            Loss::MeanSquaredError => outputs
+                .iter()
+                .zip(targets.iter())
+                .map(|(output, target)| 2.0 * (output - target) / outputs.len() as f64)
+                .collect(),
+            Loss::CrossEntropy => outputs
+                .iter()
+                .zip(targets.iter())
+                .map(|(output, target)| -target / output.max(CROSS_ENTROPY_EPSILON))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Optimizer {
+    Sgd {
+        learning_rate: f64,
+        momentum: f64,
+    },
+    Adam {
+        learning_rate: f64,
+        beta1: f64,
+        beta2: f64,
+        epsilon: f64,
+    },
+}
+
+impl Optimizer {
+    // Adam with the default decay rates of the paper.
+    pub fn adam(learning_rate: f64) -> Optimizer {
+        Optimizer::Adam {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+        }
+    }
+}
+
+// Momentum (SGD) or first and second moments (Adam) of every weight. Lives for one call to train.
+struct OptimizerState {
+    first_moment: Vec<f64>,
+    second_moment: Vec<f64>,
+    steps: i32,
+}
+
+impl OptimizerState {
+    fn new(number_of_weights: usize) -> OptimizerState {
+        OptimizerState {
+            first_moment: vec![0.0; number_of_weights],
+            second_moment: vec![0.0; number_of_weights],
+            steps: 0,
+        }
+    }
+
+    fn update(&mut self, optimizer: &Optimizer, weights: &mut [f64], gradient: &[f64]) {
+        self.steps += 1;
+        match *optimizer {
+            Optimizer::Sgd {
+                learning_rate,
+                momentum,
+            } => {
+                for ((weight, velocity), gradient) in weights
+                    .iter_mut()
+                    .zip(self.first_moment.iter_mut())
+                    .zip(gradient.iter())
+                {
+                    *velocity = momentum * *velocity - learning_rate * gradient;
+                    *weight += *velocity;
+                }
+            }
+            Optimizer::Adam {
+                learning_rate,
+                beta1,
+                beta2,
+                epsilon,
+            } => {
+                let first_correction = 1.0 - beta1.powi(self.steps);
+                let second_correction = 1.0 - beta2.powi(self.steps);
+                for (((weight, first_moment), second_moment), gradient) in weights
+                    .iter_mut()
+                    .zip(self.first_moment.iter_mut())
+                    .zip(self.second_moment.iter_mut())
+                    .zip(gradient.iter())
+                {
+                    *first_moment = beta1 * *first_moment + (1.0 - beta1) * gradient;
+                    *second_moment = beta2 * *second_moment + (1.0 - beta2) * gradient * gradient;
+                    *weight -= learning_rate * (*first_moment / first_correction)
+                        / ((*second_moment / second_correction).sqrt() + epsilon);
+                }
+            }
+        }
+    }
+}
+
+// Mini-batch backpropagation for a NeuralNetwork. The samples are shuffled at the start of every epoch.
+pub struct Trainer {
+    optimizer: Optimizer,
+    loss: Loss,
+    epochs: u32,
+    batch_size: usize,
+    seed: StdRng,
+}
+
+impl Trainer {
+    pub fn new(
+        optimizer: Optimizer,
+        loss: Loss,
+        epochs: u32,
+        batch_size: usize,
+        seed: [u8; 32],
+    ) -> Trainer {
+        if batch_size == 0 {
+            panic!("The batch size has to be at least 1.");
+        }
+        Trainer {
+            optimizer,
+            loss,
+            epochs,
+            batch_size,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+
+    pub fn loss(&self) -> Loss {
+        self.loss
+    }
+
+    pub fn mean_loss(
+        &self,
+        network: &NeuralNetwork,
+        inputs: &[Vec<f64>],
+        targets: &[Vec<f64>],
+    ) -> f64 {
+        inputs
+            .iter()
+            .zip(targets.iter())
+            .map(|(inputs, targets)| self.loss.value(&network.feedforward(inputs), targets))
+            .sum::<f64>()
+            / inputs.len() as f64
+    }
+
+    // Trains the network in place and returns the mean loss of every epoch, measured on the batches while training.
+    pub fn train(
+        &mut self,
+        network: &mut NeuralNetwork,
+        inputs: &[Vec<f64>],
+        targets: &[Vec<f64>],
+    ) -> Vec<f64> {
+        if inputs.len() != targets.len() {
+            panic!(
+                "Got {} inputs but {} targets, every input needs a target.",
+                inputs.len(),
+                targets.len()
+            );
+        }
+        if inputs.is_empty() {
+            return Vec::new();
+        }
+
+        let mut weights = network.weights();
+        let mut optimizer_state = OptimizerState::new(weights.len());
+        let mut order: Vec<usize> = (0..inputs.len()).collect();
+        let mut epoch_losses = Vec::with_capacity(self.epochs as usize);
+
+        for _ in 0..self.epochs {
+            order.shuffle(&mut self.seed);
+            let mut epoch_loss = 0.0;
+
+            for batch in order.chunks(self.batch_size) {
+                let mut batch_gradient = vec![0.0; weights.len()];
+                for &sample in batch.iter() {
+                    let loss = self.loss;
+                    let sample_targets = &targets[sample];
+                    let mut sample_loss = 0.0;
+                    let gradient = network.gradient(&inputs[sample], |outputs| {
+                        sample_loss = loss.value(outputs, sample_targets);
+                        loss.gradient(outputs, sample_targets)
+                    });
+                    epoch_loss += sample_loss;
+                    for (total, gradient) in batch_gradient.iter_mut().zip(gradient.iter()) {
+                        *total += gradient;
+                    }
+                }
+                for gradient in batch_gradient.iter_mut() {
+                    *gradient /= batch.len() as f64;
+                }
+
+                optimizer_state.update(&self.optimizer, &mut weights, &batch_gradient);
+                network.set_weights(&weights);
+            }
+
+            epoch_losses.push(epoch_loss / inputs.len() as f64);
+        }
+
+        epoch_losses
+    }
+}
+
+#[cfg(test)]
+mod trainer_test {
+    use crate::neural_network::activation::Activation;
+    use crate::neural_network::neural_network::NeuralNetwork;
+    use crate::neural_network::trainer::{Loss, Optimizer, Trainer};
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    fn create_xor() -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        let inputs = vec![
+            vec![0.0, 0.0],
+            vec![0.0, 1.0],
+            vec![1.0, 0.0],
+            vec![1.0, 1.0],
+        ];
+        let targets = vec![
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![0.0, 1.0],
+            vec![1.0, 0.0],
+        ];
+        (inputs, targets)
+    }
+
+    #[test]
+    fn loss_test() {
+        assert_eq!(Loss::MeanSquaredError.value(&[1.0, 2.0], &[0.0, 0.0]), 2.5);
+        assert_eq!(
+            Loss::MeanSquaredError.gradient(&[1.0, 2.0], &[0.0, 0.0]),
+            vec![1.0, 2.0]
+        );
+        assert_eq!(Loss::CrossEntropy.value(&[1.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(
+            Loss::CrossEntropy.gradient(&[0.5, 0.5], &[1.0, 0.0]),
+            vec![-2.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn train_xor_test() {
+        let (inputs, targets) = create_xor();
+        let optimizers = [
+            Optimizer::Sgd {
+                learning_rate: 0.5,
+                momentum: 0.9,
+            },
+            Optimizer::adam(0.05),
+        ];
+
+        for optimizer in optimizers.iter() {
+            let xs: [u32; 1] = [6];
+            let mut net = NeuralNetwork::new(2, xs.as_ref(), 2, *DEFAULT_SEED);
+            net.set_hidden_activation(0, Activation::Tanh);
+            net.set_output_activation(Activation::Softmax);
+
+            let mut trainer = Trainer::new(*optimizer, Loss::CrossEntropy, 300, 4, *DEFAULT_SEED);
+            let initial_loss = trainer.mean_loss(&net, &inputs, &targets);
+            let epoch_losses = trainer.train(&mut net, &inputs, &targets);
+
+            assert_eq!(epoch_losses.len(), 300);
+            assert!(trainer.mean_loss(&net, &inputs, &targets) < initial_loss * 0.1);
+            for (inputs, targets) in inputs.iter().zip(targets.iter()) {
+                let outputs = net.feedforward(inputs);
+                assert_eq!(outputs[0] > outputs[1], targets[0] > targets[1]);
+            }
+        }
+    }
+}
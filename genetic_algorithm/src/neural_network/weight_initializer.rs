@@ -0,0 +1,75 @@
+use crate::neural_network::neural_network::{MAX_POSITIVE_VALUE, MIN_NEGATIVE_VALUE};
+use rand::distributions::StandardNormal;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// How new connection weights are drawn. fan_in is the number of nodes of the layer a connection starts in and fan_out the
+// number of nodes of the layer it ends in.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WeightInitializer {
+    Uniform { min: f64, max: f64 },
+    // Glorot uniform, meant for sigmoid and tanh layers.
+    Xavier,
+    // Gaussian with a standard deviation of sqrt(2 / fan_in), meant for relu layers.
+    He,
+    Normal { sigma: f64 },
+    Zeros,
+}
+
+impl Default for WeightInitializer {
+    fn default() -> WeightInitializer {
+        WeightInitializer::Uniform {
+            min: MIN_NEGATIVE_VALUE,
+            max: MAX_POSITIVE_VALUE,
+        }
+    }
+}
+
+impl WeightInitializer {
+    pub fn sample<R: Rng>(&self, fan_in: usize, fan_out: usize, rng: &mut R) -> f64 {
+        match *self {
+            WeightInitializer::Uniform { min, max } => rng.gen_range(min, max),
+            WeightInitializer::Xavier => {
+                let limit = (6.0 / (fan_in + fan_out).max(1) as f64).sqrt();
+                rng.gen_range(-limit, limit)
+            }
+            WeightInitializer::He => {
+                let sigma = (2.0 / fan_in.max(1) as f64).sqrt();
+                sigma * rng.sample::<f64, _>(StandardNormal)
+            }
+            WeightInitializer::Normal { sigma } => sigma * rng.sample::<f64, _>(StandardNormal),
+            WeightInitializer::Zeros => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod weight_initializer_test {
+    use crate::neural_network::weight_initializer::WeightInitializer;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    #[test]
+    fn weight_initializer_range_test() {
+        let mut rng: ChaChaRng = SeedableRng::from_seed(*DEFAULT_SEED);
+        for _ in 0..1000 {
+            let weight = WeightInitializer::default().sample(4, 4, &mut rng);
+            assert!((-0.1..0.1).contains(&weight));
+            // sqrt(6 / (10 + 14)) = 0.5
+            let weight = WeightInitializer::Xavier.sample(10, 14, &mut rng);
+            assert!((-0.5..0.5).contains(&weight));
+            assert_eq!(WeightInitializer::Zeros.sample(4, 4, &mut rng), 0.0);
+        }
+
+        let he_weights: Vec<f64> = (0..10000)
+            .map(|_| WeightInitializer::He.sample(8, 1, &mut rng))
+            .collect();
+        let variance = he_weights.iter().map(|weight| weight * weight).sum::<f64>() / 10000.0;
+        assert!((variance - 0.25).abs() < 0.02);
+    }
+}
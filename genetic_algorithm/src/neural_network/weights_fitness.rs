@@ -0,0 +1,76 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::neural_network::neural_network::NeuralNetwork;
+
+// Lets real valued optimizers (evolution strategies, particle swarms) evolve the weights of a fixed network topology.
+// The flat weight vector uses the ordering of `NeuralNetwork::weights`.
+pub struct NetworkWeightsFitness {
+    network: NeuralNetwork,
+    fitness_function: Box<dyn FitnessFunction<T = NeuralNetwork>>,
+}
+
+impl NetworkWeightsFitness {
+    pub fn new(
+        network: NeuralNetwork,
+        fitness_function: Box<dyn FitnessFunction<T = NeuralNetwork>>,
+    ) -> NetworkWeightsFitness {
+        NetworkWeightsFitness {
+            network,
+            fitness_function,
+        }
+    }
+
+    pub fn network(&self) -> &NeuralNetwork {
+        &self.network
+    }
+
+    pub fn network_with_weights(&self, weights: &[f64]) -> NeuralNetwork {
+        let mut network = self.network.clone();
+        network.set_weights(weights);
+        network
+    }
+}
+
+impl FitnessFunction for NetworkWeightsFitness {
+    type T = Vec<f64>;
+
+    fn calculate_fitness(&mut self, weights: &Vec<f64>) -> f64 {
+        self.network.set_weights(weights);
+        self.fitness_function.calculate_fitness(&self.network)
+    }
+}
+
+#[cfg(test)]
+mod weights_fitness_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::neural_network::neural_network::NeuralNetwork;
+    use crate::neural_network::weights_fitness::NetworkWeightsFitness;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestNeuralNetworkFitnessFunction;
+    impl FitnessFunction for TestNeuralNetworkFitnessFunction {
+        type T = NeuralNetwork;
+
+        fn calculate_fitness(&mut self, net: &NeuralNetwork) -> f64 {
+            net.feedforward(&[1.0])[0]
+        }
+    }
+
+    #[test]
+    fn weights_fitness_test() {
+        let net = NeuralNetwork::new(1, &[], 1, *DEFAULT_SEED);
+        let mut fitness_function =
+            NetworkWeightsFitness::new(net, Box::new(TestNeuralNetworkFitnessFunction::default()));
+
+        // One input weight and one output bias weight.
+        assert_eq!(fitness_function.calculate_fitness(&vec![0.0, 0.0]), 0.5);
+        assert!(fitness_function.calculate_fitness(&vec![10.0, 0.0]) > 0.99);
+        assert_eq!(
+            fitness_function.network_with_weights(&[2.0, 1.0]).weights(),
+            vec![2.0, 1.0]
+        );
+    }
+}
@@ -0,0 +1 @@
+pub mod particle_swarm;
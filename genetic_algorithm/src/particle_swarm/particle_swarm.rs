@@ -0,0 +1,436 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::ProblemType;
+use crate::genome::statistics::GenerationStatistics;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Topology {
+    // Every particle is attracted to the best position found by the whole swarm.
+    GlobalBest,
+    // Every particle is attracted to the best position found by the `neighbours` particles on each side of it in a ring.
+    Ring { neighbours: usize },
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum InertiaSchedule {
+    Constant(f64),
+    // Linearly moves the inertia weight from start to end over the given amount of iterations.
+    LinearDecreasing {
+        start: f64,
+        end: f64,
+        iterations: u64,
+    },
+    // Draws a new inertia weight in [0.5, 1.0) every iteration.
+    Random,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum VelocityUpdate {
+    Inertia {
+        schedule: InertiaSchedule,
+        cognitive: f64,
+        social: f64,
+    },
+    // Clerc's constriction factor. cognitive + social has to be greater than 4.
+    Constriction {
+        cognitive: f64,
+        social: f64,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Particle {
+    position: Vec<f64>,
+    velocity: Vec<f64>,
+    fitness: f64,
+    best_position: Vec<f64>,
+    best_fitness: f64,
+}
+
+pub struct ParticleSwarm {
+    particles: Vec<Particle>,
+    bounds: Vec<(f64, f64)>,
+    topology: Topology,
+    velocity_update: VelocityUpdate,
+    problem_type: ProblemType,
+    iteration: u64,
+    seed: StdRng,
+}
+
+impl Particle {
+    pub fn position(&self) -> &Vec<f64> {
+        &self.position
+    }
+    pub fn fitness(&self) -> f64 {
+        self.fitness
+    }
+    pub fn best_position(&self) -> &Vec<f64> {
+        &self.best_position
+    }
+    pub fn best_fitness(&self) -> f64 {
+        self.best_fitness
+    }
+}
+
+impl ParticleSwarm {
+    pub fn new(
+        swarm_size: usize,
+        bounds: Vec<(f64, f64)>,
+        problem_type: ProblemType,
+        topology: Topology,
+        velocity_update: VelocityUpdate,
+        fitness_function: &mut Box<dyn FitnessFunction<T = Vec<f64>>>,
+        seed: [u8; 32],
+    ) -> ParticleSwarm {
+        if swarm_size == 0 {
+            panic!("Please make the swarm size greater than 0.");
+        }
+        if let Some(&(lower, upper)) = bounds.iter().find(|&&(lower, upper)| lower > upper) {
+            panic!(
+                "Got the bounds ({}, {}), please make every lower bound less than or equal to its upper bound.",
+                lower, upper
+            );
+        }
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let mut particles = Vec::new();
+
+        for _ in 0..swarm_size {
+            let mut position = Vec::new();
+            let mut velocity = Vec::new();
+            for &(lower, upper) in bounds.iter() {
+                // Equal bounds fix the dimension.
+                if lower == upper {
+                    position.push(lower);
+                    velocity.push(0.0);
+                    continue;
+                }
+                position.push(rng.gen_range(lower, upper));
+                let range = upper - lower;
+                velocity.push(rng.gen_range(-range, range) * 0.5);
+            }
+            let fitness = fitness_function.calculate_fitness(&position);
+            particles.push(Particle {
+                best_position: position.clone(),
+                best_fitness: fitness,
+                position,
+                velocity,
+                fitness,
+            });
+        }
+
+        ParticleSwarm {
+            particles,
+            bounds,
+            topology,
+            velocity_update,
+            problem_type,
+            iteration: 0,
+            seed: rng,
+        }
+    }
+
+    // Moves every particle once and returns the statistics of the swarm after the move.
+    pub fn step(
+        &mut self,
+        fitness_function: &mut Box<dyn FitnessFunction<T = Vec<f64>>>,
+    ) -> GenerationStatistics {
+        let attractors: Vec<Vec<f64>> = (0..self.particles.len())
+            .map(|index| self.neighbourhood_best(index).best_position.clone())
+            .collect();
+
+        let (inertia, cognitive, social, constriction) = match self.velocity_update {
+            VelocityUpdate::Inertia {
+                schedule,
+                cognitive,
+                social,
+            } => (self.inertia_weight(schedule), cognitive, social, 1.0),
+            VelocityUpdate::Constriction { cognitive, social } => (
+                1.0,
+                cognitive,
+                social,
+                constriction_factor(cognitive + social),
+            ),
+        };
+
+        for (particle, attractor) in self.particles.iter_mut().zip(attractors.iter()) {
+            for dimension in 0..particle.position.len() {
+                let (lower, upper) = self.bounds[dimension];
+                let max_velocity = upper - lower;
+
+                let cognitive_pull = cognitive
+                    * self.seed.gen::<f64>()
+                    * (particle.best_position[dimension] - particle.position[dimension]);
+                let social_pull = social
+                    * self.seed.gen::<f64>()
+                    * (attractor[dimension] - particle.position[dimension]);
+
+                let mut velocity = constriction
+                    * (inertia * particle.velocity[dimension] + cognitive_pull + social_pull);
+                velocity = velocity.max(-max_velocity).min(max_velocity);
+
+                let mut position = particle.position[dimension] + velocity;
+                // Particles that leave the search space are put back on the boundary and stop moving in that dimension.
+                if position < lower || position > upper {
+                    position = position.max(lower).min(upper);
+                    velocity = 0.0;
+                }
+
+                particle.velocity[dimension] = velocity;
+                particle.position[dimension] = position;
+            }
+
+            particle.fitness = fitness_function.calculate_fitness(&particle.position);
+            if self
+                .problem_type
+                .is_better(particle.fitness, particle.best_fitness)
+            {
+                particle.best_fitness = particle.fitness;
+                particle.best_position = particle.position.clone();
+            }
+        }
+
+        self.iteration += 1;
+        self.statistics()
+    }
+
+    pub fn run(
+        &mut self,
+        iterations: u64,
+        fitness_function: &mut Box<dyn FitnessFunction<T = Vec<f64>>>,
+    ) -> Vec<GenerationStatistics> {
+        (0..iterations)
+            .map(|_| self.step(fitness_function))
+            .collect()
+    }
+
+    pub fn statistics(&self) -> GenerationStatistics {
+        let fitnesses: Vec<f64> = self
+            .particles
+            .iter()
+            .map(|particle| particle.fitness)
+            .collect();
+        GenerationStatistics::from_fitnesses(self.iteration, &fitnesses, self.problem_type)
+    }
+
+    // Best position any particle has visited so far.
+    pub fn global_best(&self) -> &Particle {
+        let mut best = &self.particles[0];
+        for particle in self.particles.iter().skip(1) {
+            if self
+                .problem_type
+                .is_better(particle.best_fitness, best.best_fitness)
+            {
+                best = particle;
+            }
+        }
+        best
+    }
+
+    pub fn particles(&self) -> &Vec<Particle> {
+        &self.particles
+    }
+
+    pub fn iteration(&self) -> u64 {
+        self.iteration
+    }
+
+    fn neighbourhood_best(&self, index: usize) -> &Particle {
+        match self.topology {
+            Topology::GlobalBest => self.global_best(),
+            Topology::Ring { neighbours } => {
+                let swarm_size = self.particles.len();
+                let mut best = &self.particles[index];
+                for offset in 1..=neighbours.min(swarm_size / 2) {
+                    let left = &self.particles[(index + swarm_size - offset) % swarm_size];
+                    let right = &self.particles[(index + offset) % swarm_size];
+                    for neighbour in [left, right].iter() {
+                        if self
+                            .problem_type
+                            .is_better(neighbour.best_fitness, best.best_fitness)
+                        {
+                            best = neighbour;
+                        }
+                    }
+                }
+                best
+            }
+        }
+    }
+
+    fn inertia_weight(&mut self, schedule: InertiaSchedule) -> f64 {
+        match schedule {
+            InertiaSchedule::Constant(weight) => weight,
+            InertiaSchedule::LinearDecreasing {
+                start,
+                end,
+                iterations,
+            } => {
+                if iterations == 0 || self.iteration >= iterations {
+                    end
+                } else {
+                    start - (start - end) * (self.iteration as f64 / iterations as f64)
+                }
+            }
+            InertiaSchedule::Random => 0.5 + self.seed.gen::<f64>() / 2.0,
+        }
+    }
+}
+
+fn constriction_factor(phi: f64) -> f64 {
+    if phi <= 4.0 {
+        panic!(
+            "The constriction factor requires cognitive + social to be greater than 4. Current value is: {}",
+            phi
+        );
+    }
+    2.0 / (2.0 - phi - (phi * phi - 4.0 * phi).sqrt()).abs()
+}
+
+#[cfg(test)]
+mod particle_swarm_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::ProblemType;
+    use crate::particle_swarm::particle_swarm::{
+        constriction_factor, InertiaSchedule, ParticleSwarm, Topology, VelocityUpdate,
+    };
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    #[derive(Default, Copy, Clone, Debug)]
+    struct TestSphereFitnessFunction;
+    impl FitnessFunction for TestSphereFitnessFunction {
+        type T = Vec<f64>;
+
+        fn calculate_fitness(&mut self, individual: &Vec<f64>) -> f64 {
+            individual.iter().map(|x| x * x).sum()
+        }
+    }
+
+    #[test]
+    fn global_best_inertia_converges() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestSphereFitnessFunction::default());
+        let mut swarm = ParticleSwarm::new(
+            20,
+            vec![(-5.0, 5.0); 3],
+            ProblemType::Min,
+            Topology::GlobalBest,
+            VelocityUpdate::Inertia {
+                schedule: InertiaSchedule::LinearDecreasing {
+                    start: 0.9,
+                    end: 0.4,
+                    iterations: 100,
+                },
+                cognitive: 2.0,
+                social: 2.0,
+            },
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+        let start_fitness = swarm.global_best().best_fitness();
+        let stats = swarm.run(100, &mut fitness_function);
+
+        assert_eq!(stats.len(), 100);
+        assert_eq!(stats[99].generation, 100);
+        assert!(swarm.global_best().best_fitness() <= start_fitness);
+        assert!(swarm.global_best().best_fitness() < 0.01);
+        for particle in swarm.particles() {
+            for position in particle.position() {
+                assert!(*position >= -5.0 && *position <= 5.0);
+            }
+        }
+    }
+
+    #[test]
+    fn ring_constriction_converges() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestSphereFitnessFunction::default());
+        let mut swarm = ParticleSwarm::new(
+            20,
+            vec![(-5.0, 5.0); 3],
+            ProblemType::Min,
+            Topology::Ring { neighbours: 1 },
+            VelocityUpdate::Constriction {
+                cognitive: 2.05,
+                social: 2.05,
+            },
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+        swarm.run(200, &mut fitness_function);
+
+        assert!(swarm.global_best().best_fitness() < 0.01);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_swarm_is_rejected() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestSphereFitnessFunction::default());
+        ParticleSwarm::new(
+            0,
+            vec![(-5.0, 5.0); 3],
+            ProblemType::Min,
+            Topology::GlobalBest,
+            VelocityUpdate::Constriction {
+                cognitive: 2.05,
+                social: 2.05,
+            },
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+    }
+
+    #[test]
+    fn fixed_dimension_test() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestSphereFitnessFunction::default());
+        let mut swarm = ParticleSwarm::new(
+            10,
+            vec![(-5.0, 5.0), (2.0, 2.0)],
+            ProblemType::Min,
+            Topology::GlobalBest,
+            VelocityUpdate::Constriction {
+                cognitive: 2.05,
+                social: 2.05,
+            },
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+        swarm.run(50, &mut fitness_function);
+
+        for particle in swarm.particles() {
+            assert_eq!(particle.position()[1], 2.0);
+        }
+        assert!((swarm.global_best().best_fitness() - 4.0).abs() < 0.01);
+    }
+
+    #[test]
+    #[should_panic]
+    fn reversed_bounds_are_rejected() {
+        let mut fitness_function: Box<dyn FitnessFunction<T = Vec<f64>>> =
+            Box::new(TestSphereFitnessFunction::default());
+        ParticleSwarm::new(
+            10,
+            vec![(-5.0, 5.0), (1.0, -1.0)],
+            ProblemType::Min,
+            Topology::GlobalBest,
+            VelocityUpdate::Constriction {
+                cognitive: 2.05,
+                social: 2.05,
+            },
+            &mut fitness_function,
+            *DEFAULT_SEED,
+        );
+    }
+
+    #[test]
+    fn constriction_factor_test() {
+        assert!((constriction_factor(4.1) - 0.7298).abs() < 0.0001);
+    }
+}
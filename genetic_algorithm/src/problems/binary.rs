@@ -0,0 +1,402 @@
+use crate::genome::fitness_function::FitnessFunction;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Binary benchmark problems on strings of '0' and '1', the genomes of StringMutation and StringCrossover. Any other
+// character counts as a zero. All of them are maximized and know their optimal fitness, so runs can be checked against
+// it.
+pub trait BinaryBenchmark: FitnessFunction<T = String> {
+    // Number of bits of a genome.
+    fn length(&self) -> usize;
+
+    fn optimal_fitness(&self) -> f64;
+
+    fn is_optimal(&mut self, individual: &String) -> bool {
+        (self.calculate_fitness(individual) - self.optimal_fitness()).abs() < 1e-9
+    }
+}
+
+pub(crate) fn bits(individual: &str, length: usize) -> Vec<bool> {
+    let bits: Vec<bool> = individual.chars().map(|bit| bit == '1').collect();
+    if bits.len() != length {
+        panic!(
+            "The problem needs {} bits but the individual has {}.",
+            length,
+            bits.len()
+        );
+    }
+    bits
+}
+
+pub(crate) fn bit_string(bits: &[bool]) -> String {
+    bits.iter()
+        .map(|&bit| if bit { '1' } else { '0' })
+        .collect()
+}
+
+// A random genome, e.g. for the initial population.
+pub fn random_bit_string(length: usize, rng: &mut impl Rng) -> String {
+    (0..length)
+        .map(|_| if rng.gen::<bool>() { '1' } else { '0' })
+        .collect()
+}
+
+// Number of ones.
+#[derive(Copy, Clone, Debug)]
+pub struct OneMax {
+    length: usize,
+}
+
+impl OneMax {
+    pub fn new(length: usize) -> OneMax {
+        OneMax { length }
+    }
+}
+
+impl FitnessFunction for OneMax {
+    type T = String;
+
+    fn calculate_fitness(&mut self, individual: &String) -> f64 {
+        bits(individual, self.length)
+            .iter()
+            .filter(|&&bit| bit)
+            .count() as f64
+    }
+}
+
+impl BinaryBenchmark for OneMax {
+    fn length(&self) -> usize {
+        self.length
+    }
+
+    fn optimal_fitness(&self) -> f64 {
+        self.length as f64
+    }
+}
+
+// Number of ones before the first zero.
+#[derive(Copy, Clone, Debug)]
+pub struct LeadingOnes {
+    length: usize,
+}
+
+impl LeadingOnes {
+    pub fn new(length: usize) -> LeadingOnes {
+        LeadingOnes { length }
+    }
+}
+
+impl FitnessFunction for LeadingOnes {
+    type T = String;
+
+    fn calculate_fitness(&mut self, individual: &String) -> f64 {
+        bits(individual, self.length)
+            .iter()
+            .take_while(|&&bit| bit)
+            .count() as f64
+    }
+}
+
+impl BinaryBenchmark for LeadingOnes {
+    fn length(&self) -> usize {
+        self.length
+    }
+
+    fn optimal_fitness(&self) -> f64 {
+        self.length as f64
+    }
+}
+
+// The genome is cut into blocks and every block of only ones scores its size, other blocks score nothing.
+#[derive(Copy, Clone, Debug)]
+pub struct RoyalRoad {
+    number_of_blocks: usize,
+    block_size: usize,
+}
+
+impl RoyalRoad {
+    pub fn new(number_of_blocks: usize, block_size: usize) -> RoyalRoad {
+        if block_size == 0 {
+            panic!("The blocks need at least 1 bit.");
+        }
+        RoyalRoad {
+            number_of_blocks,
+            block_size,
+        }
+    }
+}
+
+impl FitnessFunction for RoyalRoad {
+    type T = String;
+
+    fn calculate_fitness(&mut self, individual: &String) -> f64 {
+        bits(individual, self.length())
+            .chunks(self.block_size)
+            .filter(|block| block.iter().all(|&bit| bit))
+            .count() as f64
+            * self.block_size as f64
+    }
+}
+
+impl BinaryBenchmark for RoyalRoad {
+    fn length(&self) -> usize {
+        self.number_of_blocks * self.block_size
+    }
+
+    fn optimal_fitness(&self) -> f64 {
+        self.length() as f64
+    }
+}
+
+// Concatenated deceptive traps of k bits. A trap of only ones scores k, otherwise every one in it costs a point from
+// k - 1, so the slope leads towards the zeros while the optimum is all ones.
+#[derive(Copy, Clone, Debug)]
+pub struct DeceptiveTrap {
+    k: usize,
+    number_of_traps: usize,
+}
+
+impl DeceptiveTrap {
+    pub fn new(k: usize, number_of_traps: usize) -> DeceptiveTrap {
+        if k < 2 {
+            panic!("A trap needs at least 2 bits to be deceptive.");
+        }
+        DeceptiveTrap { k, number_of_traps }
+    }
+}
+
+impl FitnessFunction for DeceptiveTrap {
+    type T = String;
+
+    fn calculate_fitness(&mut self, individual: &String) -> f64 {
+        bits(individual, self.length())
+            .chunks(self.k)
+            .map(|trap| {
+                let ones = trap.iter().filter(|&&bit| bit).count();
+                if ones == self.k {
+                    self.k
+                } else {
+                    self.k - 1 - ones
+                }
+            })
+            .sum::<usize>() as f64
+    }
+}
+
+impl BinaryBenchmark for DeceptiveTrap {
+    fn length(&self) -> usize {
+        self.k * self.number_of_traps
+    }
+
+    fn optimal_fitness(&self) -> f64 {
+        self.length() as f64
+    }
+}
+
+// Hierarchical if-and-only-if on 2^levels bits. Every block in the binary tree over the genome, from single bits up to
+// the whole genome, scores its size when its bits are all the same. All ones and all zeros are both optimal.
+#[derive(Copy, Clone, Debug)]
+pub struct Hiff {
+    levels: u32,
+}
+
+impl Hiff {
+    pub fn new(levels: u32) -> Hiff {
+        Hiff { levels }
+    }
+}
+
+// Returns the score of the block and whether all of its bits are the same.
+fn hiff_block(bits: &[bool]) -> (f64, bool) {
+    if bits.len() == 1 {
+        return (1.0, true);
+    }
+    let (left, right) = bits.split_at(bits.len() / 2);
+    let (left_fitness, left_uniform) = hiff_block(left);
+    let (right_fitness, right_uniform) = hiff_block(right);
+    let uniform = left_uniform && right_uniform && left[0] == right[0];
+    let fitness = left_fitness + right_fitness;
+    if uniform {
+        (fitness + bits.len() as f64, true)
+    } else {
+        (fitness, false)
+    }
+}
+
+impl FitnessFunction for Hiff {
+    type T = String;
+
+    fn calculate_fitness(&mut self, individual: &String) -> f64 {
+        hiff_block(&bits(individual, self.length())).0
+    }
+}
+
+impl BinaryBenchmark for Hiff {
+    fn length(&self) -> usize {
+        1 << self.levels
+    }
+
+    fn optimal_fitness(&self) -> f64 {
+        ((self.levels + 1) as usize * self.length()) as f64
+    }
+}
+
+// NK landscape with adjacent neighbourhoods: bit i contributes a value from its own random table, looked up by bit i
+// and the k bits after it, wrapping around at the end. The fitness is the mean of the contributions. The tables are
+// drawn from the seed, so the same seed gives the same landscape, and the optimum is found by dynamic programming.
+#[derive(Clone, Debug)]
+pub struct NkLandscape {
+    n: usize,
+    k: usize,
+    // For every bit 2^(k + 1) values, indexed by its neighbourhood read as a binary number with bit i first.
+    tables: Vec<Vec<f64>>,
+}
+
+impl NkLandscape {
+    pub fn new(n: usize, k: usize, seed: [u8; 32]) -> NkLandscape {
+        if k >= n {
+            panic!("k has to be below n, got n = {} and k = {}.", n, k);
+        }
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let tables = (0..n)
+            .map(|_| (0..1 << (k + 1)).map(|_| rng.gen::<f64>()).collect())
+            .collect();
+        NkLandscape { n, k, tables }
+    }
+
+    pub fn tables(&self) -> &Vec<Vec<f64>> {
+        &self.tables
+    }
+}
+
+impl FitnessFunction for NkLandscape {
+    type T = String;
+
+    fn calculate_fitness(&mut self, individual: &String) -> f64 {
+        let bits = bits(individual, self.n);
+        (0..self.n)
+            .map(|bit| {
+                let neighbourhood = (0..=self.k).fold(0, |neighbourhood, offset| {
+                    (neighbourhood << 1) | bits[(bit + offset) % self.n] as usize
+                });
+                self.tables[bit][neighbourhood]
+            })
+            .sum::<f64>()
+            / self.n as f64
+    }
+}
+
+impl BinaryBenchmark for NkLandscape {
+    fn length(&self) -> usize {
+        self.n
+    }
+
+    // Fixes the first k bits and walks along the genome, keeping the best sum for every value of the last k bits. The
+    // contributions that wrap around are added once the last bit is set.
+    fn optimal_fitness(&self) -> f64 {
+        let k = self.k;
+        let state_mask = (1 << k) - 1;
+        let neighbourhood_mask = (1 << (k + 1)) - 1;
+        let mut optimum = std::f64::NEG_INFINITY;
+        for first_bits in 0..1usize << k {
+            let mut best = vec![std::f64::NEG_INFINITY; 1 << k];
+            best[first_bits] = 0.0;
+            for bit in k..self.n {
+                let mut next_best = vec![std::f64::NEG_INFINITY; 1 << k];
+                for (state, &sum) in best.iter().enumerate() {
+                    if sum == std::f64::NEG_INFINITY {
+                        continue;
+                    }
+                    for value in 0..2 {
+                        let neighbourhood = (state << 1) | value;
+                        let next_sum = sum + self.tables[bit - k][neighbourhood];
+                        let next_state = neighbourhood & state_mask;
+                        next_best[next_state] = next_best[next_state].max(next_sum);
+                    }
+                }
+                best = next_best;
+            }
+            for (last_bits, &sum) in best.iter().enumerate() {
+                if sum == std::f64::NEG_INFINITY {
+                    continue;
+                }
+                // The last k bits followed by the first k bits.
+                let wrapped = (last_bits << k) | first_bits;
+                let wrapped_sum: f64 = (0..k)
+                    .map(|offset| {
+                        self.tables[self.n - k + offset]
+                            [(wrapped >> (k - 1 - offset)) & neighbourhood_mask]
+                    })
+                    .sum();
+                optimum = optimum.max(sum + wrapped_sum);
+            }
+        }
+        optimum / self.n as f64
+    }
+}
+
+#[cfg(test)]
+mod binary_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::problems::binary::{
+        BinaryBenchmark, DeceptiveTrap, Hiff, LeadingOnes, NkLandscape, OneMax, RoyalRoad,
+    };
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    #[test]
+    fn binary_benchmark_test() {
+        let genome = String::from("11101111");
+        assert_eq!(OneMax::new(8).calculate_fitness(&genome), 7.0);
+        assert_eq!(LeadingOnes::new(8).calculate_fitness(&genome), 3.0);
+        assert_eq!(RoyalRoad::new(2, 4).calculate_fitness(&genome), 4.0);
+        // The first trap has three ones and the second is complete.
+        assert_eq!(DeceptiveTrap::new(4, 2).calculate_fitness(&genome), 4.0);
+        assert_eq!(
+            DeceptiveTrap::new(4, 2).calculate_fitness(&String::from("00000000")),
+            6.0
+        );
+        // Single bits score 8, the pairs 11, 10, 11 and 11 score 6 and the block 1111 scores 4.
+        assert_eq!(Hiff::new(3).calculate_fitness(&genome), 18.0);
+
+        let ones = "1".repeat(8);
+        let mut problems: Vec<Box<dyn BinaryBenchmark>> = vec![
+            Box::new(OneMax::new(8)),
+            Box::new(LeadingOnes::new(8)),
+            Box::new(RoyalRoad::new(2, 4)),
+            Box::new(DeceptiveTrap::new(4, 2)),
+            Box::new(Hiff::new(3)),
+        ];
+        for problem in problems.iter_mut() {
+            assert_eq!(problem.length(), 8);
+            assert!(problem.is_optimal(&ones));
+            assert!(!problem.is_optimal(&genome));
+        }
+        assert!(Hiff::new(3).is_optimal(&"0".repeat(8)));
+    }
+
+    #[test]
+    fn nk_landscape_test() {
+        for &k in [0, 2, 3].iter() {
+            let mut landscape = NkLandscape::new(8, k, *DEFAULT_SEED);
+            let best = (0..256usize)
+                .map(|genome| {
+                    let genome: String = (0..8)
+                        .map(|bit| if genome >> bit & 1 == 1 { '1' } else { '0' })
+                        .collect();
+                    landscape.calculate_fitness(&genome)
+                })
+                .fold(std::f64::NEG_INFINITY, f64::max);
+            assert!((landscape.optimal_fitness() - best).abs() < 1e-12);
+        }
+
+        let landscape = NkLandscape::new(8, 2, *DEFAULT_SEED);
+        assert_eq!(
+            NkLandscape::new(8, 2, *DEFAULT_SEED).tables(),
+            landscape.tables()
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::ProblemType;
+use rand::distributions::StandardNormal;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+// Optimum of Schwefel's function in every coordinate.
+const SCHWEFEL_OPTIMUM: f64 = 420.968_746_359_982;
+// Optimum of the Styblinski-Tang function in every coordinate.
+const STYBLINSKI_TANG_OPTIMUM: f64 = -2.903_534_027_771_177_6;
+
+// Standard continuous test functions, all minimized and defined for any number of dimensions.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BenchmarkFunction {
+    Sphere,
+    Rosenbrock,
+    Rastrigin,
+    Ackley,
+    Griewank,
+    Schwefel,
+    Levy,
+    StyblinskiTang,
+}
+
+impl BenchmarkFunction {
+    pub fn evaluate(&self, x: &[f64]) -> f64 {
+        let dimensions = x.len() as f64;
+        match self {
+            BenchmarkFunction::Sphere => x.iter().map(|xi| xi * xi).sum(),
+            BenchmarkFunction::Rosenbrock => x
+                .windows(2)
+                .map(|pair| 100.0 * (pair[1] - pair[0] * pair[0]).powi(2) + (1.0 - pair[0]).powi(2))
+                .sum(),
+            BenchmarkFunction::Rastrigin => {
+                10.0 * dimensions
+                    + x.iter()
+                        .map(|xi| xi * xi - 10.0 * (2.0 * PI * xi).cos())
+                        .sum::<f64>()
+            }
+            BenchmarkFunction::Ackley => {
+                let square_mean = x.iter().map(|xi| xi * xi).sum::<f64>() / dimensions;
+                let cosine_mean =
+                    x.iter().map(|xi| (2.0 * PI * xi).cos()).sum::<f64>() / dimensions;
+                -20.0 * (-0.2 * square_mean.sqrt()).exp() - cosine_mean.exp()
+                    + 20.0
+                    + std::f64::consts::E
+            }
+            BenchmarkFunction::Griewank => {
+                let sum = x.iter().map(|xi| xi * xi).sum::<f64>() / 4000.0;
+                let product = x
+                    .iter()
+                    .enumerate()
+                    .map(|(i, xi)| (xi / ((i + 1) as f64).sqrt()).cos())
+                    .product::<f64>();
+                sum - product + 1.0
+            }
+            // Outside of [-500, 500] the function keeps falling, so a coordinate out there counts as the nearest bound
+            // plus the squared distance to it, as in the CEC benchmarks. Shifted and rotated points can end up there.
+            BenchmarkFunction::Schwefel => {
+                418.982_887_272_433_9 * dimensions
+                    - x.iter()
+                        .map(|xi| {
+                            let zi = xi.clamp(-500.0, 500.0);
+                            zi * zi.abs().sqrt().sin() - (xi - zi).powi(2)
+                        })
+                        .sum::<f64>()
+            }
+            BenchmarkFunction::Levy => {
+                let w: Vec<f64> = x.iter().map(|xi| 1.0 + (xi - 1.0) / 4.0).collect();
+                let last = w[w.len() - 1];
+                (PI * w[0]).sin().powi(2)
+                    + w[..w.len() - 1]
+                        .iter()
+                        .map(|wi| (wi - 1.0).powi(2) * (1.0 + 10.0 * (PI * wi + 1.0).sin().powi(2)))
+                        .sum::<f64>()
+                    + (last - 1.0).powi(2) * (1.0 + (2.0 * PI * last).sin().powi(2))
+            }
+            BenchmarkFunction::StyblinskiTang => {
+                0.5 * x
+                    .iter()
+                    .map(|xi| xi.powi(4) - 16.0 * xi * xi + 5.0 * xi)
+                    .sum::<f64>()
+            }
+        }
+    }
+
+    // The usual search domain, the same for every coordinate.
+    pub fn bounds(&self) -> (f64, f64) {
+        match self {
+            BenchmarkFunction::Sphere | BenchmarkFunction::Rastrigin => (-5.12, 5.12),
+            BenchmarkFunction::Rosenbrock => (-5.0, 10.0),
+            BenchmarkFunction::Ackley => (-32.768, 32.768),
+            BenchmarkFunction::Griewank => (-600.0, 600.0),
+            BenchmarkFunction::Schwefel => (-500.0, 500.0),
+            BenchmarkFunction::Levy => (-10.0, 10.0),
+            BenchmarkFunction::StyblinskiTang => (-5.0, 5.0),
+        }
+    }
+
+    // The global optimum, the same value in every coordinate.
+    pub fn optimum_coordinate(&self) -> f64 {
+        match self {
+            BenchmarkFunction::Sphere
+            | BenchmarkFunction::Rastrigin
+            | BenchmarkFunction::Ackley
+            | BenchmarkFunction::Griewank => 0.0,
+            BenchmarkFunction::Rosenbrock | BenchmarkFunction::Levy => 1.0,
+            BenchmarkFunction::Schwefel => SCHWEFEL_OPTIMUM,
+            BenchmarkFunction::StyblinskiTang => STYBLINSKI_TANG_OPTIMUM,
+        }
+    }
+}
+
+// A benchmark function in a fixed number of dimensions, optionally shifted and rotated so the optimum is not in the
+// middle of the domain and the coordinates are not separable. The function is evaluated at
+// rotation * (x - shift) + optimum of the plain function, so the optimum of the benchmark is at the shift.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ContinuousBenchmark {
+    function: BenchmarkFunction,
+    dimensions: usize,
+    shift: Option<Vec<f64>>,
+    rotation: Option<Vec<Vec<f64>>>,
+}
+
+impl ContinuousBenchmark {
+    pub fn new(function: BenchmarkFunction, dimensions: usize) -> ContinuousBenchmark {
+        if dimensions == 0 {
+            panic!("The benchmark needs at least 1 dimension.");
+        }
+        ContinuousBenchmark {
+            function,
+            dimensions,
+            shift: None,
+            rotation: None,
+        }
+    }
+
+    // Moves the optimum to a random point in the inner 80% of the domain.
+    pub fn shifted(
+        function: BenchmarkFunction,
+        dimensions: usize,
+        seed: [u8; 32],
+    ) -> ContinuousBenchmark {
+        let mut benchmark = ContinuousBenchmark::new(function, dimensions);
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        benchmark.shift = Some(benchmark.random_shift(&mut rng));
+        benchmark
+    }
+
+    pub fn rotated(
+        function: BenchmarkFunction,
+        dimensions: usize,
+        seed: [u8; 32],
+    ) -> ContinuousBenchmark {
+        let mut benchmark = ContinuousBenchmark::new(function, dimensions);
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        benchmark.rotation = Some(random_rotation(dimensions, &mut rng));
+        benchmark
+    }
+
+    pub fn shifted_and_rotated(
+        function: BenchmarkFunction,
+        dimensions: usize,
+        seed: [u8; 32],
+    ) -> ContinuousBenchmark {
+        let mut benchmark = ContinuousBenchmark::new(function, dimensions);
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        benchmark.shift = Some(benchmark.random_shift(&mut rng));
+        benchmark.rotation = Some(random_rotation(dimensions, &mut rng));
+        benchmark
+    }
+
+    fn random_shift(&self, rng: &mut StdRng) -> Vec<f64> {
+        let (lower, upper) = self.function.bounds();
+        let margin = 0.1 * (upper - lower);
+        (0..self.dimensions)
+            .map(|_| rng.gen_range(lower + margin, upper - margin))
+            .collect()
+    }
+
+    pub fn function(&self) -> BenchmarkFunction {
+        self.function
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn shift(&self) -> Option<&Vec<f64>> {
+        self.shift.as_ref()
+    }
+
+    // Orthogonal matrix, one row per output coordinate.
+    pub fn rotation(&self) -> Option<&Vec<Vec<f64>>> {
+        self.rotation.as_ref()
+    }
+
+    // Per dimension, in the form ParticleSwarm and EvolutionStrategy::new_within_bounds take.
+    pub fn bounds(&self) -> Vec<(f64, f64)> {
+        vec![self.function.bounds(); self.dimensions]
+    }
+
+    pub fn problem_type(&self) -> ProblemType {
+        ProblemType::Min
+    }
+
+    pub fn optimum(&self) -> Vec<f64> {
+        match &self.shift {
+            Some(shift) => shift.clone(),
+            None => vec![self.function.optimum_coordinate(); self.dimensions],
+        }
+    }
+
+    pub fn optimal_fitness(&self) -> f64 {
+        self.function
+            .evaluate(&vec![self.function.optimum_coordinate(); self.dimensions])
+    }
+
+    pub fn evaluate(&self, x: &[f64]) -> f64 {
+        if x.len() != self.dimensions {
+            panic!(
+                "The benchmark has {} dimensions but got {}.",
+                self.dimensions,
+                x.len()
+            );
+        }
+        if self.shift.is_none() && self.rotation.is_none() {
+            return self.function.evaluate(x);
+        }
+        let offset: Vec<f64> = match &self.shift {
+            Some(shift) => x.iter().zip(shift.iter()).map(|(xi, si)| xi - si).collect(),
+            None => {
+                let optimum = self.function.optimum_coordinate();
+                x.iter().map(|xi| xi - optimum).collect()
+            }
+        };
+        let offset = match &self.rotation {
+            Some(rotation) => rotation
+                .iter()
+                .map(|row| row.iter().zip(offset.iter()).map(|(r, o)| r * o).sum())
+                .collect(),
+            None => offset,
+        };
+        let optimum = self.function.optimum_coordinate();
+        let z: Vec<f64> = offset.iter().map(|o| o + optimum).collect();
+        self.function.evaluate(&z)
+    }
+}
+
+impl FitnessFunction for ContinuousBenchmark {
+    type T = Vec<f64>;
+
+    fn calculate_fitness(&mut self, individual: &Vec<f64>) -> f64 {
+        self.evaluate(individual)
+    }
+}
+
+// Gram-Schmidt orthonormalization of a matrix of normally distributed values.
+fn random_rotation(dimensions: usize, rng: &mut StdRng) -> Vec<Vec<f64>> {
+    let mut rows: Vec<Vec<f64>> = Vec::with_capacity(dimensions);
+    while rows.len() < dimensions {
+        let mut row: Vec<f64> = (0..dimensions)
+            .map(|_| rng.sample::<f64, _>(StandardNormal))
+            .collect();
+        for previous in rows.iter() {
+            let projection: f64 = row.iter().zip(previous.iter()).map(|(a, b)| a * b).sum();
+            for (value, previous_value) in row.iter_mut().zip(previous.iter()) {
+                *value -= projection * previous_value;
+            }
+        }
+        let norm = row.iter().map(|value| value * value).sum::<f64>().sqrt();
+        // Draw again in the unlikely case the row was almost a combination of the previous ones.
+        if norm > 1e-8 {
+            rows.push(row.iter().map(|value| value / norm).collect());
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod continuous_test {
+    use crate::problems::continuous::{BenchmarkFunction, ContinuousBenchmark};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    const FUNCTIONS: [BenchmarkFunction; 8] = [
+        BenchmarkFunction::Sphere,
+        BenchmarkFunction::Rosenbrock,
+        BenchmarkFunction::Rastrigin,
+        BenchmarkFunction::Ackley,
+        BenchmarkFunction::Griewank,
+        BenchmarkFunction::Schwefel,
+        BenchmarkFunction::Levy,
+        BenchmarkFunction::StyblinskiTang,
+    ];
+
+    #[test]
+    fn benchmark_optimum_test() {
+        for &function in FUNCTIONS.iter() {
+            let benchmark = ContinuousBenchmark::new(function, 4);
+            let optimum = benchmark.optimum();
+            let optimal_fitness = benchmark.optimal_fitness();
+            assert!((benchmark.evaluate(&optimum) - optimal_fitness).abs() < 1e-9);
+
+            // Nearby points are worse.
+            for dimension in 0..4 {
+                let mut nearby = optimum.clone();
+                nearby[dimension] += 0.01;
+                assert!(
+                    benchmark.evaluate(&nearby) > optimal_fitness,
+                    "{:?}",
+                    function
+                );
+            }
+            let (lower, upper) = benchmark.bounds()[0];
+            assert!(lower < optimum[0] && optimum[0] < upper);
+        }
+        assert!(
+            ContinuousBenchmark::new(BenchmarkFunction::Schwefel, 4)
+                .optimal_fitness()
+                .abs()
+                < 1e-3
+        );
+        assert!(
+            (ContinuousBenchmark::new(BenchmarkFunction::StyblinskiTang, 2).optimal_fitness()
+                + 2.0 * 39.166_165_703_771_42)
+                .abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn benchmark_bounds_test() {
+        let mut rng: StdRng = SeedableRng::from_seed(*DEFAULT_SEED);
+        for &function in FUNCTIONS.iter() {
+            let benchmarks = [
+                ContinuousBenchmark::new(function, 3),
+                ContinuousBenchmark::shifted(function, 3, *DEFAULT_SEED),
+                ContinuousBenchmark::rotated(function, 3, *DEFAULT_SEED),
+                ContinuousBenchmark::shifted_and_rotated(function, 3, *DEFAULT_SEED),
+            ];
+            for benchmark in benchmarks.iter() {
+                let bounds = benchmark.bounds();
+                let corners = (0..8).map(|corner: usize| {
+                    bounds
+                        .iter()
+                        .enumerate()
+                        .map(
+                            |(i, &(lower, upper))| if corner >> i & 1 == 0 { lower } else { upper },
+                        )
+                        .collect::<Vec<f64>>()
+                });
+                let samples: Vec<Vec<f64>> = (0..2000)
+                    .map(|_| {
+                        bounds
+                            .iter()
+                            .map(|&(lower, upper)| rng.gen_range(lower, upper))
+                            .collect()
+                    })
+                    .chain(corners)
+                    .collect();
+                for sample in samples.iter() {
+                    assert!(
+                        benchmark.evaluate(sample) >= benchmark.optimal_fitness() - 1e-9,
+                        "{:?} at {:?}",
+                        function,
+                        sample
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shifted_and_rotated_benchmark_test() {
+        for &function in FUNCTIONS.iter() {
+            let benchmark = ContinuousBenchmark::shifted_and_rotated(function, 3, *DEFAULT_SEED);
+            let optimum = benchmark.optimum();
+            assert_eq!(benchmark.shift(), Some(&optimum));
+            assert!((benchmark.evaluate(&optimum) - benchmark.optimal_fitness()).abs() < 1e-9);
+            let mut nearby = optimum.clone();
+            nearby[0] += 0.01;
+            assert!(benchmark.evaluate(&nearby) > benchmark.optimal_fitness());
+        }
+
+        // Shifted by -400, the point -108 maps to 713, outside of the domain of the plain function.
+        let mut schwefel = ContinuousBenchmark::new(BenchmarkFunction::Schwefel, 1);
+        schwefel.shift = Some(vec![-400.0]);
+        assert!(schwefel.evaluate(&[-108.0]) > schwefel.optimal_fitness());
+
+        // The rotation keeps distances, so the rotated sphere only depends on the distance to the optimum.
+        let sphere = ContinuousBenchmark::rotated(BenchmarkFunction::Sphere, 3, *DEFAULT_SEED);
+        assert!((sphere.evaluate(&[1.0, 2.0, 2.0]) - 9.0).abs() < 1e-9);
+        let rotation = sphere.rotation().unwrap();
+        assert!(rotation[0][0] != 1.0);
+        let shifted = ContinuousBenchmark::shifted(BenchmarkFunction::Sphere, 3, *DEFAULT_SEED);
+        assert!(shifted.rotation().is_none());
+        assert!(shifted.optimum() != vec![0.0; 3]);
+    }
+}
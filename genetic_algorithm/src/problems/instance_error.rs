@@ -0,0 +1,38 @@
+use std::fmt;
+
+// Error of reading a problem instance file, e.g. a TSPLIB file.
+#[derive(Debug)]
+pub enum InstanceError {
+    Io(std::io::Error),
+    InvalidFormat(String),
+}
+
+impl fmt::Display for InstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InstanceError::Io(error) => write!(f, "Could not read the instance: {}", error),
+            InstanceError::InvalidFormat(reason) => write!(f, "Invalid instance: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for InstanceError {}
+
+impl From<std::io::Error> for InstanceError {
+    fn from(error: std::io::Error) -> InstanceError {
+        InstanceError::Io(error)
+    }
+}
+
+// Parses the next whitespace separated token as a number.
+pub(crate) fn next_number<'a, N: std::str::FromStr>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+    what: &str,
+) -> Result<N, InstanceError> {
+    let token = tokens.next().ok_or_else(|| {
+        InstanceError::InvalidFormat(format!("the file ends before the {}", what))
+    })?;
+    token
+        .parse::<N>()
+        .map_err(|_| InstanceError::InvalidFormat(format!("{:?} is not a valid {}", token, what)))
+}
@@ -0,0 +1,326 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::problems::binary::{bit_string, bits};
+use crate::problems::instance_error::{next_number, InstanceError};
+use crate::problems::repair::Repair;
+use std::fs;
+use std::sync::Arc;
+
+// Multidimensional 0/1 knapsack: pick items to maximize the profit without any of the constraints going over its
+// capacity. The 0/1 knapsack is the case of a single constraint. Genomes are strings of one bit per item.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Knapsack {
+    profits: Vec<f64>,
+    // One row per constraint, with the weight of every item.
+    weights: Vec<Vec<f64>>,
+    capacities: Vec<f64>,
+    optimal_profit: Option<f64>,
+}
+
+impl Knapsack {
+    pub fn new(profits: Vec<f64>, weights: Vec<Vec<f64>>, capacities: Vec<f64>) -> Knapsack {
+        if weights.len() != capacities.len() {
+            panic!(
+                "Got {} rows of weights for {} capacities.",
+                weights.len(),
+                capacities.len()
+            );
+        }
+        if weights.iter().any(|row| row.len() != profits.len()) {
+            panic!(
+                "Every constraint needs a weight for each of the {} items.",
+                profits.len()
+            );
+        }
+        Knapsack {
+            profits,
+            weights,
+            capacities,
+            optimal_profit: None,
+        }
+    }
+
+    pub fn zero_one(profits: Vec<f64>, weights: Vec<f64>, capacity: f64) -> Knapsack {
+        Knapsack::new(profits, vec![weights], vec![capacity])
+    }
+
+    pub fn read_from_file(file_path: &str) -> Result<Vec<Knapsack>, InstanceError> {
+        Knapsack::parse(&fs::read_to_string(file_path)?)
+    }
+
+    // Parses the OR-Library mknap format: the number of instances, then for every instance the number of items n, the
+    // number of constraints m and the optimal profit (0 when unknown), n profits, m rows of n weights and m capacities.
+    pub fn parse(text: &str) -> Result<Vec<Knapsack>, InstanceError> {
+        let mut tokens = text.split_whitespace();
+        let number_of_instances: usize = next_number(&mut tokens, "number of instances")?;
+        let mut instances = Vec::with_capacity(number_of_instances);
+        for _ in 0..number_of_instances {
+            let number_of_items: usize = next_number(&mut tokens, "number of items")?;
+            let number_of_constraints: usize = next_number(&mut tokens, "number of constraints")?;
+            let optimal_profit: f64 = next_number(&mut tokens, "optimal profit")?;
+            let mut read_numbers = |count: usize, what: &str| {
+                (0..count)
+                    .map(|_| next_number::<f64>(&mut tokens, what))
+                    .collect::<Result<Vec<f64>, InstanceError>>()
+            };
+            let profits = read_numbers(number_of_items, "profit")?;
+            let weights = (0..number_of_constraints)
+                .map(|_| read_numbers(number_of_items, "weight"))
+                .collect::<Result<Vec<Vec<f64>>, InstanceError>>()?;
+            let capacities = read_numbers(number_of_constraints, "capacity")?;
+
+            let mut knapsack = Knapsack::new(profits, weights, capacities);
+            if optimal_profit > 0.0 {
+                knapsack.optimal_profit = Some(optimal_profit);
+            }
+            instances.push(knapsack);
+        }
+        Ok(instances)
+    }
+
+    pub fn number_of_items(&self) -> usize {
+        self.profits.len()
+    }
+
+    pub fn number_of_constraints(&self) -> usize {
+        self.capacities.len()
+    }
+
+    pub fn profits(&self) -> &Vec<f64> {
+        &self.profits
+    }
+
+    pub fn weights(&self) -> &Vec<Vec<f64>> {
+        &self.weights
+    }
+
+    pub fn capacities(&self) -> &Vec<f64> {
+        &self.capacities
+    }
+
+    // Known from the instance file, if it has one.
+    pub fn optimal_profit(&self) -> Option<f64> {
+        self.optimal_profit
+    }
+
+    pub fn profit(&self, selection: &[bool]) -> f64 {
+        self.profits
+            .iter()
+            .zip(selection.iter())
+            .filter(|(_, &selected)| selected)
+            .map(|(profit, _)| profit)
+            .sum()
+    }
+
+    // Total weight per constraint.
+    pub fn loads(&self, selection: &[bool]) -> Vec<f64> {
+        self.weights
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(selection.iter())
+                    .filter(|(_, &selected)| selected)
+                    .map(|(weight, _)| weight)
+                    .sum()
+            })
+            .collect()
+    }
+
+    // Sum of the weight over the capacity of every constraint, 0 for a feasible selection.
+    pub fn excess(&self, selection: &[bool]) -> f64 {
+        self.loads(selection)
+            .iter()
+            .zip(self.capacities.iter())
+            .map(|(load, capacity)| (load - capacity).max(0.0))
+            .sum()
+    }
+
+    pub fn is_feasible(&self, individual: &str) -> bool {
+        self.excess(&bits(individual, self.number_of_items())) == 0.0
+    }
+}
+
+// Profit minus penalty times the excess weight, to be maximized. Use a penalty above the largest profit per unit of
+// weight, so no infeasible selection beats the feasible ones it contains.
+#[derive(Clone, Debug)]
+pub struct KnapsackFitness {
+    knapsack: Arc<Knapsack>,
+    penalty: f64,
+}
+
+impl KnapsackFitness {
+    pub fn new(knapsack: Arc<Knapsack>, penalty: f64) -> KnapsackFitness {
+        KnapsackFitness { knapsack, penalty }
+    }
+}
+
+impl FitnessFunction for KnapsackFitness {
+    type T = String;
+
+    fn calculate_fitness(&mut self, individual: &String) -> f64 {
+        let selection = bits(individual, self.knapsack.number_of_items());
+        self.knapsack.profit(&selection) - self.penalty * self.knapsack.excess(&selection)
+    }
+}
+
+// Greedy repair of Chu and Beasley: items are ranked by their profit per weight, with the weights relative to the
+// capacities. The worst selected items are dropped until the selection fits, then the best unselected items that still
+// fit are added.
+#[derive(Clone, Debug)]
+pub struct KnapsackRepair {
+    knapsack: Arc<Knapsack>,
+    // Items from the best to the worst ratio.
+    ranking: Vec<usize>,
+}
+
+impl KnapsackRepair {
+    pub fn new(knapsack: Arc<Knapsack>) -> KnapsackRepair {
+        let ratio = |item: usize| {
+            let relative_weight: f64 = knapsack
+                .weights
+                .iter()
+                .zip(knapsack.capacities.iter())
+                .filter(|(row, _)| row[item] != 0.0)
+                .map(|(row, capacity)| row[item] / capacity)
+                .sum();
+            // Items without weight always fit, so they rank first.
+            if relative_weight == 0.0 {
+                std::f64::INFINITY
+            } else {
+                knapsack.profits[item] / relative_weight
+            }
+        };
+        let mut ranking: Vec<usize> = (0..knapsack.number_of_items()).collect();
+        ranking.sort_by(|&first, &second| ratio(second).partial_cmp(&ratio(first)).unwrap());
+        KnapsackRepair { knapsack, ranking }
+    }
+}
+
+impl Repair for KnapsackRepair {
+    type T = String;
+
+    fn repair(&self, individual: &String) -> String {
+        let mut selection = bits(individual, self.knapsack.number_of_items());
+        let mut loads = self.knapsack.loads(&selection);
+        let fits = |loads: &[f64]| {
+            loads
+                .iter()
+                .zip(self.knapsack.capacities.iter())
+                .all(|(load, capacity)| load <= capacity)
+        };
+
+        for &item in self.ranking.iter().rev() {
+            if fits(&loads) {
+                break;
+            }
+            if selection[item] {
+                selection[item] = false;
+                for (load, row) in loads.iter_mut().zip(self.knapsack.weights.iter()) {
+                    *load -= row[item];
+                }
+            }
+        }
+        for &item in self.ranking.iter() {
+            if selection[item] {
+                continue;
+            }
+            let new_loads: Vec<f64> = loads
+                .iter()
+                .zip(self.knapsack.weights.iter())
+                .map(|(load, row)| load + row[item])
+                .collect();
+            if fits(&new_loads) {
+                selection[item] = true;
+                loads = new_loads;
+            }
+        }
+        bit_string(&selection)
+    }
+}
+
+#[cfg(test)]
+mod knapsack_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::genome_mutation::Mutate;
+    use crate::problems::knapsack::{Knapsack, KnapsackFitness, KnapsackRepair};
+    use crate::problems::repair::{Repair, RepairStep};
+    use std::sync::Arc;
+
+    const MKNAP: &str = "2\n\
+                         4 1 10\n\
+                         6 5 4 3\n\
+                         4 3 2 3\n\
+                         7\n\
+                         3 2 0\n\
+                         1 2 3\n\
+                         1 1 1\n\
+                         2 2 2\n\
+                         2 3\n";
+
+    #[test]
+    fn knapsack_parse_test() {
+        let instances = Knapsack::parse(MKNAP).unwrap();
+        assert_eq!(instances.len(), 2);
+        assert_eq!(instances[0].number_of_items(), 4);
+        assert_eq!(instances[0].optimal_profit(), Some(10.0));
+        assert_eq!(instances[1].number_of_constraints(), 2);
+        assert_eq!(instances[1].optimal_profit(), None);
+        assert_eq!(*instances[1].capacities(), vec![2.0, 3.0]);
+        assert!(Knapsack::parse("1\n3 1 0\n1 2\n").is_err());
+    }
+
+    #[test]
+    fn knapsack_fitness_test() {
+        let knapsack = Arc::new(Knapsack::parse(MKNAP).unwrap().remove(0));
+        let mut fitness_function = KnapsackFitness::new(knapsack.clone(), 10.0);
+        assert_eq!(
+            fitness_function.calculate_fitness(&String::from("0110")),
+            9.0
+        );
+        // Weight 9 is 2 over the capacity.
+        assert_eq!(
+            fitness_function.calculate_fitness(&String::from("1110")),
+            -5.0
+        );
+        assert!(!knapsack.is_feasible("1110"));
+    }
+
+    #[test]
+    fn knapsack_repair_test() {
+        let knapsack = Arc::new(Knapsack::parse(MKNAP).unwrap().remove(0));
+        let repair = KnapsackRepair::new(knapsack.clone());
+        // Profit per weight is 1.5, 1.67, 2 and 1, so the last and then the first item are dropped.
+        assert_eq!(repair.repair(&String::from("1111")), "0110");
+        // Room is filled with the best items that fit.
+        assert_eq!(repair.repair(&String::from("0000")), "0110");
+        assert_eq!(repair.repair(&String::from("1000")), "1010");
+
+        // The last item weighs nothing and is worth nothing.
+        let with_empty_item = Arc::new(Knapsack::zero_one(
+            vec![6.0, 5.0, 0.0],
+            vec![4.0, 3.0, 0.0],
+            5.0,
+        ));
+        let repaired = KnapsackRepair::new(with_empty_item.clone()).repair(&String::from("110"));
+        assert_eq!(repaired, "011");
+        assert!(with_empty_item.is_feasible(&repaired));
+
+        let multidimensional = Arc::new(Knapsack::parse(MKNAP).unwrap().remove(1));
+        let repaired = KnapsackRepair::new(multidimensional.clone()).repair(&String::from("111"));
+        assert!(multidimensional.is_feasible(&repaired));
+
+        let population = Population::new(
+            vec![
+                Individual::new(String::from("1111"), 0.0),
+                Individual::new(String::from("0110"), 9.0),
+            ],
+            ProblemType::Max,
+        );
+        let new_population = RepairStep::new(repair)
+            .mutate(&population, Box::new(KnapsackFitness::new(knapsack, 10.0)));
+        for individual in new_population.iter() {
+            assert_eq!(individual.retrieve_individual(), "0110");
+            assert_eq!(*individual.fitness(), 9.0);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod binary;
+pub mod continuous;
+pub mod instance_error;
+pub mod knapsack;
+pub mod repair;
+pub mod sat;
+pub mod scheduling;
+pub mod set_cover;
+pub mod tsp;
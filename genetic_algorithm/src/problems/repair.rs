@@ -0,0 +1,46 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use serde::{Deserialize, Serialize};
+
+// Turns an infeasible genome into a feasible one. Feasible genomes may be improved as well.
+pub trait Repair {
+    type T;
+    fn repair(&self, individual: &Self::T) -> Self::T;
+}
+
+// Runs a repair on every individual, meant as the last mutation of a generation so only feasible genomes are selected:
+// `population.mutate(&mut RepairStep::new(repair), fitness_function)`. Only changed genomes are evaluated again.
+pub struct RepairStep<R> {
+    repair: R,
+}
+
+impl<R> RepairStep<R> {
+    pub fn new(repair: R) -> RepairStep<R> {
+        RepairStep { repair }
+    }
+}
+
+impl<R: Repair<T = T>, T: Clone + PartialEq + Serialize + Deserialize> Mutate for RepairStep<R> {
+    type T = T;
+
+    fn mutate(
+        &mut self,
+        population: &Population<T>,
+        mut fitness_function: Box<dyn FitnessFunction<T = T>>,
+    ) -> Vec<Individual<T>> {
+        population
+            .list_of_individuals()
+            .iter()
+            .map(|individual| {
+                let repaired = self.repair.repair(individual.retrieve_individual());
+                if repaired == *individual.retrieve_individual() {
+                    Individual::new(repaired, *individual.fitness())
+                } else {
+                    let fitness = fitness_function.calculate_fitness(&repaired);
+                    Individual::new(repaired, fitness)
+                }
+            })
+            .collect()
+    }
+}
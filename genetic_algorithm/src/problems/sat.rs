@@ -0,0 +1,550 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use crate::problems::binary::{bit_string, bits};
+use crate::problems::instance_error::InstanceError;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs;
+use std::sync::Arc;
+
+// Formula in conjunctive normal form. Literals are DIMACS numbers: variable v is v and its negation -v. Genomes are
+// strings of one bit per variable, bit i being variable i + 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cnf {
+    number_of_variables: usize,
+    clauses: Vec<Vec<i32>>,
+    // For every variable the clauses it occurs in.
+    occurrences: Vec<Vec<usize>>,
+}
+
+impl Cnf {
+    pub fn new(number_of_variables: usize, clauses: Vec<Vec<i32>>) -> Cnf {
+        let mut occurrences = vec![Vec::new(); number_of_variables];
+        for (clause_index, clause) in clauses.iter().enumerate() {
+            for &literal in clause.iter() {
+                let variable = literal.abs() as usize;
+                if literal == 0 || variable > number_of_variables {
+                    panic!(
+                        "Literal {} is out of range, there are {} variables.",
+                        literal, number_of_variables
+                    );
+                }
+                if !occurrences[variable - 1].contains(&clause_index) {
+                    occurrences[variable - 1].push(clause_index);
+                }
+            }
+        }
+        Cnf {
+            number_of_variables,
+            clauses,
+            occurrences,
+        }
+    }
+
+    pub fn read_from_file(file_path: &str) -> Result<Cnf, InstanceError> {
+        Cnf::parse(&fs::read_to_string(file_path)?)
+    }
+
+    // Parses DIMACS CNF: comment lines start with c, the problem line is "p cnf <variables> <clauses>" and every clause is
+    // a list of literals ended by 0, which may span lines. A line starting with % ends the formula, as in SATLIB files.
+    pub fn parse(text: &str) -> Result<Cnf, InstanceError> {
+        let invalid_format = |reason: String| Err(InstanceError::InvalidFormat(reason));
+        let mut header = None;
+        let mut clauses = Vec::new();
+        let mut clause = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('c') {
+                continue;
+            }
+            if line.starts_with('%') {
+                break;
+            }
+            if line.starts_with('p') {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let numbers: Vec<Option<usize>> = fields
+                    .iter()
+                    .skip(2)
+                    .map(|field| field.parse().ok())
+                    .collect();
+                match (fields.get(1), numbers.as_slice()) {
+                    (Some(&"cnf"), &[Some(variables), Some(clauses)]) => {
+                        header = Some((variables, clauses))
+                    }
+                    _ => return invalid_format(format!("invalid problem line {:?}", line)),
+                }
+                continue;
+            }
+            let number_of_variables = match header {
+                Some((variables, _)) => variables as i64,
+                None => {
+                    return invalid_format(String::from("clauses come before the problem line"))
+                }
+            };
+            for token in line.split_whitespace() {
+                let literal: i64 = token.parse().map_err(|_| {
+                    InstanceError::InvalidFormat(format!("{:?} is not a literal", token))
+                })?;
+                if literal == 0 {
+                    clauses.push(clause);
+                    clause = Vec::new();
+                } else if literal.abs() > number_of_variables {
+                    return invalid_format(format!(
+                        "literal {} is out of range, there are {} variables",
+                        literal, number_of_variables
+                    ));
+                } else {
+                    clause.push(literal as i32);
+                }
+            }
+        }
+        if !clause.is_empty() {
+            clauses.push(clause);
+        }
+
+        let (number_of_variables, number_of_clauses) = match header {
+            Some(header) => header,
+            None => return invalid_format(String::from("the problem line is missing")),
+        };
+        if clauses.len() != number_of_clauses {
+            return invalid_format(format!(
+                "the problem line announces {} clauses but there are {}",
+                number_of_clauses,
+                clauses.len()
+            ));
+        }
+        Ok(Cnf::new(number_of_variables, clauses))
+    }
+
+    pub fn number_of_variables(&self) -> usize {
+        self.number_of_variables
+    }
+
+    pub fn clauses(&self) -> &Vec<Vec<i32>> {
+        &self.clauses
+    }
+
+    pub fn is_true(literal: i32, assignment: &[bool]) -> bool {
+        assignment[literal.abs() as usize - 1] == (literal > 0)
+    }
+
+    pub fn satisfied_clauses(&self, assignment: &[bool]) -> usize {
+        self.clauses
+            .iter()
+            .filter(|clause| {
+                clause
+                    .iter()
+                    .any(|&literal| Cnf::is_true(literal, assignment))
+            })
+            .count()
+    }
+
+    pub fn is_satisfying(&self, individual: &str) -> bool {
+        self.satisfied_clauses(&bits(individual, self.number_of_variables)) == self.clauses.len()
+    }
+
+    // The assignment in the DIMACS solution format of the SAT competitions: a status line, "s SATISFIABLE" or
+    // "s UNKNOWN" when clauses are left unsatisfied, and the value of every variable on v lines, ended by 0.
+    pub fn solution(&self, individual: &str) -> String {
+        let assignment = bits(individual, self.number_of_variables);
+        let satisfied = self.satisfied_clauses(&assignment);
+        let mut solution = format!(
+            "c {} of {} clauses satisfied\n",
+            satisfied,
+            self.clauses.len()
+        );
+        solution.push_str(if satisfied == self.clauses.len() {
+            "s SATISFIABLE\n"
+        } else {
+            "s UNKNOWN\n"
+        });
+        let literals: Vec<String> = assignment
+            .iter()
+            .enumerate()
+            .map(|(variable, &value)| {
+                let variable = variable as i64 + 1;
+                (if value { variable } else { -variable }).to_string()
+            })
+            .chain(std::iter::once(String::from("0")))
+            .collect();
+        for line in literals.chunks(10) {
+            solution.push_str(&format!("v {}\n", line.join(" ")));
+        }
+        solution
+    }
+}
+
+// Number of satisfied clauses, to be maximized.
+#[derive(Clone, Debug)]
+pub struct MaxSatFitness {
+    cnf: Arc<Cnf>,
+}
+
+impl MaxSatFitness {
+    pub fn new(cnf: Arc<Cnf>) -> MaxSatFitness {
+        MaxSatFitness { cnf }
+    }
+}
+
+impl FitnessFunction for MaxSatFitness {
+    type T = String;
+
+    fn calculate_fitness(&mut self, individual: &String) -> f64 {
+        self.cnf
+            .satisfied_clauses(&bits(individual, self.cnf.number_of_variables())) as f64
+    }
+}
+
+// Assignment that keeps the number of true literals of every clause, so flipping a variable only looks at the clauses
+// it occurs in instead of the whole formula.
+#[derive(Clone, Debug)]
+pub struct SatState {
+    cnf: Arc<Cnf>,
+    assignment: Vec<bool>,
+    true_literals: Vec<usize>,
+    // Unsatisfied clauses, without the empty ones that no flip can satisfy.
+    unsatisfied: Vec<usize>,
+    // Position of every unsatisfied clause in unsatisfied.
+    unsatisfied_positions: Vec<Option<usize>>,
+    empty_clauses: usize,
+}
+
+impl SatState {
+    pub fn new(cnf: Arc<Cnf>, individual: &str) -> SatState {
+        let assignment = bits(individual, cnf.number_of_variables());
+        let true_literals: Vec<usize> = cnf
+            .clauses
+            .iter()
+            .map(|clause| {
+                clause
+                    .iter()
+                    .filter(|&&literal| Cnf::is_true(literal, &assignment))
+                    .count()
+            })
+            .collect();
+        let mut state = SatState {
+            assignment,
+            unsatisfied: Vec::new(),
+            unsatisfied_positions: vec![None; true_literals.len()],
+            empty_clauses: cnf
+                .clauses
+                .iter()
+                .filter(|clause| clause.is_empty())
+                .count(),
+            true_literals,
+            cnf,
+        };
+        for clause in 0..state.true_literals.len() {
+            if state.true_literals[clause] == 0 && !state.cnf.clauses[clause].is_empty() {
+                state.mark_unsatisfied(clause);
+            }
+        }
+        state
+    }
+
+    fn mark_unsatisfied(&mut self, clause: usize) {
+        self.unsatisfied_positions[clause] = Some(self.unsatisfied.len());
+        self.unsatisfied.push(clause);
+    }
+
+    fn mark_satisfied(&mut self, clause: usize) {
+        let position = self.unsatisfied_positions[clause].take().unwrap();
+        self.unsatisfied.swap_remove(position);
+        if let Some(&moved_clause) = self.unsatisfied.get(position) {
+            self.unsatisfied_positions[moved_clause] = Some(position);
+        }
+    }
+
+    // Number of true literals of the variable in the clause, before and after flipping it.
+    fn literal_change(&self, clause: usize, variable: usize) -> (usize, usize) {
+        let value = self.assignment[variable];
+        let (mut before, mut after) = (0, 0);
+        for &literal in self.cnf.clauses[clause].iter() {
+            if literal.abs() as usize - 1 == variable {
+                let positive = literal > 0;
+                before += (positive == value) as usize;
+                after += (positive != value) as usize;
+            }
+        }
+        (before, after)
+    }
+
+    // Flips the variable, counted from 0.
+    pub fn flip(&mut self, variable: usize) {
+        for index in 0..self.cnf.occurrences[variable].len() {
+            let clause = self.cnf.occurrences[variable][index];
+            let (before, after) = self.literal_change(clause, variable);
+            let was_satisfied = self.true_literals[clause] > 0;
+            self.true_literals[clause] = self.true_literals[clause] + after - before;
+            let is_satisfied = self.true_literals[clause] > 0;
+            if was_satisfied && !is_satisfied {
+                self.mark_unsatisfied(clause);
+            } else if !was_satisfied && is_satisfied {
+                self.mark_satisfied(clause);
+            }
+        }
+        self.assignment[variable] = !self.assignment[variable];
+    }
+
+    // Number of satisfied clauses that flipping the variable would make unsatisfied.
+    pub fn break_count(&self, variable: usize) -> usize {
+        self.cnf.occurrences[variable]
+            .iter()
+            .filter(|&&clause| {
+                let (before, after) = self.literal_change(clause, variable);
+                self.true_literals[clause] > 0 && self.true_literals[clause] + after == before
+            })
+            .count()
+    }
+
+    pub fn unsatisfied_clauses(&self) -> &Vec<usize> {
+        &self.unsatisfied
+    }
+
+    pub fn satisfied_clauses(&self) -> usize {
+        self.true_literals.len() - self.unsatisfied.len() - self.empty_clauses
+    }
+
+    pub fn assignment(&self) -> &Vec<bool> {
+        &self.assignment
+    }
+
+    pub fn individual(&self) -> String {
+        bit_string(&self.assignment)
+    }
+}
+
+// WalkSAT local search as mutation, run on every individual with the search rate: up to max_flips times an unsatisfied
+// clause is picked at random and one of its variables is flipped. A variable that breaks no clause is always taken,
+// otherwise with the noise probability a random variable of the clause and else the one that breaks the fewest clauses.
+// The best assignment seen is kept.
+pub struct WalkSat {
+    search_rate: f64,
+    cnf: Arc<Cnf>,
+    max_flips: usize,
+    noise: f64,
+    seed: StdRng,
+}
+
+impl WalkSat {
+    pub fn new(
+        search_rate: f64,
+        cnf: Arc<Cnf>,
+        max_flips: usize,
+        noise: f64,
+        seed: [u8; 32],
+    ) -> WalkSat {
+        WalkSat {
+            search_rate,
+            cnf,
+            max_flips,
+            noise,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+
+    pub fn search(&mut self, individual: &str) -> String {
+        let mut state = SatState::new(self.cnf.clone(), individual);
+        let mut best = (state.satisfied_clauses(), state.individual());
+        for _ in 0..self.max_flips {
+            if state.unsatisfied_clauses().is_empty() {
+                break;
+            }
+            let unsatisfied = state.unsatisfied_clauses();
+            let clause = &self.cnf.clauses[unsatisfied[self.seed.gen_range(0, unsatisfied.len())]];
+            let variables: Vec<usize> = clause
+                .iter()
+                .map(|literal| literal.abs() as usize - 1)
+                .collect();
+            let break_counts: Vec<usize> = variables
+                .iter()
+                .map(|&variable| state.break_count(variable))
+                .collect();
+            let least_breaking = (0..variables.len())
+                .min_by_key(|&index| break_counts[index])
+                .unwrap();
+            let variable =
+                if break_counts[least_breaking] > 0 && self.seed.gen::<f64>() < self.noise {
+                    variables[self.seed.gen_range(0, variables.len())]
+                } else {
+                    variables[least_breaking]
+                };
+            state.flip(variable);
+            if state.satisfied_clauses() > best.0 {
+                best = (state.satisfied_clauses(), state.individual());
+            }
+        }
+        best.1
+    }
+}
+
+impl Mutate for WalkSat {
+    type T = String;
+
+    fn mutate(
+        &mut self,
+        population: &Population<String>,
+        mut fitness_function: Box<dyn FitnessFunction<T = String>>,
+    ) -> Vec<Individual<String>> {
+        let mut new_population = Vec::new();
+        for individual in population.list_of_individuals().iter() {
+            if self.seed.gen::<f64>() > self.search_rate {
+                new_population.push(individual.clone());
+                continue;
+            }
+            let searched_individual = self.search(individual.retrieve_individual());
+            let new_fitness = fitness_function.calculate_fitness(&searched_individual);
+            new_population.push(Individual::new(searched_individual, new_fitness));
+        }
+        new_population
+    }
+}
+
+#[cfg(test)]
+mod sat_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::genome_mutation::Mutate;
+    use crate::problems::binary::random_bit_string;
+    use crate::problems::sat::{Cnf, MaxSatFitness, SatState, WalkSat};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::sync::Arc;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    const CNF: &str = "c example\n\
+                       p cnf 3 4\n\
+                       1 -2 0\n\
+                       2 3 0\n\
+                       -1 -3\n\
+                       0\n\
+                       -1 2 3 0\n\
+                       %\n\
+                       0\n";
+
+    #[test]
+    fn dimacs_test() {
+        let cnf = Cnf::parse(CNF).unwrap();
+        assert_eq!(cnf.number_of_variables(), 3);
+        assert_eq!(cnf.clauses()[2], vec![-1, -3]);
+        assert!(cnf.is_satisfying("110"));
+        assert!(!cnf.is_satisfying("101"));
+        assert_eq!(
+            MaxSatFitness::new(Arc::new(cnf.clone())).calculate_fitness(&String::from("101")),
+            3.0
+        );
+        assert_eq!(
+            cnf.solution("110"),
+            "c 4 of 4 clauses satisfied\ns SATISFIABLE\nv 1 2 -3 0\n"
+        );
+        assert!(cnf.solution("101").contains("s UNKNOWN"));
+
+        assert!(Cnf::parse("p cnf 2 1\n1 3 0\n").is_err());
+        assert!(Cnf::parse("p cnf 2 2\n1 2 0\n").is_err());
+        assert!(Cnf::parse("1 2 0\n").is_err());
+    }
+
+    #[test]
+    fn incremental_flip_test() {
+        let mut rng: StdRng = SeedableRng::from_seed(*DEFAULT_SEED);
+        let clauses = (0..40)
+            .map(|_| {
+                (0..3)
+                    .map(|_| {
+                        let variable = rng.gen_range(1, 11);
+                        if rng.gen::<bool>() {
+                            variable
+                        } else {
+                            -variable
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let cnf = Arc::new(Cnf::new(10, clauses));
+        let mut state = SatState::new(cnf.clone(), &random_bit_string(10, &mut rng));
+        for _ in 0..200 {
+            let variable = rng.gen_range(0, 10);
+            let satisfied = state.satisfied_clauses();
+            let break_count = state.break_count(variable);
+            state.flip(variable);
+            assert_eq!(
+                state.satisfied_clauses(),
+                cnf.satisfied_clauses(state.assignment())
+            );
+            assert!(state.satisfied_clauses() + break_count >= satisfied);
+            for &clause in state.unsatisfied_clauses().iter() {
+                assert!(!cnf.clauses()[clause]
+                    .iter()
+                    .any(|&literal| Cnf::is_true(literal, state.assignment())));
+            }
+        }
+    }
+
+    #[test]
+    fn walk_sat_test() {
+        // Satisfied by every assignment where x1 to x5 are equal and x6 differs from x5.
+        let cnf = Arc::new(
+            Cnf::parse(
+                "p cnf 6 10\n-1 2 0\n-2 3 0\n-3 4 0\n-4 5 0\n-5 1 0\n\
+                 1 -2 0\n2 -3 0\n3 -4 0\n4 -5 0\n-5 -6 0\n",
+            )
+            .unwrap(),
+        );
+        let population = Population::new(
+            vec![
+                Individual::new(String::from("101010"), 0.0),
+                Individual::new(String::from("110011"), 0.0),
+            ],
+            ProblemType::Max,
+        );
+        let mut walk_sat = WalkSat::new(1.0, cnf.clone(), 100, 0.5, *DEFAULT_SEED);
+        let new_population =
+            walk_sat.mutate(&population, Box::new(MaxSatFitness::new(cnf.clone())));
+        for individual in new_population.iter() {
+            assert!(cnf.is_satisfying(individual.retrieve_individual()));
+            assert_eq!(*individual.fitness(), 10.0);
+        }
+
+        let mut walk_sat = WalkSat::new(0.0, cnf.clone(), 100, 0.5, *DEFAULT_SEED);
+        let new_population = walk_sat.mutate(&population, Box::new(MaxSatFitness::new(cnf)));
+        for (individual, new_individual) in population
+            .list_of_individuals()
+            .iter()
+            .zip(new_population.iter())
+        {
+            assert_eq!(
+                individual.retrieve_individual(),
+                new_individual.retrieve_individual()
+            );
+        }
+    }
+
+    #[test]
+    fn empty_clause_test() {
+        // The empty clause is never satisfied, the others are by x1 and not x2.
+        let cnf = Arc::new(
+            Cnf::parse(
+                "p cnf 2 3\n\
+                 1 2 0\n\
+                 0\n\
+                 -2 0\n",
+            )
+            .unwrap(),
+        );
+        assert_eq!(cnf.clauses()[1], Vec::<i32>::new());
+        let state = SatState::new(cnf.clone(), "01");
+        assert_eq!(state.satisfied_clauses(), 1);
+        assert_eq!(*state.unsatisfied_clauses(), vec![2]);
+
+        let searched_individual =
+            WalkSat::new(1.0, cnf.clone(), 100, 0.5, *DEFAULT_SEED).search("01");
+        assert_eq!(cnf.satisfied_clauses(&[true, false]), 2);
+        assert_eq!(searched_individual, "10");
+        assert!(cnf.solution(&searched_individual).contains("s UNKNOWN"));
+    }
+}
@@ -0,0 +1,712 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::problems::instance_error::{next_number, InstanceError};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fs;
+use std::io;
+use std::sync::Arc;
+
+// Shop scheduling problem whose Vec<u32> genomes decode into a schedule.
+pub trait ShopProblem {
+    fn number_of_jobs(&self) -> usize;
+    fn number_of_machines(&self) -> usize;
+    fn due_dates(&self) -> Option<&Vec<f64>>;
+    fn schedule(&self, genome: &[u32]) -> Schedule;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledOperation {
+    pub job: usize,
+    pub operation: usize,
+    pub machine: usize,
+    pub start: f64,
+    pub end: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schedule {
+    number_of_jobs: usize,
+    number_of_machines: usize,
+    operations: Vec<ScheduledOperation>,
+}
+
+impl Schedule {
+    pub fn operations(&self) -> &Vec<ScheduledOperation> {
+        &self.operations
+    }
+
+    pub fn makespan(&self) -> f64 {
+        self.operations
+            .iter()
+            .map(|operation| operation.end)
+            .fold(0.0, f64::max)
+    }
+
+    // Time at which the last operation of every job ends.
+    pub fn completion_times(&self) -> Vec<f64> {
+        let mut completion_times: Vec<f64> = vec![0.0; self.number_of_jobs];
+        for operation in self.operations.iter() {
+            completion_times[operation.job] = completion_times[operation.job].max(operation.end);
+        }
+        completion_times
+    }
+
+    pub fn total_tardiness(&self, due_dates: &[f64]) -> f64 {
+        if due_dates.len() != self.number_of_jobs {
+            panic!(
+                "Got {} due dates for {} jobs.",
+                due_dates.len(),
+                self.number_of_jobs
+            );
+        }
+        self.completion_times()
+            .iter()
+            .zip(due_dates.iter())
+            .map(|(completion_time, due_date)| (completion_time - due_date).max(0.0))
+            .sum()
+    }
+
+    // One line per operation with jobs, operations and machines counted from 0, sorted by machine and start.
+    pub fn to_csv(&self) -> String {
+        let mut operations: Vec<&ScheduledOperation> = self.operations.iter().collect();
+        operations.sort_by(|first, second| {
+            (first.machine, first.start)
+                .partial_cmp(&(second.machine, second.start))
+                .unwrap()
+        });
+        let mut csv = String::from("job,operation,machine,start,end\n");
+        for operation in operations {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                operation.job,
+                operation.operation,
+                operation.machine,
+                operation.start,
+                operation.end
+            ));
+        }
+        csv
+    }
+
+    // Gantt chart with a row per machine and a colour per job.
+    pub fn to_svg(&self) -> String {
+        let (left, top, chart_width, row_height) = (80.0, 20.0, 800.0, 30.0);
+        let scale = chart_width / self.makespan().max(1e-12);
+        let chart_height = row_height * self.number_of_machines as f64;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" \
+             font-size=\"12\">\n",
+            left + chart_width + 20.0,
+            top + chart_height + 40.0
+        );
+        for machine in 0..self.number_of_machines {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">Machine {}</text>\n",
+                left - 8.0,
+                top + row_height * (machine as f64 + 0.5) + 4.0,
+                machine
+            ));
+        }
+        for operation in self.operations.iter() {
+            let x = left + operation.start * scale;
+            let y = top + row_height * operation.machine as f64 + 3.0;
+            let width = (operation.end - operation.start) * scale;
+            svg.push_str(&format!(
+                "<rect x=\"{:.2}\" y=\"{}\" width=\"{:.2}\" height=\"{}\" fill=\"hsl({}, 65%, 60%)\" stroke=\"black\">\
+                 <title>Job {} operation {}: {} to {}</title></rect>\n",
+                x,
+                y,
+                width,
+                row_height - 6.0,
+                operation.job * 360 / self.number_of_jobs.max(1),
+                operation.job,
+                operation.operation,
+                operation.start,
+                operation.end
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{:.2}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n",
+                x + width / 2.0,
+                y + row_height / 2.0 + 1.0,
+                operation.job
+            ));
+        }
+        let axis = top + chart_height;
+        svg.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\"/>\n",
+            left,
+            axis,
+            left + chart_width,
+            axis
+        ));
+        for tick in 0..=10 {
+            let x = left + chart_width * tick as f64 / 10.0;
+            svg.push_str(&format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\"/>\
+                 <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n",
+                x,
+                axis,
+                x,
+                axis + 5.0,
+                x,
+                axis + 20.0,
+                (self.makespan() * tick as f64 / 10.0).round()
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn write_csv(&self, file_path: &str) -> io::Result<()> {
+        fs::write(file_path, self.to_csv())
+    }
+
+    pub fn write_svg(&self, file_path: &str) -> io::Result<()> {
+        fs::write(file_path, self.to_svg())
+    }
+}
+
+// Tokens of the lines that hold only numbers, which skips the names, descriptions and labels around the instances in
+// OR-Library and Taillard files.
+fn numeric_tokens(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter(|line| {
+            line.split_whitespace()
+                .all(|token| token.parse::<f64>().is_ok())
+        })
+        .flat_map(str::split_whitespace)
+        .collect()
+}
+
+// Job shop: every job visits the machines in its own order. Genomes use the operation based encoding, a sequence in
+// which every job appears once per operation and its k-th appearance stands for its k-th operation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobShop {
+    number_of_machines: usize,
+    // For every job its operations as machine and processing time.
+    routes: Vec<Vec<(usize, f64)>>,
+    due_dates: Option<Vec<f64>>,
+    bounds: Option<(f64, f64)>,
+}
+
+impl JobShop {
+    pub fn new(number_of_machines: usize, routes: Vec<Vec<(usize, f64)>>) -> JobShop {
+        for (job, route) in routes.iter().enumerate() {
+            if let Some(&(machine, _)) = route
+                .iter()
+                .find(|&&(machine, _)| machine >= number_of_machines)
+            {
+                panic!(
+                    "Job {} uses machine {}, there are only {} machines.",
+                    job, machine, number_of_machines
+                );
+            }
+        }
+        JobShop {
+            number_of_machines,
+            routes,
+            due_dates: None,
+            bounds: None,
+        }
+    }
+
+    pub fn read_from_file(file_path: &str) -> Result<Vec<JobShop>, InstanceError> {
+        JobShop::parse(&fs::read_to_string(file_path)?)
+    }
+
+    pub fn read_taillard_file(file_path: &str) -> Result<Vec<JobShop>, InstanceError> {
+        JobShop::parse_taillard(&fs::read_to_string(file_path)?)
+    }
+
+    // Parses the OR-Library jobshop format: the number of jobs and machines, then for every job its operations as
+    // machine, counted from 0, and processing time. Lines with text between the instances are skipped.
+    pub fn parse(text: &str) -> Result<Vec<JobShop>, InstanceError> {
+        let tokens = numeric_tokens(text);
+        let mut tokens = tokens.into_iter().peekable();
+        let mut instances = Vec::new();
+        while tokens.peek().is_some() {
+            let number_of_jobs: usize = next_number(&mut tokens, "number of jobs")?;
+            let number_of_machines: usize = next_number(&mut tokens, "number of machines")?;
+            let mut routes = Vec::with_capacity(number_of_jobs);
+            for _ in 0..number_of_jobs {
+                let mut route = Vec::with_capacity(number_of_machines);
+                for _ in 0..number_of_machines {
+                    let machine: usize = next_number(&mut tokens, "machine")?;
+                    let processing_time: f64 = next_number(&mut tokens, "processing time")?;
+                    route.push((machine, processing_time));
+                }
+                routes.push(route);
+            }
+            instances.push(JobShop::checked(number_of_machines, routes)?);
+        }
+        Ok(instances)
+    }
+
+    // Parses Taillard job shop files: a line with the number of jobs and machines, the time and machine seeds and the
+    // upper and lower bound, the processing times with a row per job and the machines, counted from 1, in the same layout.
+    pub fn parse_taillard(text: &str) -> Result<Vec<JobShop>, InstanceError> {
+        let tokens = numeric_tokens(text);
+        let mut tokens = tokens.into_iter().peekable();
+        let mut instances = Vec::new();
+        while tokens.peek().is_some() {
+            let number_of_jobs: usize = next_number(&mut tokens, "number of jobs")?;
+            let number_of_machines: usize = next_number(&mut tokens, "number of machines")?;
+            let _time_seed: u64 = next_number(&mut tokens, "time seed")?;
+            let _machine_seed: u64 = next_number(&mut tokens, "machine seed")?;
+            let upper_bound: f64 = next_number(&mut tokens, "upper bound")?;
+            let lower_bound: f64 = next_number(&mut tokens, "lower bound")?;
+            let number_of_operations = number_of_jobs * number_of_machines;
+            let processing_times = (0..number_of_operations)
+                .map(|_| next_number::<f64>(&mut tokens, "processing time"))
+                .collect::<Result<Vec<f64>, InstanceError>>()?;
+            let mut routes = vec![Vec::with_capacity(number_of_machines); number_of_jobs];
+            for (operation, &processing_time) in processing_times.iter().enumerate() {
+                let machine: usize = next_number(&mut tokens, "machine")?;
+                if machine < 1 {
+                    return Err(InstanceError::InvalidFormat(String::from(
+                        "machines are counted from 1",
+                    )));
+                }
+                routes[operation / number_of_machines].push((machine - 1, processing_time));
+            }
+            let mut job_shop = JobShop::checked(number_of_machines, routes)?;
+            job_shop.bounds = Some((lower_bound, upper_bound));
+            instances.push(job_shop);
+        }
+        Ok(instances)
+    }
+
+    fn checked(
+        number_of_machines: usize,
+        routes: Vec<Vec<(usize, f64)>>,
+    ) -> Result<JobShop, InstanceError> {
+        for (job, route) in routes.iter().enumerate() {
+            for &(machine, _) in route.iter() {
+                if machine >= number_of_machines {
+                    return Err(InstanceError::InvalidFormat(format!(
+                        "job {} uses machine {} of {}",
+                        job, machine, number_of_machines
+                    )));
+                }
+            }
+        }
+        Ok(JobShop::new(number_of_machines, routes))
+    }
+
+    pub fn routes(&self) -> &Vec<Vec<(usize, f64)>> {
+        &self.routes
+    }
+
+    pub fn number_of_operations(&self) -> usize {
+        self.routes.iter().map(Vec::len).sum()
+    }
+
+    pub fn set_due_dates(&mut self, due_dates: Vec<f64>) {
+        if due_dates.len() != self.routes.len() {
+            panic!(
+                "Got {} due dates for {} jobs.",
+                due_dates.len(),
+                self.routes.len()
+            );
+        }
+        self.due_dates = Some(due_dates);
+    }
+
+    // Lower and upper bound of the makespan, known for Taillard instances.
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        self.bounds
+    }
+
+    pub fn random_operation_sequence(&self, rng: &mut impl Rng) -> Vec<u32> {
+        let mut sequence: Vec<u32> = self
+            .routes
+            .iter()
+            .enumerate()
+            .flat_map(|(job, route)| std::iter::repeat(job as u32).take(route.len()))
+            .collect();
+        sequence.shuffle(rng);
+        sequence
+    }
+}
+
+impl ShopProblem for JobShop {
+    fn number_of_jobs(&self) -> usize {
+        self.routes.len()
+    }
+
+    fn number_of_machines(&self) -> usize {
+        self.number_of_machines
+    }
+
+    fn due_dates(&self) -> Option<&Vec<f64>> {
+        self.due_dates.as_ref()
+    }
+
+    // Operations are taken in the order of the sequence and each starts in the earliest idle time of its machine after
+    // the previous operation of its job ends, also in gaps left between operations already scheduled.
+    fn schedule(&self, sequence: &[u32]) -> Schedule {
+        let mut next_operations = vec![0; self.routes.len()];
+        let mut job_ready = vec![0.0; self.routes.len()];
+        // Busy intervals of every machine, sorted by start.
+        let mut machine_intervals: Vec<Vec<(f64, f64)>> = vec![Vec::new(); self.number_of_machines];
+        let mut operations = Vec::with_capacity(sequence.len());
+        for &job in sequence.iter() {
+            let job = job as usize;
+            if job >= self.routes.len() || next_operations[job] == self.routes[job].len() {
+                panic!(
+                    "Job {} appears more often in the sequence than it has operations.",
+                    job
+                );
+            }
+            let operation = next_operations[job];
+            let (machine, processing_time) = self.routes[job][operation];
+            let intervals = &mut machine_intervals[machine];
+            let mut start = job_ready[job];
+            let mut position = intervals.len();
+            for (index, &(busy_start, busy_end)) in intervals.iter().enumerate() {
+                if start + processing_time <= busy_start {
+                    position = index;
+                    break;
+                }
+                start = start.max(busy_end);
+            }
+            let end = start + processing_time;
+            intervals.insert(position, (start, end));
+            operations.push(ScheduledOperation {
+                job,
+                operation,
+                machine,
+                start,
+                end,
+            });
+            next_operations[job] += 1;
+            job_ready[job] = end;
+        }
+        if sequence.len() != self.number_of_operations() {
+            panic!(
+                "The sequence has {} operations but the instance has {}.",
+                sequence.len(),
+                self.number_of_operations()
+            );
+        }
+        Schedule {
+            number_of_jobs: self.routes.len(),
+            number_of_machines: self.number_of_machines,
+            operations,
+        }
+    }
+}
+
+// Permutation flow shop: every job visits the machines in the same order and every machine processes the jobs in the
+// same order. Genomes are permutations of the jobs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowShop {
+    // One row per job, with the processing time on every machine.
+    processing_times: Vec<Vec<f64>>,
+    due_dates: Option<Vec<f64>>,
+    bounds: Option<(f64, f64)>,
+}
+
+impl FlowShop {
+    pub fn new(processing_times: Vec<Vec<f64>>) -> FlowShop {
+        if processing_times
+            .iter()
+            .any(|row| row.len() != processing_times[0].len())
+        {
+            panic!("Every job needs a processing time on each machine.");
+        }
+        FlowShop {
+            processing_times,
+            due_dates: None,
+            bounds: None,
+        }
+    }
+
+    pub fn read_from_file(file_path: &str) -> Result<Vec<FlowShop>, InstanceError> {
+        FlowShop::parse(&fs::read_to_string(file_path)?)
+    }
+
+    pub fn read_taillard_file(file_path: &str) -> Result<Vec<FlowShop>, InstanceError> {
+        FlowShop::parse_taillard(&fs::read_to_string(file_path)?)
+    }
+
+    // Parses the OR-Library flowshop format: the number of jobs and machines, then for every job the machine, counted
+    // from 0, and processing time of each of its operations. Lines with text between the instances are skipped.
+    pub fn parse(text: &str) -> Result<Vec<FlowShop>, InstanceError> {
+        let tokens = numeric_tokens(text);
+        let mut tokens = tokens.into_iter().peekable();
+        let mut instances = Vec::new();
+        while tokens.peek().is_some() {
+            let number_of_jobs: usize = next_number(&mut tokens, "number of jobs")?;
+            let number_of_machines: usize = next_number(&mut tokens, "number of machines")?;
+            let mut processing_times = vec![vec![0.0; number_of_machines]; number_of_jobs];
+            for (job, row) in processing_times.iter_mut().enumerate() {
+                for _ in 0..number_of_machines {
+                    let machine: usize = next_number(&mut tokens, "machine")?;
+                    let processing_time: f64 = next_number(&mut tokens, "processing time")?;
+                    if machine >= number_of_machines {
+                        return Err(InstanceError::InvalidFormat(format!(
+                            "job {} uses machine {} of {}",
+                            job, machine, number_of_machines
+                        )));
+                    }
+                    row[machine] = processing_time;
+                }
+            }
+            instances.push(FlowShop::new(processing_times));
+        }
+        Ok(instances)
+    }
+
+    // Parses Taillard flow shop files: a line with the number of jobs and machines, the seed and the upper and lower
+    // bound, then the processing times with a row per machine.
+    pub fn parse_taillard(text: &str) -> Result<Vec<FlowShop>, InstanceError> {
+        let tokens = numeric_tokens(text);
+        let mut tokens = tokens.into_iter().peekable();
+        let mut instances = Vec::new();
+        while tokens.peek().is_some() {
+            let number_of_jobs: usize = next_number(&mut tokens, "number of jobs")?;
+            let number_of_machines: usize = next_number(&mut tokens, "number of machines")?;
+            let _seed: u64 = next_number(&mut tokens, "seed")?;
+            let upper_bound: f64 = next_number(&mut tokens, "upper bound")?;
+            let lower_bound: f64 = next_number(&mut tokens, "lower bound")?;
+            let mut processing_times = vec![vec![0.0; number_of_machines]; number_of_jobs];
+            for machine in 0..number_of_machines {
+                for row in processing_times.iter_mut() {
+                    row[machine] = next_number(&mut tokens, "processing time")?;
+                }
+            }
+            let mut flow_shop = FlowShop::new(processing_times);
+            flow_shop.bounds = Some((lower_bound, upper_bound));
+            instances.push(flow_shop);
+        }
+        Ok(instances)
+    }
+
+    pub fn processing_times(&self) -> &Vec<Vec<f64>> {
+        &self.processing_times
+    }
+
+    pub fn set_due_dates(&mut self, due_dates: Vec<f64>) {
+        if due_dates.len() != self.processing_times.len() {
+            panic!(
+                "Got {} due dates for {} jobs.",
+                due_dates.len(),
+                self.processing_times.len()
+            );
+        }
+        self.due_dates = Some(due_dates);
+    }
+
+    // Lower and upper bound of the makespan, known for Taillard instances.
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        self.bounds
+    }
+
+    pub fn random_permutation(&self, rng: &mut impl Rng) -> Vec<u32> {
+        let mut permutation: Vec<u32> = (0..self.processing_times.len() as u32).collect();
+        permutation.shuffle(rng);
+        permutation
+    }
+}
+
+impl ShopProblem for FlowShop {
+    fn number_of_jobs(&self) -> usize {
+        self.processing_times.len()
+    }
+
+    fn number_of_machines(&self) -> usize {
+        self.processing_times.first().map_or(0, Vec::len)
+    }
+
+    fn due_dates(&self) -> Option<&Vec<f64>> {
+        self.due_dates.as_ref()
+    }
+
+    // Every operation starts as soon as both its machine and the previous operation of its job are done.
+    fn schedule(&self, permutation: &[u32]) -> Schedule {
+        let mut seen = vec![false; self.number_of_jobs()];
+        for &job in permutation.iter() {
+            if job as usize >= seen.len() || seen[job as usize] {
+                panic!("{:?} is not a permutation of the jobs.", permutation);
+            }
+            seen[job as usize] = true;
+        }
+        if permutation.len() != seen.len() {
+            panic!("{:?} is not a permutation of the jobs.", permutation);
+        }
+
+        let mut machine_ready = vec![0.0; self.number_of_machines()];
+        let mut operations = Vec::with_capacity(permutation.len() * machine_ready.len());
+        for &job in permutation.iter() {
+            let job = job as usize;
+            let mut job_ready: f64 = 0.0;
+            for (machine, &processing_time) in self.processing_times[job].iter().enumerate() {
+                let start = job_ready.max(machine_ready[machine]);
+                let end = start + processing_time;
+                operations.push(ScheduledOperation {
+                    job,
+                    operation: machine,
+                    machine,
+                    start,
+                    end,
+                });
+                machine_ready[machine] = end;
+                job_ready = end;
+            }
+        }
+        Schedule {
+            number_of_jobs: self.number_of_jobs(),
+            number_of_machines: self.number_of_machines(),
+            operations,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScheduleObjective {
+    Makespan,
+    TotalTardiness,
+}
+
+// Makespan or total tardiness of the decoded schedule, to be minimized.
+#[derive(Clone, Debug)]
+pub struct ScheduleFitness<S> {
+    problem: Arc<S>,
+    objective: ScheduleObjective,
+}
+
+impl<S: ShopProblem> ScheduleFitness<S> {
+    pub fn new(problem: Arc<S>, objective: ScheduleObjective) -> ScheduleFitness<S> {
+        if let (ScheduleObjective::TotalTardiness, None) = (objective, problem.due_dates()) {
+            panic!("Total tardiness needs due dates, please set them first.");
+        }
+        ScheduleFitness { problem, objective }
+    }
+}
+
+impl<S: ShopProblem> FitnessFunction for ScheduleFitness<S> {
+    type T = Vec<u32>;
+
+    fn calculate_fitness(&mut self, genome: &Vec<u32>) -> f64 {
+        let schedule = self.problem.schedule(genome);
+        match self.objective {
+            ScheduleObjective::Makespan => schedule.makespan(),
+            ScheduleObjective::TotalTardiness => {
+                schedule.total_tardiness(self.problem.due_dates().unwrap())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod scheduling_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::problems::scheduling::{
+        FlowShop, JobShop, ScheduleFitness, ScheduleObjective, ShopProblem,
+    };
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::sync::Arc;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    const JOBSHOP: &str = " +++++++++++++++++++++++++++++\n \
+                           instance tiny\n \
+                           +++++++++++++++++++++++++++++\n \
+                           a 2x2 example, made up\n \
+                           2 2\n \
+                           0 3 1 2\n \
+                           1 4 0 1\n";
+
+    const TAILLARD_JOBSHOP: &str =
+        "Nb of jobs, Nb of Machines, Time seed, Machine seed, Upper bound, Lower bound\n\
+                                    2 2 840612802 398197754 7 6\n\
+                                    Times\n\
+                                    3 2\n\
+                                    4 1\n\
+                                    Machines\n\
+                                    1 2\n\
+                                    2 1\n";
+
+    const TAILLARD_FLOWSHOP: &str =
+        "number of jobs, number of machines, initial seed, upper bound and lower bound :\n\
+         3 2 873654221 9 8\n\
+         processing times :\n\
+         2 4 1\n\
+         3 1 4\n";
+
+    #[test]
+    fn job_shop_test() {
+        let job_shop = JobShop::parse(JOBSHOP).unwrap().remove(0);
+        let taillard_job_shop = JobShop::parse_taillard(TAILLARD_JOBSHOP).unwrap().remove(0);
+        assert_eq!(job_shop.routes(), taillard_job_shop.routes());
+        assert_eq!(taillard_job_shop.bounds(), Some((6.0, 7.0)));
+
+        // Job 0 runs on machine 0 for 3 and machine 1 for 2, job 1 on machine 1 for 4 and machine 0 for 1.
+        let schedule = job_shop.schedule(&[0, 1, 1, 0]);
+        assert_eq!(schedule.makespan(), 6.0);
+        assert_eq!(schedule.completion_times(), vec![6.0, 5.0]);
+        assert_eq!(schedule.total_tardiness(&[5.0, 4.0]), 2.0);
+        assert_eq!(job_shop.schedule(&[0, 0, 1, 1]).makespan(), 10.0);
+
+        // Job 1 comes last in the sequence but fits in the idle time of machine 0 before job 0 gets there.
+        let job_shop = JobShop::new(2, vec![vec![(1, 5.0), (0, 2.0)], vec![(0, 3.0)]]);
+        let schedule = job_shop.schedule(&[0, 0, 1]);
+        assert_eq!(schedule.operations()[2].start, 0.0);
+        assert_eq!(schedule.makespan(), 7.0);
+
+        let mut rng: StdRng = SeedableRng::from_seed(*DEFAULT_SEED);
+        let mut sequence = job_shop.random_operation_sequence(&mut rng);
+        sequence.sort();
+        assert_eq!(sequence, vec![0, 0, 1]);
+        assert!(JobShop::parse("2 2\n0 3 2 2\n1 4 0 1\n").is_err());
+    }
+
+    #[test]
+    fn flow_shop_test() {
+        let flow_shop = FlowShop::parse_taillard(TAILLARD_FLOWSHOP)
+            .unwrap()
+            .remove(0);
+        assert_eq!(flow_shop.bounds(), Some((8.0, 9.0)));
+        assert_eq!(
+            FlowShop::parse("3 2\n0 2 1 3\n0 4 1 1\n1 4 0 1\n").unwrap()[0].processing_times(),
+            flow_shop.processing_times()
+        );
+
+        let mut fitness_function =
+            ScheduleFitness::new(Arc::new(flow_shop.clone()), ScheduleObjective::Makespan);
+        assert_eq!(fitness_function.calculate_fitness(&vec![0, 1, 2]), 11.0);
+        assert_eq!(fitness_function.calculate_fitness(&vec![2, 0, 1]), 9.0);
+
+        let mut flow_shop = flow_shop;
+        flow_shop.set_due_dates(vec![5.0, 5.0, 5.0]);
+        let mut fitness_function =
+            ScheduleFitness::new(Arc::new(flow_shop), ScheduleObjective::TotalTardiness);
+        // Jobs complete at 8, 9 and 5.
+        assert_eq!(fitness_function.calculate_fitness(&vec![2, 0, 1]), 7.0);
+        let mut boxed_fitness_function: Box<dyn FitnessFunction<T = Vec<u32>>> =
+            Box::new(fitness_function.clone());
+        assert_eq!(
+            boxed_fitness_function.calculate_fitness(&vec![2, 0, 1]),
+            7.0
+        );
+    }
+
+    #[test]
+    fn gantt_test() {
+        let job_shop = JobShop::parse(JOBSHOP).unwrap().remove(0);
+        let schedule = job_shop.schedule(&[0, 1, 1, 0]);
+        assert_eq!(
+            schedule.to_csv(),
+            "job,operation,machine,start,end\n0,0,0,0,3\n1,1,0,4,5\n1,0,1,0,4\n0,1,1,4,6\n"
+        );
+        let svg = schedule.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), 4);
+        assert!(svg.contains("Machine 1"));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::problems::binary::{bit_string, bits};
+use crate::problems::instance_error::{next_number, InstanceError};
+use crate::problems::repair::Repair;
+use std::fs;
+use std::sync::Arc;
+
+// Weighted set cover: pick columns at the least total cost so every row is covered by at least one of them. Genomes
+// are strings of one bit per column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetCover {
+    costs: Vec<f64>,
+    // For every row the columns that cover it.
+    rows: Vec<Vec<usize>>,
+    // For every column the rows it covers.
+    columns: Vec<Vec<usize>>,
+}
+
+impl SetCover {
+    pub fn new(costs: Vec<f64>, rows: Vec<Vec<usize>>) -> SetCover {
+        let mut columns = vec![Vec::new(); costs.len()];
+        for (row, covering_columns) in rows.iter().enumerate() {
+            if covering_columns.is_empty() {
+                panic!("Row {} is not covered by any column.", row);
+            }
+            for &column in covering_columns.iter() {
+                if column >= costs.len() {
+                    panic!(
+                        "Column {} is out of range, there are only {} columns.",
+                        column,
+                        costs.len()
+                    );
+                }
+                columns[column].push(row);
+            }
+        }
+        SetCover {
+            costs,
+            rows,
+            columns,
+        }
+    }
+
+    pub fn read_from_file(file_path: &str) -> Result<SetCover, InstanceError> {
+        SetCover::parse(&fs::read_to_string(file_path)?)
+    }
+
+    // Parses the OR-Library scp format: the number of rows and columns, the cost of every column and then for every row
+    // the number of columns that cover it followed by those columns, counted from 1.
+    pub fn parse(text: &str) -> Result<SetCover, InstanceError> {
+        let mut tokens = text.split_whitespace();
+        let number_of_rows: usize = next_number(&mut tokens, "number of rows")?;
+        let number_of_columns: usize = next_number(&mut tokens, "number of columns")?;
+        let costs = (0..number_of_columns)
+            .map(|_| next_number::<f64>(&mut tokens, "cost"))
+            .collect::<Result<Vec<f64>, InstanceError>>()?;
+        let mut rows = Vec::with_capacity(number_of_rows);
+        for row in 0..number_of_rows {
+            let number_of_covering_columns: usize =
+                next_number(&mut tokens, "number of covering columns")?;
+            if number_of_covering_columns == 0 {
+                return Err(InstanceError::InvalidFormat(format!(
+                    "row {} is not covered by any column",
+                    row + 1
+                )));
+            }
+            let mut covering_columns = Vec::with_capacity(number_of_covering_columns);
+            for _ in 0..number_of_covering_columns {
+                let column: usize = next_number(&mut tokens, "column")?;
+                if column < 1 || column > number_of_columns {
+                    return Err(InstanceError::InvalidFormat(format!(
+                        "column {} of row {} does not exist",
+                        column,
+                        row + 1
+                    )));
+                }
+                covering_columns.push(column - 1);
+            }
+            rows.push(covering_columns);
+        }
+        Ok(SetCover::new(costs, rows))
+    }
+
+    pub fn number_of_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn number_of_columns(&self) -> usize {
+        self.costs.len()
+    }
+
+    pub fn costs(&self) -> &Vec<f64> {
+        &self.costs
+    }
+
+    pub fn rows(&self) -> &Vec<Vec<usize>> {
+        &self.rows
+    }
+
+    pub fn cost(&self, selection: &[bool]) -> f64 {
+        self.costs
+            .iter()
+            .zip(selection.iter())
+            .filter(|(_, &selected)| selected)
+            .map(|(cost, _)| cost)
+            .sum()
+    }
+
+    // Number of selected columns covering every row.
+    pub fn coverage(&self, selection: &[bool]) -> Vec<usize> {
+        self.rows
+            .iter()
+            .map(|columns| columns.iter().filter(|&&column| selection[column]).count())
+            .collect()
+    }
+
+    pub fn uncovered_rows(&self, selection: &[bool]) -> usize {
+        self.coverage(selection)
+            .iter()
+            .filter(|&&coverage| coverage == 0)
+            .count()
+    }
+
+    pub fn is_feasible(&self, individual: &str) -> bool {
+        self.uncovered_rows(&bits(individual, self.number_of_columns())) == 0
+    }
+}
+
+// Cost plus penalty for every uncovered row, to be minimized.
+#[derive(Clone, Debug)]
+pub struct SetCoverFitness {
+    set_cover: Arc<SetCover>,
+    penalty: f64,
+}
+
+impl SetCoverFitness {
+    pub fn new(set_cover: Arc<SetCover>, penalty: f64) -> SetCoverFitness {
+        SetCoverFitness { set_cover, penalty }
+    }
+}
+
+impl FitnessFunction for SetCoverFitness {
+    type T = String;
+
+    fn calculate_fitness(&mut self, individual: &String) -> f64 {
+        let selection = bits(individual, self.set_cover.number_of_columns());
+        self.set_cover.cost(&selection)
+            + self.penalty * self.set_cover.uncovered_rows(&selection) as f64
+    }
+}
+
+// Greedy repair of Beasley and Chu: every uncovered row gets the column with the least cost per newly covered row, then
+// selected columns are dropped from the most expensive down as long as all of their rows stay covered.
+#[derive(Clone, Debug)]
+pub struct SetCoverRepair {
+    set_cover: Arc<SetCover>,
+}
+
+impl SetCoverRepair {
+    pub fn new(set_cover: Arc<SetCover>) -> SetCoverRepair {
+        SetCoverRepair { set_cover }
+    }
+}
+
+impl Repair for SetCoverRepair {
+    type T = String;
+
+    fn repair(&self, individual: &String) -> String {
+        let set_cover = &self.set_cover;
+        let mut selection = bits(individual, set_cover.number_of_columns());
+        let mut coverage = set_cover.coverage(&selection);
+
+        for row in 0..set_cover.number_of_rows() {
+            if coverage[row] > 0 {
+                continue;
+            }
+            let cost_per_row = |column: usize| {
+                let newly_covered = set_cover.columns[column]
+                    .iter()
+                    .filter(|&&row| coverage[row] == 0)
+                    .count();
+                set_cover.costs[column] / newly_covered as f64
+            };
+            let best_column = set_cover.rows[row]
+                .iter()
+                .cloned()
+                .min_by(|&first, &second| {
+                    cost_per_row(first)
+                        .partial_cmp(&cost_per_row(second))
+                        .unwrap()
+                })
+                .unwrap();
+            selection[best_column] = true;
+            for &covered_row in set_cover.columns[best_column].iter() {
+                coverage[covered_row] += 1;
+            }
+        }
+
+        let mut selected: Vec<usize> = (0..selection.len())
+            .filter(|&column| selection[column])
+            .collect();
+        selected.sort_by(|&first, &second| {
+            set_cover.costs[second]
+                .partial_cmp(&set_cover.costs[first])
+                .unwrap()
+        });
+        for column in selected {
+            if set_cover.columns[column]
+                .iter()
+                .all(|&row| coverage[row] > 1)
+            {
+                selection[column] = false;
+                for &row in set_cover.columns[column].iter() {
+                    coverage[row] -= 1;
+                }
+            }
+        }
+        bit_string(&selection)
+    }
+}
+
+#[cfg(test)]
+mod set_cover_test {
+    use crate::genome::fitness_function::FitnessFunction;
+    use crate::problems::repair::Repair;
+    use crate::problems::set_cover::{SetCover, SetCoverFitness, SetCoverRepair};
+    use std::sync::Arc;
+
+    // Columns 1 to 3 cover one row each and column 4 covers all of them at a higher cost.
+    const SCP: &str = "3 4\n\
+                       1 1 1 4\n\
+                       2 1 4\n\
+                       2 2 4\n\
+                       2 3 4\n";
+
+    #[test]
+    fn set_cover_test() {
+        let set_cover = Arc::new(SetCover::parse(SCP).unwrap());
+        assert_eq!(set_cover.number_of_rows(), 3);
+        assert_eq!(set_cover.rows()[2], vec![2, 3]);
+        assert!(set_cover.is_feasible("0001"));
+        assert!(!set_cover.is_feasible("1100"));
+
+        let mut fitness_function = SetCoverFitness::new(set_cover, 10.0);
+        assert_eq!(
+            fitness_function.calculate_fitness(&String::from("1110")),
+            3.0
+        );
+        assert_eq!(
+            fitness_function.calculate_fitness(&String::from("1100")),
+            12.0
+        );
+
+        assert!(SetCover::parse("2 2\n1 1\n1 1\n0\n").is_err());
+        assert!(SetCover::parse("1 2\n1 1\n1 3\n").is_err());
+    }
+
+    #[test]
+    fn set_cover_repair_test() {
+        let set_cover = Arc::new(SetCover::parse(SCP).unwrap());
+        let repair = SetCoverRepair::new(set_cover.clone());
+        // Every uncovered row costs 1 with its own column and 4 / 3 or more with column 4.
+        assert_eq!(repair.repair(&String::from("1000")), "1110");
+        assert_eq!(repair.repair(&String::from("0000")), "1110");
+        assert_eq!(repair.repair(&String::from("0001")), "0001");
+        // Redundant columns are dropped, the most expensive first.
+        assert_eq!(repair.repair(&String::from("1111")), "1110");
+        assert_eq!(repair.repair(&String::from("0101")), "0001");
+        assert!(set_cover.is_feasible(&repair.repair(&String::from("0110"))));
+    }
+}
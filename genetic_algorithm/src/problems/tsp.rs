@@ -0,0 +1,549 @@
+use crate::genome::fitness_function::FitnessFunction;
+use crate::genome::population::{Individual, Population};
+use crate::mutation::genome_mutation::Mutate;
+use crate::problems::instance_error::{next_number, InstanceError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::fs;
+use std::sync::Arc;
+
+// TSPLIB computes GEO distances with this truncated pi, the published optima depend on it.
+#[allow(clippy::approx_constant)]
+const TSPLIB_PI: f64 = 3.141_592;
+
+// Travelling salesman instance with a precomputed distance matrix. Tours are permutations of the cities 0 up to the
+// dimension, as Vec<u32> genomes, and return to the first city at the end.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TspInstance {
+    name: String,
+    distances: Vec<Vec<f64>>,
+    coordinates: Option<Vec<(f64, f64)>>,
+    symmetric: bool,
+}
+
+impl TspInstance {
+    pub fn new(name: &str, distances: Vec<Vec<f64>>) -> TspInstance {
+        if distances.iter().any(|row| row.len() != distances.len()) {
+            panic!("The distance matrix has to be square.");
+        }
+        let symmetric = (0..distances.len())
+            .all(|from| (0..from).all(|to| distances[from][to] == distances[to][from]));
+        TspInstance {
+            name: String::from(name),
+            distances,
+            coordinates: None,
+            symmetric,
+        }
+    }
+
+    pub fn read_from_file(file_path: &str) -> Result<TspInstance, InstanceError> {
+        TspInstance::parse(&fs::read_to_string(file_path)?)
+    }
+
+    // Parses a TSPLIB .tsp file with EUC_2D, CEIL_2D, GEO or ATT coordinates or an EXPLICIT matrix in any of the TSPLIB
+    // edge weight formats. Distances are rounded the way TSPLIB defines them, so tour lengths match the published optima.
+    pub fn parse(text: &str) -> Result<TspInstance, InstanceError> {
+        let invalid_format = |reason: String| Err(InstanceError::InvalidFormat(reason));
+        let mut name = String::new();
+        let mut dimension = None;
+        let mut edge_weight_type = None;
+        let mut edge_weight_format = None;
+        let mut section = "";
+        let mut coordinates = Vec::new();
+        let mut weights = Vec::new();
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if line == "EOF" {
+                break;
+            }
+            let keyword = line
+                .split(|c: char| c == ':' || c.is_whitespace())
+                .next()
+                .unwrap();
+            if keyword.ends_with("_SECTION") {
+                section = keyword;
+                continue;
+            }
+            if let Some(colon) = line.find(':') {
+                let value = line[colon + 1..].trim();
+                match keyword {
+                    "NAME" => name = String::from(value),
+                    "TYPE" if value != "TSP" && value != "ATSP" => {
+                        return invalid_format(format!("{} instances are not supported", value))
+                    }
+                    "DIMENSION" => {
+                        dimension = Some(value.parse::<usize>().map_err(|_| {
+                            InstanceError::InvalidFormat(format!("invalid dimension {:?}", value))
+                        })?)
+                    }
+                    "EDGE_WEIGHT_TYPE" => edge_weight_type = Some(String::from(value)),
+                    "EDGE_WEIGHT_FORMAT" => edge_weight_format = Some(String::from(value)),
+                    _ => {}
+                }
+                section = "";
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            match section {
+                "NODE_COORD_SECTION" => {
+                    next_number::<usize>(&mut tokens, "city number")?;
+                    let x = next_number(&mut tokens, "coordinate")?;
+                    let y = next_number(&mut tokens, "coordinate")?;
+                    coordinates.push((x, y));
+                }
+                "EDGE_WEIGHT_SECTION" => {
+                    let mut tokens = tokens.peekable();
+                    while tokens.peek().is_some() {
+                        weights.push(next_number::<f64>(&mut tokens, "edge weight")?);
+                    }
+                }
+                "" => return invalid_format(format!("unexpected line {:?}", line)),
+                _ => {}
+            }
+        }
+
+        let dimension = match dimension {
+            Some(dimension) => dimension,
+            None => return invalid_format(String::from("the DIMENSION is missing")),
+        };
+        let edge_weight_type = edge_weight_type.unwrap_or_default();
+        let mut instance = match edge_weight_type.as_str() {
+            "EUC_2D" | "CEIL_2D" | "GEO" | "ATT" => {
+                if coordinates.len() != dimension {
+                    return invalid_format(format!(
+                        "got {} coordinates for {} cities",
+                        coordinates.len(),
+                        dimension
+                    ));
+                }
+                let distances = coordinates
+                    .iter()
+                    .map(|&from| {
+                        coordinates
+                            .iter()
+                            .map(|&to| coordinate_distance(&edge_weight_type, from, to))
+                            .collect()
+                    })
+                    .collect();
+                let mut instance = TspInstance::new(&name, distances);
+                instance.coordinates = Some(coordinates);
+                instance
+            }
+            "EXPLICIT" => {
+                let format = edge_weight_format.unwrap_or_default();
+                TspInstance::new(&name, explicit_distances(&format, dimension, &weights)?)
+            }
+            _ => {
+                return invalid_format(format!(
+                    "edge weight type {:?} is not supported",
+                    edge_weight_type
+                ))
+            }
+        };
+        for city in 0..dimension {
+            instance.distances[city][city] = 0.0;
+        }
+        Ok(instance)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Number of cities.
+    pub fn dimension(&self) -> usize {
+        self.distances.len()
+    }
+
+    pub fn distances(&self) -> &Vec<Vec<f64>> {
+        &self.distances
+    }
+
+    pub fn distance(&self, from: u32, to: u32) -> f64 {
+        self.distances[from as usize][to as usize]
+    }
+
+    // Only for instances read from coordinates.
+    pub fn coordinates(&self) -> Option<&Vec<(f64, f64)>> {
+        self.coordinates.as_ref()
+    }
+
+    pub fn is_symmetric(&self) -> bool {
+        self.symmetric
+    }
+
+    pub fn random_tour(&self, rng: &mut impl Rng) -> Vec<u32> {
+        let mut tour: Vec<u32> = (0..self.dimension() as u32).collect();
+        tour.shuffle(rng);
+        tour
+    }
+
+    pub fn tour_length(&self, tour: &[u32]) -> f64 {
+        if tour.len() != self.dimension() {
+            panic!(
+                "The tour visits {} cities but the instance has {}.",
+                tour.len(),
+                self.dimension()
+            );
+        }
+        (0..tour.len())
+            .map(|index| self.distance(tour[index], tour[(index + 1) % tour.len()]))
+            .sum()
+    }
+
+    // Reverses parts of the tour as long as that makes it shorter, until it is 2-opt optimal. Returns the new length.
+    // Only for symmetric instances, as reversing changes the length of every edge of an asymmetric tour.
+    pub fn two_opt(&self, tour: &mut [u32]) -> f64 {
+        if !self.is_symmetric() {
+            panic!("2-opt needs a symmetric instance.");
+        }
+        let length = tour.len();
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for first in 0..length.saturating_sub(2) {
+                for second in first + 2..length {
+                    // The edges from first and from second can not be next to each other.
+                    if first == 0 && second == length - 1 {
+                        continue;
+                    }
+                    let (a, b) = (tour[first], tour[first + 1]);
+                    let (c, d) = (tour[second], tour[(second + 1) % length]);
+                    let change = self.distance(a, c) + self.distance(b, d)
+                        - self.distance(a, b)
+                        - self.distance(c, d);
+                    if change < -1e-10 {
+                        tour[first + 1..=second].reverse();
+                        improved = true;
+                    }
+                }
+            }
+        }
+        self.tour_length(tour)
+    }
+}
+
+fn nint(value: f64) -> f64 {
+    (value + 0.5).floor()
+}
+
+fn coordinate_distance(edge_weight_type: &str, from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (dx, dy) = (from.0 - to.0, from.1 - to.1);
+    match edge_weight_type {
+        "EUC_2D" => nint((dx * dx + dy * dy).sqrt()),
+        "CEIL_2D" => (dx * dx + dy * dy).sqrt().ceil(),
+        "ATT" => {
+            let distance = ((dx * dx + dy * dy) / 10.0).sqrt();
+            let rounded = nint(distance);
+            if rounded < distance {
+                rounded + 1.0
+            } else {
+                rounded
+            }
+        }
+        _ => {
+            // GEO: coordinates are latitude and longitude as degrees.minutes.
+            let radians = |coordinate: f64| {
+                let degrees = coordinate.trunc();
+                TSPLIB_PI * (degrees + 5.0 * (coordinate - degrees) / 3.0) / 180.0
+            };
+            let (latitude_from, longitude_from) = (radians(from.0), radians(from.1));
+            let (latitude_to, longitude_to) = (radians(to.0), radians(to.1));
+            let q1 = (longitude_from - longitude_to).cos();
+            let q2 = (latitude_from - latitude_to).cos();
+            let q3 = (latitude_from + latitude_to).cos();
+            (6378.388 * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+        }
+    }
+}
+
+// Fills a matrix from the weights of an EDGE_WEIGHT_SECTION. The column formats list the same triangle as the row
+// format of the other triangle, so they only differ for the full matrix.
+fn explicit_distances(
+    format: &str,
+    dimension: usize,
+    weights: &[f64],
+) -> Result<Vec<Vec<f64>>, InstanceError> {
+    let cells: Vec<(usize, usize)> = match format {
+        "FULL_MATRIX" => (0..dimension)
+            .flat_map(|row| (0..dimension).map(move |column| (row, column)))
+            .collect(),
+        "UPPER_ROW" | "LOWER_COL" => (0..dimension)
+            .flat_map(|row| (row + 1..dimension).map(move |column| (row, column)))
+            .collect(),
+        "LOWER_ROW" | "UPPER_COL" => (0..dimension)
+            .flat_map(|row| (0..row).map(move |column| (row, column)))
+            .collect(),
+        "UPPER_DIAG_ROW" | "LOWER_DIAG_COL" => (0..dimension)
+            .flat_map(|row| (row..dimension).map(move |column| (row, column)))
+            .collect(),
+        "LOWER_DIAG_ROW" | "UPPER_DIAG_COL" => (0..dimension)
+            .flat_map(|row| (0..=row).map(move |column| (row, column)))
+            .collect(),
+        _ => {
+            return Err(InstanceError::InvalidFormat(format!(
+                "edge weight format {:?} is not supported",
+                format
+            )))
+        }
+    };
+    if weights.len() != cells.len() {
+        return Err(InstanceError::InvalidFormat(format!(
+            "{} needs {} edge weights for {} cities but got {}",
+            format,
+            cells.len(),
+            dimension,
+            weights.len()
+        )));
+    }
+
+    let mut distances = vec![vec![0.0; dimension]; dimension];
+    for (&(row, column), &weight) in cells.iter().zip(weights.iter()) {
+        distances[row][column] = weight;
+        if format != "FULL_MATRIX" {
+            distances[column][row] = weight;
+        }
+    }
+    Ok(distances)
+}
+
+pub fn read_tour(file_path: &str) -> Result<Vec<u32>, InstanceError> {
+    parse_tour(&fs::read_to_string(file_path)?)
+}
+
+// Parses a TSPLIB .opt.tour file into a tour of cities counted from 0.
+pub fn parse_tour(text: &str) -> Result<Vec<u32>, InstanceError> {
+    let mut dimension = None;
+    let mut in_tour_section = false;
+    let mut tour = Vec::new();
+    'lines: for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if line == "EOF" {
+            break;
+        }
+        if !in_tour_section {
+            if line.starts_with("TOUR_SECTION") {
+                in_tour_section = true;
+            } else if line.starts_with("DIMENSION") {
+                let value = line.split_once(':').map_or("", |(_, value)| value).trim();
+                dimension = Some(value.parse::<usize>().map_err(|_| {
+                    InstanceError::InvalidFormat(format!("invalid dimension {:?}", value))
+                })?);
+            }
+            continue;
+        }
+        let mut tokens = line.split_whitespace().peekable();
+        while tokens.peek().is_some() {
+            let city: i64 = next_number(&mut tokens, "city number")?;
+            if city == -1 {
+                break 'lines;
+            }
+            if city < 1 {
+                return Err(InstanceError::InvalidFormat(format!(
+                    "city {} does not exist, cities are counted from 1",
+                    city
+                )));
+            }
+            tour.push(city as u32 - 1);
+        }
+    }
+
+    let mut visited = vec![false; tour.len()];
+    for &city in tour.iter() {
+        if city as usize >= tour.len() || visited[city as usize] {
+            return Err(InstanceError::InvalidFormat(String::from(
+                "the tour has to visit every city exactly once",
+            )));
+        }
+        visited[city as usize] = true;
+    }
+    if let Some(dimension) = dimension {
+        if dimension != tour.len() {
+            return Err(InstanceError::InvalidFormat(format!(
+                "the tour visits {} cities instead of {}",
+                tour.len(),
+                dimension
+            )));
+        }
+    }
+    Ok(tour)
+}
+
+// Length of the tour, to be minimized.
+#[derive(Clone, Debug)]
+pub struct TourLength {
+    instance: Arc<TspInstance>,
+}
+
+impl TourLength {
+    pub fn new(instance: Arc<TspInstance>) -> TourLength {
+        TourLength { instance }
+    }
+
+    pub fn instance(&self) -> &TspInstance {
+        &self.instance
+    }
+}
+
+impl FitnessFunction for TourLength {
+    type T = Vec<u32>;
+
+    fn calculate_fitness(&mut self, tour: &Vec<u32>) -> f64 {
+        self.instance.tour_length(tour)
+    }
+}
+
+// Memetic step: every tour is, with the search rate, replaced by its 2-opt local optimum.
+pub struct TwoOptSearch {
+    search_rate: f64,
+    instance: Arc<TspInstance>,
+    seed: StdRng,
+}
+
+impl TwoOptSearch {
+    pub fn new(search_rate: f64, instance: Arc<TspInstance>, seed: [u8; 32]) -> TwoOptSearch {
+        TwoOptSearch {
+            search_rate,
+            instance,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl Mutate for TwoOptSearch {
+    type T = Vec<u32>;
+
+    fn mutate(
+        &mut self,
+        population: &Population<Vec<u32>>,
+        mut fitness_function: Box<dyn FitnessFunction<T = Vec<u32>>>,
+    ) -> Vec<Individual<Vec<u32>>> {
+        let mut new_population = Vec::new();
+        for individual in population.list_of_individuals().iter() {
+            if self.seed.gen::<f64>() > self.search_rate {
+                new_population.push(individual.clone());
+                continue;
+            }
+            let mut tour = individual.retrieve_individual().clone();
+            self.instance.two_opt(&mut tour);
+            let new_fitness = fitness_function.calculate_fitness(&tour);
+            new_population.push(Individual::new(tour, new_fitness));
+        }
+        new_population
+    }
+}
+
+#[cfg(test)]
+mod tsp_test {
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::mutation::genome_mutation::Mutate;
+    use crate::problems::instance_error::InstanceError;
+    use crate::problems::tsp::{parse_tour, TourLength, TspInstance, TwoOptSearch};
+    use std::sync::Arc;
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    const SQUARE: &str = "NAME : square\n\
+                          TYPE : TSP\n\
+                          DIMENSION : 5\n\
+                          EDGE_WEIGHT_TYPE : EUC_2D\n\
+                          NODE_COORD_SECTION\n\
+                          1 0 0\n\
+                          2 0 10\n\
+                          3 10 10\n\
+                          4 10 0\n\
+                          5 5 0.4\n\
+                          EOF\n";
+
+    #[test]
+    fn tsplib_coordinates_test() {
+        let instance = TspInstance::parse(SQUARE).unwrap();
+        assert_eq!(instance.name(), "square");
+        assert_eq!(instance.dimension(), 5);
+        // 5.016 and 10.824 are rounded to the nearest integer.
+        assert_eq!(instance.distance(0, 4), 5.0);
+        assert_eq!(instance.distance(4, 2), 11.0);
+        assert_eq!(instance.tour_length(&[0, 1, 2, 3, 4]), 40.0);
+
+        let att = TspInstance::parse(
+            "DIMENSION: 3\nEDGE_WEIGHT_TYPE: ATT\nNODE_COORD_SECTION\n1 0 0\n2 10 0\n3 30 40\n",
+        )
+        .unwrap();
+        // sqrt(10) is rounded up and sqrt(250) to the nearest integer.
+        assert_eq!(att.distance(0, 1), 4.0);
+        assert_eq!(att.distance(0, 2), 16.0);
+
+        // About 111 km per degree of latitude, with 0.30 being 30 minutes.
+        let geo = TspInstance::parse(
+            "DIMENSION: 2\nEDGE_WEIGHT_TYPE: GEO\nNODE_COORD_SECTION\n1 10.00 20.00\n2 10.30 20.00\n",
+        )
+        .unwrap();
+        assert_eq!(geo.distance(0, 1), 56.0);
+        assert_eq!(geo.distance(1, 1), 0.0);
+    }
+
+    #[test]
+    fn tsplib_explicit_test() {
+        let upper_row = TspInstance::parse(
+            "NAME: small\nTYPE: TSP\nDIMENSION: 4\nEDGE_WEIGHT_TYPE: EXPLICIT\n\
+             EDGE_WEIGHT_FORMAT: UPPER_ROW\nEDGE_WEIGHT_SECTION\n1 2 3\n4 5\n6\nEOF\n",
+        )
+        .unwrap();
+        let lower_diag_row = TspInstance::parse(
+            "DIMENSION: 4\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: LOWER_DIAG_ROW\n\
+             EDGE_WEIGHT_SECTION\n0 1 0 2 4 0 3 5 6 0\n",
+        )
+        .unwrap();
+        assert_eq!(upper_row.distances(), lower_diag_row.distances());
+        assert_eq!(upper_row.distance(2, 1), 4.0);
+        assert!(upper_row.is_symmetric());
+
+        let full_matrix = TspInstance::parse(
+            "TYPE: ATSP\nDIMENSION: 2\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: FULL_MATRIX\n\
+             EDGE_WEIGHT_SECTION\n0 3\n7 0\n",
+        )
+        .unwrap();
+        assert_eq!(full_matrix.distance(1, 0), 7.0);
+        assert!(!full_matrix.is_symmetric());
+
+        match TspInstance::parse(
+            "DIMENSION: 4\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: UPPER_ROW\nEDGE_WEIGHT_SECTION\n1 2\n",
+        ) {
+            Err(InstanceError::InvalidFormat(_)) => {}
+            _ => panic!("UPPER_ROW needs 6 weights for 4 cities"),
+        }
+    }
+
+    #[test]
+    fn tour_test() {
+        let tour = parse_tour(
+            "NAME : square.opt.tour\nTYPE : TOUR\nDIMENSION : 5\nTOUR_SECTION\n1\n2\n3\n4 5\n-1\nEOF\n",
+        )
+        .unwrap();
+        assert_eq!(tour, vec![0, 1, 2, 3, 4]);
+        assert!(parse_tour("TOUR_SECTION\n1\n2\n2\n-1\n").is_err());
+        assert!(parse_tour("DIMENSION : 3\nTOUR_SECTION\n1\n2\n-1\n").is_err());
+    }
+
+    #[test]
+    fn two_opt_test() {
+        let instance = Arc::new(TspInstance::parse(SQUARE).unwrap());
+        // Crosses the square twice.
+        let mut tour = vec![0, 2, 1, 3, 4];
+        assert!(instance.tour_length(&tour) > 40.0);
+        assert_eq!(instance.two_opt(&mut tour), 40.0);
+
+        let population = Population::new(
+            vec![Individual::new(vec![0, 2, 4, 1, 3], 0.0)],
+            ProblemType::Min,
+        );
+        let mut two_opt_search = TwoOptSearch::new(1.0, instance.clone(), *DEFAULT_SEED);
+        let new_population =
+            two_opt_search.mutate(&population, Box::new(TourLength::new(instance)));
+        assert_eq!(*new_population[0].fitness(), 40.0);
+    }
+}
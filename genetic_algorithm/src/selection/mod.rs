@@ -1 +1,2 @@
-pub mod genome_selection;
\ No newline at end of file
+pub mod genome_selection;
+pub mod parsimony_selection;
@@ -0,0 +1,212 @@
+use crate::genome::population::{Individual, Population, ProblemType};
+use crate::selection::genome_selection::SelectIndividual;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+// Tournament where the best fitness wins and, between equally fit individuals, the smallest one wins.
+#[derive(Clone, Debug)]
+pub struct LexicographicTournamentSelection<T> {
+    k_value: u32,
+    size: fn(&T) -> usize,
+    seed: StdRng,
+}
+
+impl<T> LexicographicTournamentSelection<T> {
+    pub fn new(
+        k_value: u32,
+        size: fn(&T) -> usize,
+        seed: [u8; 32],
+    ) -> LexicographicTournamentSelection<T> {
+        if k_value == 0 {
+            panic!("A tournament needs at least 1 individual.");
+        }
+        LexicographicTournamentSelection {
+            k_value,
+            size,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl<T: Clone + Serialize + Deserialize> SelectIndividual<T>
+    for LexicographicTournamentSelection<T>
+{
+    fn select_individual(&mut self, population: &Population<T>) -> Individual<T> {
+        let individuals = population.list_of_individuals();
+        let mut chosen_individual = &individuals[self.seed.gen_range(0, individuals.len())];
+
+        for _ in 0..self.k_value - 1 {
+            let individual = &individuals[self.seed.gen_range(0, individuals.len())];
+            let is_better = match population.problem_type() {
+                ProblemType::Max => individual.fitness() > chosen_individual.fitness(),
+                ProblemType::Min => individual.fitness() < chosen_individual.fitness(),
+            };
+            let is_smaller_tie = individual.fitness() == chosen_individual.fitness()
+                && (self.size)(individual.retrieve_individual())
+                    < (self.size)(chosen_individual.retrieve_individual());
+            if is_better || is_smaller_tie {
+                chosen_individual = individual;
+            }
+        }
+        chosen_individual.clone()
+    }
+}
+
+// Double tournament (Luke and Panait): two individuals are picked by fitness tournaments of k_value individuals, then
+// the smaller of the two wins with smaller_individual_win_chance. A chance of 0.5 turns the size pressure off, 0.6 to
+// 0.8 is a usual range.
+#[derive(Clone, Debug)]
+pub struct DoubleTournamentSelection<T> {
+    k_value: u32,
+    smaller_individual_win_chance: f64,
+    size: fn(&T) -> usize,
+    seed: StdRng,
+}
+
+impl<T> DoubleTournamentSelection<T> {
+    pub fn new(
+        k_value: u32,
+        smaller_individual_win_chance: f64,
+        size: fn(&T) -> usize,
+        seed: [u8; 32],
+    ) -> DoubleTournamentSelection<T> {
+        if k_value == 0 {
+            panic!("A tournament needs at least 1 individual.");
+        }
+        DoubleTournamentSelection {
+            k_value,
+            smaller_individual_win_chance,
+            size,
+            seed: SeedableRng::from_seed(seed),
+        }
+    }
+}
+
+impl<T: Clone + Serialize + Deserialize> DoubleTournamentSelection<T> {
+    fn fitness_tournament<'a>(&mut self, population: &'a Population<T>) -> &'a Individual<T> {
+        let individuals = population.list_of_individuals();
+        let mut chosen_individual = &individuals[self.seed.gen_range(0, individuals.len())];
+
+        for _ in 0..self.k_value - 1 {
+            let individual = &individuals[self.seed.gen_range(0, individuals.len())];
+            let is_better = match population.problem_type() {
+                ProblemType::Max => individual.fitness() > chosen_individual.fitness(),
+                ProblemType::Min => individual.fitness() < chosen_individual.fitness(),
+            };
+            if is_better {
+                chosen_individual = individual;
+            }
+        }
+        chosen_individual
+    }
+}
+
+impl<T: Clone + Serialize + Deserialize> SelectIndividual<T> for DoubleTournamentSelection<T> {
+    fn select_individual(&mut self, population: &Population<T>) -> Individual<T> {
+        let first_individual = self.fitness_tournament(population);
+        let second_individual = self.fitness_tournament(population);
+
+        let first_size = (self.size)(first_individual.retrieve_individual());
+        let second_size = (self.size)(second_individual.retrieve_individual());
+        let (smaller_individual, larger_individual) = if first_size <= second_size {
+            (first_individual, second_individual)
+        } else {
+            (second_individual, first_individual)
+        };
+
+        if self.seed.gen::<f64>() < self.smaller_individual_win_chance {
+            smaller_individual.clone()
+        } else {
+            larger_individual.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod parsimony_selection_test {
+    use crate::genome::population::{Individual, Population, ProblemType};
+    use crate::selection::genome_selection::SelectIndividual;
+    use crate::selection::parsimony_selection::{
+        DoubleTournamentSelection, LexicographicTournamentSelection,
+    };
+
+    const DEFAULT_SEED: &[u8; 32] = &[
+        1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 3, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2,
+        3, 4,
+    ];
+
+    fn create_population(problem_type: ProblemType) -> Population<String> {
+        Population::new(
+            vec![
+                Individual::new(String::from("0000000"), 6.0),
+                Individual::new(String::from("000"), 6.0),
+                Individual::new(String::from("00000"), 5.0),
+            ],
+            problem_type,
+        )
+    }
+
+    #[test]
+    fn lexicographic_tournament_selection_test() {
+        let population = create_population(ProblemType::Max);
+        let mut selection = LexicographicTournamentSelection::new(
+            30,
+            |individual: &String| individual.len(),
+            *DEFAULT_SEED,
+        );
+        for _ in 0..10 {
+            let individual = selection.select_individual(&population);
+            assert_eq!(individual.retrieve_individual(), &String::from("000"));
+        }
+
+        let population = create_population(ProblemType::Min);
+        let individual = selection.select_individual(&population);
+        assert_eq!(individual.retrieve_individual(), &String::from("00000"));
+    }
+
+    #[test]
+    fn double_tournament_selection_test() {
+        let population = create_population(ProblemType::Max);
+
+        let mut selection = DoubleTournamentSelection::new(
+            1,
+            1.0,
+            |individual: &String| individual.len(),
+            *DEFAULT_SEED,
+        );
+        let mut smaller_wins = 0;
+        for _ in 0..100 {
+            if selection
+                .select_individual(&population)
+                .retrieve_individual()
+                .len()
+                == 3
+            {
+                smaller_wins += 1;
+            }
+        }
+        // The smallest individual wins every pair it is part of, about 5 in 9 pairs.
+        assert!(smaller_wins > 40);
+
+        // With a chance of 0 the larger individual of the pair always wins, so the smallest one only wins against itself.
+        let mut selection = DoubleTournamentSelection::new(
+            1,
+            0.0,
+            |individual: &String| individual.len(),
+            *DEFAULT_SEED,
+        );
+        let mut smaller_wins = 0;
+        for _ in 0..100 {
+            if selection
+                .select_individual(&population)
+                .retrieve_individual()
+                .len()
+                == 3
+            {
+                smaller_wins += 1;
+            }
+        }
+        assert!(smaller_wins < 25);
+    }
+}